@@ -0,0 +1,63 @@
+use halo2_base::gates::circuit::builder::RangeCircuitBuilder;
+use halo2_base::gates::{RangeChip, RangeInstructions};
+use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+use halo2_base::utils::ScalarField;
+use halo2_base::Context;
+use itertools::Itertools;
+use rand::{rngs::OsRng, RngCore};
+
+use criterion::{criterion_group, criterion_main};
+use criterion::{BenchmarkId, Criterion};
+
+use pprof::criterion::{Output, PProfProfiler};
+
+const K: usize = 18;
+const LOOKUP_BITS: usize = 8;
+const NUM_BITS: usize = 64;
+
+fn range_check_bench<F: ScalarField>(ctx: &mut Context<F>, range: &RangeChip<F>, inputs: &[F]) {
+    for &x in inputs {
+        let x = ctx.load_witness(x);
+        range.range_check(ctx, x, NUM_BITS);
+    }
+}
+
+/// Witness generation only, to isolate the cost of growing the advice column.
+fn witness_gen(inputs: &[Fr], reserve: Option<usize>) {
+    let mut builder = RangeCircuitBuilder::new(true).use_k(K).use_lookup_bits(LOOKUP_BITS);
+    let range = builder.range_chip();
+    let ctx = builder.main(0);
+    if let Some(additional) = reserve {
+        ctx.reserve(additional);
+    }
+    range_check_bench(ctx, &range, inputs);
+    builder.clear();
+}
+
+fn bench(c: &mut Criterion) {
+    let inputs = (0..(1 << K) / 32).map(|_| Fr::from(OsRng.next_u64())).collect_vec();
+    // the number of cells does not depend on the inputs, so it can be measured once up-front
+    let mut builder = RangeCircuitBuilder::new(true).use_k(K).use_lookup_bits(LOOKUP_BITS);
+    let range = builder.range_chip();
+    range_check_bench(builder.main(0), &range, &inputs);
+    let num_cells = builder.main(0).advice_len();
+    builder.clear();
+
+    let mut group = c.benchmark_group("witness-gen");
+    group.sample_size(10);
+    for reserve in [None, Some(num_cells)] {
+        group.bench_with_input(
+            BenchmarkId::new("range_check_reserve", reserve.is_some()),
+            &reserve,
+            |bencher, &reserve| bencher.iter(|| witness_gen(&inputs, reserve)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(10, Output::Flamegraph(None)));
+    targets = bench
+}
+criterion_main!(benches);
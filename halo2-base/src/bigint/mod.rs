@@ -0,0 +1,275 @@
+use std::cmp::max;
+
+use num_bigint::{BigInt as BigIntValue, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+use crate::{
+    gates::{GateInstructions, RangeChip, RangeInstructions},
+    utils::{bigint_to_fe, biguint_to_fe, bit_length, fe_to_bigint, fe_to_biguint, BigPrimeField},
+    AssignedValue, Context,
+    QuantumCell::{Constant, Existing},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Non-negative integer represented by `LIMBS` little-endian limbs of `BITS` bits each, i.e.,
+/// `sum_i limbs[i] * 2^(BITS * i)`.
+///
+/// Limbs may overflow `BITS` bits after additions and subtractions: `max_limb_bits` tracks an upper bound on the
+/// number of bits of every limb. Operations of [BigIntChip] panic if the bound grows too large for the native field,
+/// so callers should use [BigIntChip::mod_reduce] to bring the limbs back to `BITS` bits before that happens.
+#[derive(Clone, Copy, Debug)]
+pub struct BigInt<F: BigPrimeField, const LIMBS: usize, const BITS: usize> {
+    /// Little-endian limbs.
+    pub limbs: [AssignedValue<F>; LIMBS],
+    /// Upper bound on the number of bits of every limb.
+    pub max_limb_bits: usize,
+}
+
+impl<F: BigPrimeField, const LIMBS: usize, const BITS: usize> BigInt<F, LIMBS, BITS> {
+    /// Returns the integer value of `self`.
+    pub fn value(&self) -> BigUint {
+        self.limbs
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |acc, limb| (acc << BITS) + fe_to_biguint(limb.value()))
+    }
+
+    /// Upper bound on the number of bits of the integer value.
+    fn value_bits(&self) -> usize {
+        // `sum_i limbs[i] * 2^(BITS * i) < 2^max_limb_bits * 2^(BITS * (LIMBS - 1) + 1)`
+        self.max_limb_bits + BITS * (LIMBS - 1) + 1
+    }
+
+    /// Returns true if every limb is known to have at most `BITS` bits.
+    pub fn is_reduced(&self) -> bool {
+        self.max_limb_bits <= BITS
+    }
+}
+
+/// Chip for arithmetic modulo `modulus` on [BigInt]s with `LIMBS` limbs of `BITS` bits.
+///
+/// Additions and subtractions do not carry between limbs, while multiplications and reductions
+/// witness a quotient and remainder and constrain their relation with carries between limbs.
+#[derive(Clone, Debug)]
+pub struct BigIntChip<'a, F: BigPrimeField, const LIMBS: usize, const BITS: usize> {
+    range: &'a RangeChip<F>,
+    modulus: BigUint,
+    modulus_limbs: Vec<F>,
+}
+
+impl<'a, F: BigPrimeField, const LIMBS: usize, const BITS: usize> BigIntChip<'a, F, LIMBS, BITS> {
+    /// Creates a new [BigIntChip] for arithmetic modulo `modulus`.
+    ///
+    /// Panics if `modulus < 2` or `modulus` does not fit in `LIMBS` limbs of `BITS` bits.
+    pub fn new(range: &'a RangeChip<F>, modulus: BigUint) -> Self {
+        assert!(LIMBS > 0 && BITS > 0, "LIMBS and BITS must be positive");
+        assert!(modulus > BigUint::one(), "modulus must be at least 2");
+        assert!(modulus.bits() as usize <= LIMBS * BITS, "modulus does not fit in the limbs");
+        let modulus_limbs =
+            Self::decompose(&modulus).into_iter().map(|x| biguint_to_fe(&x)).collect();
+        Self { range, modulus, modulus_limbs }
+    }
+
+    /// Returns the modulus.
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Loads `value` as a [BigInt], range checking each limb to `BITS` bits.
+    ///
+    /// Panics if `value` does not fit in `LIMBS` limbs of `BITS` bits.
+    pub fn load(&self, ctx: &mut Context<F>, value: &BigUint) -> BigInt<F, LIMBS, BITS> {
+        assert!(value.bits() as usize <= LIMBS * BITS, "value does not fit in the limbs");
+        let limbs = self.load_limbs(ctx, Self::decompose(value));
+        BigInt { limbs: limbs.try_into().unwrap(), max_limb_bits: BITS }
+    }
+
+    /// Constrains and returns `a + b`, without carrying between limbs.
+    pub fn add(
+        &self,
+        ctx: &mut Context<F>,
+        a: &BigInt<F, LIMBS, BITS>,
+        b: &BigInt<F, LIMBS, BITS>,
+    ) -> BigInt<F, LIMBS, BITS> {
+        let gate = self.range.gate();
+        let limbs = std::array::from_fn(|i| gate.add(ctx, a.limbs[i], b.limbs[i]));
+        self.overflowing(limbs, max(a.max_limb_bits, b.max_limb_bits) + 1)
+    }
+
+    /// Constrains and returns an integer congruent to `a - b` modulo `modulus`, without carrying between limbs.
+    ///
+    /// To keep every limb non-negative, a constant multiple of `modulus` whose limbs are all at least
+    /// `2^b.max_limb_bits` is added to the difference.
+    pub fn sub(
+        &self,
+        ctx: &mut Context<F>,
+        a: &BigInt<F, LIMBS, BITS>,
+        b: &BigInt<F, LIMBS, BITS>,
+    ) -> BigInt<F, LIMBS, BITS> {
+        let gate = self.range.gate();
+        // `pad = sum_i 2^b.max_limb_bits * 2^(BITS * i)`, plus the least `d` making it a multiple of `modulus`
+        let pad =
+            (0..LIMBS).fold(BigUint::zero(), |acc, _| (acc << BITS) + 1u64) << b.max_limb_bits;
+        let d = (&self.modulus - pad.mod_floor(&self.modulus)).mod_floor(&self.modulus);
+        let pad_limb = BigUint::one() << b.max_limb_bits;
+        let pad_limbs = Self::decompose(&d).into_iter().map(|d| biguint_to_fe(&(d + &pad_limb)));
+        let limbs = pad_limbs
+            .zip(a.limbs.iter().zip(&b.limbs))
+            .map(|(pad, (a, b))| {
+                let diff = gate.sub(ctx, Constant(pad), *b);
+                gate.add(ctx, *a, diff)
+            })
+            .collect::<Vec<_>>();
+        // each `pad - b` limb is in `[1, 2^b.max_limb_bits + 2^BITS)`
+        let max_limb_bits = max(a.max_limb_bits, max(b.max_limb_bits, BITS) + 1) + 1;
+        self.overflowing(limbs.try_into().unwrap(), max_limb_bits)
+    }
+
+    /// Constrains and returns an integer congruent to `a * b` modulo `modulus`, with limbs of `BITS` bits.
+    ///
+    /// The result is less than `2^(LIMBS * BITS)`, but not necessarily less than `modulus`.
+    pub fn mul(
+        &self,
+        ctx: &mut Context<F>,
+        a: &BigInt<F, LIMBS, BITS>,
+        b: &BigInt<F, LIMBS, BITS>,
+    ) -> BigInt<F, LIMBS, BITS> {
+        let gate = self.range.gate();
+        let products = (0..2 * LIMBS - 1)
+            .map(|k| {
+                let (lo, hi) = (k.saturating_sub(LIMBS - 1), k.min(LIMBS - 1));
+                let terms = (lo..=hi).map(|i| (a.limbs[i], Existing(b.limbs[k - i])));
+                gate.inner_product(ctx, terms.clone().map(|(a, _)| a), terms.map(|(_, b)| b))
+            })
+            .collect::<Vec<_>>();
+        let product_bits = a.max_limb_bits + b.max_limb_bits + bit_length(LIMBS as u64);
+        let value_bits = a.value_bits() + b.value_bits();
+        self.reduce_limbs(ctx, products, product_bits, a.value() * b.value(), value_bits)
+    }
+
+    /// Constrains and returns an integer congruent to `a` modulo `modulus`, with limbs of `BITS` bits.
+    ///
+    /// The result is less than `2^(LIMBS * BITS)`, but not necessarily less than `modulus`.
+    pub fn mod_reduce(
+        &self,
+        ctx: &mut Context<F>,
+        a: &BigInt<F, LIMBS, BITS>,
+    ) -> BigInt<F, LIMBS, BITS> {
+        self.reduce_limbs(ctx, a.limbs.to_vec(), a.max_limb_bits, a.value(), a.value_bits())
+    }
+
+    /// Witnesses `(q, r) = (value / modulus, value % modulus)` and constrains that `limbs` represent `q * modulus + r`.
+    /// * `limbs`: non-negative limbs with at most `limb_bits` bits, representing the integer `value`
+    /// * `value_bits`: upper bound on the number of bits of `value`
+    fn reduce_limbs(
+        &self,
+        ctx: &mut Context<F>,
+        limbs: Vec<AssignedValue<F>>,
+        limb_bits: usize,
+        value: BigUint,
+        value_bits: usize,
+    ) -> BigInt<F, LIMBS, BITS> {
+        let gate = self.range.gate();
+        let (q, r) = value.div_mod_floor(&self.modulus);
+        // `q < 2^value_bits / 2^(modulus_bits - 1)`
+        let q_bits = (value_bits + 1).saturating_sub(self.modulus.bits() as usize);
+        let q_len = max(1, (q_bits + BITS - 1) / BITS);
+        let q = self.load_limbs(ctx, Self::decompose_into(&q, q_len));
+        let r = self.load_limbs(ctx, Self::decompose(&r));
+
+        // `limbs - q * modulus - r`, column by column
+        let num_cols = max(limbs.len(), q_len + LIMBS - 1);
+        let diff = (0..num_cols)
+            .map(|k| {
+                let (lo, hi) = (k.saturating_sub(LIMBS - 1), k.min(q_len - 1));
+                let qm = (lo..=hi).map(|i| (q[i], Constant(self.modulus_limbs[k - i])));
+                let qm = gate.inner_product(ctx, qm.clone().map(|(q, _)| q), qm.map(|(_, m)| m));
+                let limb = limbs.get(k).copied().map(Existing).unwrap_or(Constant(F::ZERO));
+                let diff = gate.sub(ctx, limb, qm);
+                match r.get(k) {
+                    Some(r) => gate.sub(ctx, diff, *r),
+                    None => diff,
+                }
+            })
+            .collect::<Vec<_>>();
+        // every `q * modulus` column is a sum of at most `min(q_len, LIMBS)` products of two `BITS`-bit limbs
+        let qm_bits = 2 * BITS + bit_length(q_len.min(LIMBS) as u64);
+        self.check_carry_to_zero(ctx, &diff, max(limb_bits, qm_bits + 1));
+        BigInt { limbs: r.try_into().unwrap(), max_limb_bits: BITS }
+    }
+
+    /// Constrains that the signed integer `sum_i limbs[i] * 2^(BITS * i)` is zero, where `|limbs[i]| < 2^limb_bits`.
+    ///
+    /// Witnesses the carry out of every limb, which has absolute value less than `2^(limb_bits - BITS + 1)`.
+    fn check_carry_to_zero(
+        &self,
+        ctx: &mut Context<F>,
+        limbs: &[AssignedValue<F>],
+        limb_bits: usize,
+    ) {
+        let carry_bits = limb_bits + 1 - BITS;
+        assert!(
+            limb_bits + 2 <= F::CAPACITY as usize,
+            "limbs are too large for the native field; call mod_reduce more often"
+        );
+        let gate = self.range.gate();
+        let shift = biguint_to_fe(&(BigUint::one() << BITS));
+        let carry_offset = biguint_to_fe(&(BigUint::one() << carry_bits));
+        let (last, rest) = limbs.split_last().unwrap();
+        let mut carry = None;
+        for limb in rest {
+            let sum = match carry {
+                Some(carry) => gate.add(ctx, *limb, carry),
+                None => *limb,
+            };
+            // the division is exact if the constraint is satisfiable, so the floor does not matter
+            let carry_val: BigIntValue = fe_to_bigint(sum.value()) >> BITS;
+            let next_carry = ctx.load_witness(bigint_to_fe(&carry_val));
+            let shifted = gate.mul(ctx, next_carry, Constant(shift));
+            ctx.constrain_equal(&sum, &shifted);
+            let offset_carry = gate.add(ctx, next_carry, Constant(carry_offset));
+            self.range.range_check(ctx, offset_carry, carry_bits + 1);
+            carry = Some(next_carry);
+        }
+        let sum = match carry {
+            Some(carry) => gate.add(ctx, *last, carry),
+            None => *last,
+        };
+        gate.assert_is_const(ctx, &sum, &F::ZERO);
+    }
+
+    /// Returns a [BigInt] with `limbs` of at most `max_limb_bits` bits, checking that they are small enough to be reduced.
+    fn overflowing(
+        &self,
+        limbs: [AssignedValue<F>; LIMBS],
+        max_limb_bits: usize,
+    ) -> BigInt<F, LIMBS, BITS> {
+        assert!(
+            max_limb_bits + 2 <= F::CAPACITY as usize,
+            "limbs are too large for the native field; call mod_reduce first"
+        );
+        BigInt { limbs, max_limb_bits }
+    }
+
+    /// Loads `limbs` as witnesses, range checking each to `BITS` bits.
+    fn load_limbs(&self, ctx: &mut Context<F>, limbs: Vec<BigUint>) -> Vec<AssignedValue<F>> {
+        let limbs = ctx.assign_witnesses(limbs.iter().map(biguint_to_fe));
+        for limb in &limbs {
+            self.range.range_check(ctx, *limb, BITS);
+        }
+        limbs
+    }
+
+    fn decompose(value: &BigUint) -> Vec<BigUint> {
+        Self::decompose_into(value, LIMBS)
+    }
+
+    /// Returns the `len` little-endian limbs of `BITS` bits of `value`, which must fit in them.
+    fn decompose_into(value: &BigUint, len: usize) -> Vec<BigUint> {
+        let mask = (BigUint::one() << BITS) - 1u64;
+        (0..len).map(|i| (value >> (BITS * i)) & &mask).collect()
+    }
+}
@@ -0,0 +1,84 @@
+use crate::bigint::BigIntChip;
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use crate::utils::testing::base_test;
+use num_bigint::BigUint;
+use num_traits::Num;
+use test_case::test_case;
+
+type Chip<'a> = BigIntChip<'a, Fr, 3, 88>;
+
+/// Base field modulus of secp256k1.
+fn secp256k1_p() -> BigUint {
+    BigUint::from_str_radix("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f", 16)
+        .unwrap()
+}
+
+fn big(hex: &str) -> BigUint {
+    BigUint::from_str_radix(hex, 16).unwrap()
+}
+
+#[test_case(big("1"), big("2") ; "small")]
+#[test_case(secp256k1_p() - 1u64, secp256k1_p() - 2u64 ; "near modulus")]
+#[test_case(big("0"), secp256k1_p() - 1u64 ; "zero")]
+fn test_bigint_ops(a: BigUint, b: BigUint) {
+    let p = secp256k1_p();
+    base_test().k(15).run(|ctx, range| {
+        let chip = Chip::new(range, p.clone());
+        let [a_assigned, b_assigned] = [&a, &b].map(|x| chip.load(ctx, x));
+
+        let sum = chip.add(ctx, &a_assigned, &b_assigned);
+        assert_eq!(sum.value(), &a + &b);
+        assert_eq!(sum.max_limb_bits, 89);
+        let diff = chip.sub(ctx, &a_assigned, &b_assigned);
+        assert_eq!(diff.value() % &p, (&a + &p - &b) % &p);
+        let prod = chip.mul(ctx, &a_assigned, &b_assigned);
+        assert!(prod.is_reduced());
+        assert_eq!(prod.value() % &p, &a * &b % &p);
+
+        let reduced = chip.mod_reduce(ctx, &diff);
+        assert!(reduced.is_reduced());
+        assert_eq!(reduced.value() % &p, (&a + &p - &b) % &p);
+    });
+}
+
+#[test]
+fn test_bigint_overflow_tracking() {
+    let p = secp256k1_p();
+    base_test().k(15).run(|ctx, range| {
+        let chip = Chip::new(range, p.clone());
+        let a = chip.load(ctx, &(&p - 1u64));
+        let mut acc = a;
+        for _ in 0..20 {
+            acc = chip.add(ctx, &acc, &a);
+        }
+        assert_eq!(acc.max_limb_bits, 108);
+        assert!(!acc.is_reduced());
+        let square = chip.mul(ctx, &acc, &acc);
+        assert_eq!(square.value() % &p, BigUint::from(441u64));
+        let reduced = chip.mod_reduce(ctx, &acc);
+        assert_eq!(reduced.value() % &p, &p - 21u64);
+    });
+}
+
+#[test]
+#[should_panic(expected = "call mod_reduce first")]
+fn test_bigint_overflow_panics() {
+    base_test().k(15).run(|ctx, range| {
+        let chip = Chip::new(range, secp256k1_p());
+        let mut acc = chip.load(ctx, &big("1"));
+        for _ in 0..200 {
+            acc = chip.add(ctx, &acc, &acc);
+        }
+    });
+}
+
+#[test]
+fn test_bigint_mul_tampered() {
+    base_test().k(15).expect_satisfied(false).run(|ctx, range| {
+        let chip = Chip::new(range, secp256k1_p());
+        let [a, b] = [big("123456789abcdef"), big("fedcba987654321")].map(|x| chip.load(ctx, &x));
+        let prod = chip.mul(ctx, &a, &b);
+        let limb = prod.limbs[0];
+        limb.debug_prank(ctx, *limb.value() + Fr::from(1));
+    });
+}
@@ -0,0 +1,72 @@
+use crate::{
+    gates::{GateInstructions, RangeInstructions},
+    utils::ScalarField,
+    AssignedValue, Context,
+};
+
+use getset::CopyGetters;
+
+/// In-circuit counter that can only move forward by increments of one.
+///
+/// Every increment constrains `new = old + 1` (or `new = old + cond` for [`CounterGadget::increment_if`]),
+/// so the final value of the counter is constrained to equal the starting value plus the number of increments.
+#[derive(Clone, Copy, Debug, CopyGetters)]
+pub struct CounterGadget<F: ScalarField> {
+    /// The current value of the counter.
+    #[getset(get_copy = "pub")]
+    current: AssignedValue<F>,
+}
+
+impl<F: ScalarField> CounterGadget<F> {
+    /// Creates a new counter starting at `start`.
+    pub fn new(start: AssignedValue<F>) -> Self {
+        Self { current: start }
+    }
+
+    /// Creates a new counter starting at the constant 0.
+    pub fn zero(ctx: &mut Context<F>) -> Self {
+        Self::new(ctx.load_zero())
+    }
+
+    /// Constrains `new = old + 1`, updates the counter and returns the new value.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `gate`: [GateInstructions] to use
+    pub fn increment(
+        &mut self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+    ) -> AssignedValue<F> {
+        self.current = gate.inc(ctx, self.current);
+        self.current
+    }
+
+    /// Constrains `new = old + cond`, updates the counter and returns the new value.
+    ///
+    /// `cond` is constrained to be boolean.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `gate`: [GateInstructions] to use
+    /// * `cond`: [AssignedValue] bit deciding whether to increment
+    pub fn increment_if(
+        &mut self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        cond: AssignedValue<F>,
+    ) -> AssignedValue<F> {
+        gate.assert_bit(ctx, cond);
+        self.current = gate.add(ctx, self.current, cond);
+        self.current
+    }
+
+    /// Constrains that the current value of the counter is less than `max`.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `range`: [RangeInstructions] to use
+    /// * `max`: strict upper bound for the counter
+    pub fn assert_less_than(
+        &self,
+        ctx: &mut Context<F>,
+        range: &impl RangeInstructions<F>,
+        max: u64,
+    ) {
+        range.check_less_than_safe(ctx, self.current, max);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    gates::GateInstructions,
+    utils::ScalarField,
+    AssignedValue, Context,
+    QuantumCell::{self, Constant, Existing},
+};
+
+/// Largest `n` for which `fib(n)` is loaded from a table of constants, since `fib(64) < 2^64`.
+pub const FIB_TABLE_MAX: usize = 64;
+
+/// Returns `fib(0), ..., fib(FIB_TABLE_MAX)` computed natively.
+fn fib_table() -> Vec<u64> {
+    let mut table = vec![0, 1];
+    for i in 2..=FIB_TABLE_MAX {
+        table.push(table[i - 1] + table[i - 2]);
+    }
+    table
+}
+
+/// Constrains and returns `fib(n)` for `n <= FIB_TABLE_MAX`.
+///
+/// Same as [fib_circuit_bounded] with `max_n = FIB_TABLE_MAX`, so every candidate is a constant.
+/// * `ctx`: [Context] to add the constraints to
+/// * `gate`: [GateInstructions] to use
+/// * `n`: [AssignedValue] index of the Fibonacci number
+pub fn fib_circuit<F: ScalarField>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    n: AssignedValue<F>,
+) -> AssignedValue<F> {
+    fib_circuit_bounded(ctx, gate, n, FIB_TABLE_MAX)
+}
+
+/// Constrains and returns `fib(n)` (mod the field modulus) for `n <= max_n`.
+///
+/// Since `n` is a witness, every candidate `fib(0), ..., fib(max_n)` is laid out and `fib(n)` is selected with the
+/// indicator vector of `n`. The same pattern applies to any loop whose number of iterations is a witness.
+///
+/// `fib(i)` for `i <= FIB_TABLE_MAX` are constants. Each `fib(i)` with `FIB_TABLE_MAX < i <= max_n` is constrained
+/// with a single [GateInstructions::add] of the previous two. The constraints fail if `n > max_n`.
+/// * `ctx`: [Context] to add the constraints to
+/// * `gate`: [GateInstructions] to use
+/// * `n`: [AssignedValue] index of the Fibonacci number
+/// * `max_n`: largest supported value of `n`
+pub fn fib_circuit_bounded<F: ScalarField>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    n: AssignedValue<F>,
+    max_n: usize,
+) -> AssignedValue<F> {
+    let mut fibs: Vec<QuantumCell<F>> =
+        fib_table().into_iter().take(max_n + 1).map(|x| Constant(F::from(x))).collect();
+    for i in fibs.len()..=max_n {
+        let next = gate.add(ctx, fibs[i - 1], fibs[i - 2]);
+        fibs.push(Existing(next));
+    }
+    let indicator = gate.idx_to_indicator(ctx, n, max_n + 1);
+    // `indicator` is all zeros if `n > max_n`
+    let is_in_range = gate.sum(ctx, indicator.iter().copied());
+    gate.assert_is_const(ctx, &is_in_range, &F::ONE);
+    gate.select_by_indicator(ctx, fibs, indicator)
+}
@@ -0,0 +1,2 @@
+/// Module for computing Fibonacci numbers with a table lookup and an in-circuit recurrence
+pub mod fibonacci;
@@ -6,7 +6,7 @@ use crate::{
         },
         poly::Rotation,
     },
-    utils::ScalarField,
+    utils::{addition_chain, ScalarField},
     AssignedValue, Context,
     QuantumCell::{self, Constant, Existing, Witness, WitnessFraction},
 };
@@ -299,6 +299,23 @@ pub trait GateInstructions<F: ScalarField> {
         ctx.get(-4)
     }
 
+    /// Constrains and returns `cond ? a + b : a`, assuming `cond` is boolean.
+    ///
+    /// Computed as `a + cond * b` with a single [GateInstructions::mul_add], instead of an `add` followed by a `select`.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `a`: [AssignedValue] value
+    /// * `b`: [AssignedValue] value to add to `a` if `cond` is true
+    /// * `cond`: [AssignedValue] boolean condition
+    fn conditional_add(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        b: AssignedValue<F>,
+        cond: AssignedValue<F>,
+    ) -> AssignedValue<F> {
+        self.mul_add(ctx, cond, b, a)
+    }
+
     /// Constrains that x is boolean (e.g. 0 or 1).
     ///
     /// Defines a vertical gate of form | 0 | x | x | x |.
@@ -308,6 +325,22 @@ pub trait GateInstructions<F: ScalarField> {
         ctx.assign_region([Constant(F::ZERO), Existing(x), Existing(x), Existing(x)], [0]);
     }
 
+    /// Constrains that the bits `a` and `b` are different, i.e., `a + b = 1`.
+    ///
+    /// Defines a vertical gate of form | a | b | 1 | 1 |.
+    ///
+    /// Assumes `a` and `b` are bits, which is checked only in debug builds; otherwise `a + b = 1` does not imply `a != b`.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `a`: [AssignedValue] bit
+    /// * `b`: [AssignedValue] bit
+    fn boolean_assert_ne(&self, ctx: &mut Context<F>, a: AssignedValue<F>, b: AssignedValue<F>) {
+        debug_assert!(
+            [a, b].iter().all(|x| *x.value() == F::ZERO || *x.value() == F::ONE),
+            "boolean_assert_ne inputs must be bits"
+        );
+        ctx.assign_region([Existing(a), Existing(b), Constant(F::ONE), Constant(F::ONE)], [0]);
+    }
+
     /// Constrains and returns a / b = 0.
     ///
     /// Defines a vertical gate of form | 0 | b^1 * a | b | a |, where b^1 * a = out.
@@ -331,14 +364,74 @@ pub trait GateInstructions<F: ScalarField> {
         ctx.get(-3)
     }
 
-    /// Constrains that `a` is equal to `constant` value.
+    /// Constrains that `a` is equal to `constant` value, without assigning any new cell, see [Context::constrain_constant].
     /// * `ctx`: [Context] to add the constraints to
     /// * `a`: [QuantumCell] value
     /// * `constant`: constant value to constrain `a` to be equal to
     fn assert_is_const(&self, ctx: &mut Context<F>, a: &AssignedValue<F>, constant: &F) {
-        if !ctx.witness_gen_only {
-            ctx.copy_manager.lock().unwrap().constant_equalities.push((*constant, a.cell.unwrap()));
+        ctx.constrain_constant(a, *constant);
+    }
+
+    /// Constrains that all elements of `vals` are equal, using `vals.len() - 1` copy constraints to `vals[0]`.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `vals`: slice of [AssignedValue]s
+    fn assert_all_equal(&self, ctx: &mut Context<F>, vals: &[AssignedValue<F>]) {
+        if let Some((first, rest)) = vals.split_first() {
+            for val in rest {
+                ctx.constrain_equal(first, val);
+            }
+        }
+    }
+
+    /// Constrains that all elements of `vals` are equal to `constant`.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `vals`: slice of [AssignedValue]s
+    /// * `constant`: constant value to constrain the elements of `vals` to be equal to
+    fn assert_all_equal_to(&self, ctx: &mut Context<F>, vals: &[AssignedValue<F>], constant: &F) {
+        for val in vals {
+            self.assert_is_const(ctx, val, constant);
+        }
+    }
+
+    /// Constrains that every element of `bits` is a bit and that exactly one of them is set, e.g. that `bits` is
+    /// a one-hot indicator vector such as the output of [GateInstructions::idx_to_indicator].
+    ///
+    /// The bits are summed in a single running sum, which is then constrained to equal 1.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `bits`: slice of [AssignedValue]s to constrain
+    fn assert_bits_sum_to_one(&self, ctx: &mut Context<F>, bits: &[AssignedValue<F>]) {
+        for bit in bits {
+            self.assert_bit(ctx, *bit);
+        }
+        let sum = self.sum(ctx, bits.iter().copied());
+        self.assert_is_const(ctx, &sum, &F::ONE);
+    }
+
+    /// Constrains that every element of `bits` is a bit and that at most one of them is set.
+    ///
+    /// Same as [GateInstructions::assert_bits_sum_to_one], except that the sum is only constrained to be a bit.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `bits`: slice of [AssignedValue]s to constrain
+    fn assert_bits_sum_to_at_most_one(&self, ctx: &mut Context<F>, bits: &[AssignedValue<F>]) {
+        for bit in bits {
+            self.assert_bit(ctx, *bit);
         }
+        let sum = self.sum(ctx, bits.iter().copied());
+        self.assert_bit(ctx, sum);
+    }
+
+    /// Constrains that every element of `bits` is a bit and that at least one of them is set.
+    ///
+    /// Since the bits sum to less than the modulus, they are not all zero exactly when their sum is nonzero,
+    /// which is constrained with [GateInstructions::assert_not_zero]. The constraints fail if `bits` is empty.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `bits`: slice of [AssignedValue]s to constrain
+    fn assert_at_least_one_true(&self, ctx: &mut Context<F>, bits: &[AssignedValue<F>]) {
+        for bit in bits {
+            self.assert_bit(ctx, *bit);
+        }
+        let sum = self.sum(ctx, bits.iter().copied());
+        self.assert_not_zero(ctx, sum);
     }
 
     /// Constrains and returns the inner product of `<a, b>`.
@@ -410,6 +503,58 @@ pub trait GateInstructions<F: ScalarField> {
     where
         QA: Into<QuantumCell<F>>;
 
+    /// Constrains and returns the inner product of `<a, b>` for already assigned `a` and `b`.
+    ///
+    /// Produces the same cells and constraints as [GateInstructions::inner_product] on the same inputs, with one
+    /// multiply-add gate per pair, but without converting and inspecting each input as a general [QuantumCell].
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `a`: slice of [AssignedValue]s
+    /// * `b`: slice of [AssignedValue]s with the same length as `a`
+    fn inner_product_simple(
+        &self,
+        ctx: &mut Context<F>,
+        a: &[AssignedValue<F>],
+        b: &[AssignedValue<F>],
+    ) -> AssignedValue<F> {
+        assert_eq!(a.len(), b.len(), "a and b must have the same length");
+        let mut sum = F::ZERO;
+        let cells = iter::once(Constant(F::ZERO)).chain(a.iter().zip(b).flat_map(|(a, b)| {
+            sum += *a.value() * b.value();
+            [Existing(*a), Existing(*b), Witness(sum)]
+        }));
+        let gate_offsets = if ctx.witness_gen_only() {
+            vec![]
+        } else {
+            (0..a.len()).map(|i| 3 * i as isize).collect()
+        };
+        ctx.assign_region_last(cells, gate_offsets)
+    }
+
+    /// Constrains and returns `(<a, b>, <a, a>)`, the inner product of `a` and `b` and the squared L2 norm of `a`.
+    ///
+    /// Both sums are accumulated in a single pass over `a`, so each `a[i]` is read once for both products.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `a`: slice of [AssignedValue]s
+    /// * `b`: slice of [AssignedValue]s with the same length as `a`
+    fn dot_product_and_norm(
+        &self,
+        ctx: &mut Context<F>,
+        a: &[AssignedValue<F>],
+        b: &[AssignedValue<F>],
+    ) -> (AssignedValue<F>, AssignedValue<F>) {
+        assert_eq!(a.len(), b.len(), "a and b must have the same length");
+        let mut dot = Constant(F::ZERO);
+        let mut norm = Constant(F::ZERO);
+        for (a, b) in a.iter().zip(b) {
+            dot = Existing(self.mul_add(ctx, *a, *b, dot));
+            norm = Existing(self.mul_add(ctx, *a, *a, norm));
+        }
+        match (dot, norm) {
+            (Existing(dot), Existing(norm)) => (dot, norm),
+            _ => (ctx.load_zero(), ctx.load_zero()),
+        }
+    }
+
     /// Constrains and returns the sum of [QuantumCell]'s in iterator `a`.
     /// * `ctx`: [Context] to add the constraints to
     /// * `a`: Iterator of [QuantumCell] values to sum
@@ -438,6 +583,34 @@ pub trait GateInstructions<F: ScalarField> {
         ctx.assign_region_last(cells, (0..len).map(|i| 3 * i as isize))
     }
 
+    /// Constrains and returns `sum_i w_i * a_i` for `inputs = [(w_0, a_0), (w_1, a_1), ...]` with constant weights `w_i`.
+    ///
+    /// The terms are accumulated in a single [GateInstructions::inner_product], so each term costs one gate row of
+    /// 3 cells, compared to 4 cells for a [GateInstructions::mul_add]. Terms with weight 0 are skipped, and a term
+    /// with weight 1 is moved first so it starts the accumulation without a gate. Since every term already takes a
+    /// single row, weights forming powers of two do not allow any further saving with this gate.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `inputs`: slice of pairs of a constant weight and an [AssignedValue]
+    fn linear_combination(
+        &self,
+        ctx: &mut Context<F>,
+        inputs: &[(F, AssignedValue<F>)],
+    ) -> AssignedValue<F> {
+        let mut terms = inputs.iter().filter(|(w, _)| *w != F::ZERO).collect::<Vec<_>>();
+        if let Some(i) = terms.iter().position(|(w, _)| *w == F::ONE) {
+            terms[..=i].rotate_right(1);
+        }
+        match terms.as_slice() {
+            [] => ctx.load_zero(),
+            [(w, a)] if *w == F::ONE => *a,
+            _ => self.inner_product(
+                ctx,
+                terms.iter().map(|(_, a)| *a),
+                terms.iter().map(|(w, _)| Constant(*w)),
+            ),
+        }
+    }
+
     /// Calculates and constrains the sum of the elements of `a`.
     ///
     /// Returns the assignment trace where `output[i]` has the running sum `sum_{j=0..=i} a[j]`.
@@ -520,6 +693,28 @@ pub trait GateInstructions<F: ScalarField> {
         var: QuantumCell<F>,
     ) -> AssignedValue<F>;
 
+    /// Constrains and returns `ab_pairs[0].0 * ab_pairs[0].1 + ... + ab_pairs[n].0 * ab_pairs[n].1 + c * d`.
+    ///
+    /// Unlike [GateInstructions::sum_products_with_coeff_and_var], the extra term is a product of two [QuantumCell]s.
+    /// All products are accumulated in a single [GateInstructions::inner_product], which takes one gate and three cells
+    /// per product instead of finishing with a separate [GateInstructions::mul_add].
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `ab_pairs`: slice of pairs of [QuantumCell]s to multiply
+    /// * `c`: [QuantumCell] value
+    /// * `d`: [QuantumCell] value to multiply `c` by
+    fn sum_products_with_var_product(
+        &self,
+        ctx: &mut Context<F>,
+        ab_pairs: &[(QuantumCell<F>, QuantumCell<F>)],
+        c: QuantumCell<F>,
+        d: QuantumCell<F>,
+    ) -> AssignedValue<F> {
+        // `c * d` goes first so the `Constant(F::ONE)` optimization of `inner_product` applies when `d == 1`
+        let (a, b): (Vec<_>, Vec<_>) =
+            std::iter::once((c, d)).chain(ab_pairs.iter().copied()).unzip();
+        self.inner_product(ctx, a, b)
+    }
+
     /// Constrains and returns `a || b`, assuming `a` and `b` are boolean.
     ///
     /// Defines a vertical gate of form `| 1 - b | 1 | b | 1 | b | a | 1 - b | out |`, where `out = a + b - a * b`.
@@ -604,6 +799,57 @@ pub trait GateInstructions<F: ScalarField> {
         self.sub(ctx, Constant(F::ONE), a)
     }
 
+    /// Constrains that every element of `bits` is boolean and returns their conjunction, or 1 if `bits` is empty.
+    ///
+    /// The bits are combined pairwise with [GateInstructions::and] in a balanced tree of depth `ceil(log2(bits.len()))`.
+    /// * `ctx`: [Context] to add the constraints to.
+    /// * `bits`: slice of [AssignedValue]s to constrain to be boolean.
+    fn multi_and(&self, ctx: &mut Context<F>, bits: &[AssignedValue<F>]) -> AssignedValue<F> {
+        if bits.is_empty() {
+            return ctx.load_constant(F::ONE);
+        }
+        for bit in bits {
+            self.assert_bit(ctx, *bit);
+        }
+        let mut layer = bits.to_vec();
+        while layer.len() > 1 {
+            // an unpaired last element moves up to the next layer unchanged
+            layer = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => self.and(ctx, *a, *b),
+                    _ => pair[0],
+                })
+                .collect();
+        }
+        layer[0]
+    }
+
+    /// Constrains that every element of `bits` is boolean and returns their disjunction, or 0 if `bits` is empty.
+    ///
+    /// The bits are combined pairwise with [GateInstructions::or] in a balanced tree of depth `ceil(log2(bits.len()))`.
+    /// * `ctx`: [Context] to add the constraints to.
+    /// * `bits`: slice of [AssignedValue]s to constrain to be boolean.
+    fn multi_or(&self, ctx: &mut Context<F>, bits: &[AssignedValue<F>]) -> AssignedValue<F> {
+        if bits.is_empty() {
+            return ctx.load_zero();
+        }
+        for bit in bits {
+            self.assert_bit(ctx, *bit);
+        }
+        let mut layer = bits.to_vec();
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => self.or(ctx, *a, *b),
+                    _ => pair[0],
+                })
+                .collect();
+        }
+        layer[0]
+    }
+
     /// Constrains and returns `sel ? a : b` assuming `sel` is boolean.
     ///
     /// Defines a vertical gate of form `| 1 - sel | sel | 1 | a | 1 - sel | sel | 1 | b | out |`, where out = sel * a + (1 - sel) * b.
@@ -619,6 +865,61 @@ pub trait GateInstructions<F: ScalarField> {
         sel: impl Into<QuantumCell<F>>,
     ) -> AssignedValue<F>;
 
+    /// Constrains and returns `cond ? then_val : else_val` assuming `cond` is boolean.
+    ///
+    /// Same constraints as [Self::select], with the condition first so call sites read like an `if` expression:
+    /// ```ignore
+    /// // both return `a` if `is_a` is 1, otherwise `b`
+    /// let out = gate.select(ctx, a, b, is_a);
+    /// let out = gate.if_then_else(ctx, is_a, a, b);
+    /// ```
+    /// In debug builds, panics if the value of `cond` is not 0 or 1. This does not constrain `cond`.
+    /// * `ctx`: [Context] to add the constraints to.
+    /// * `cond`: [AssignedValue] that contains a boolean value.
+    /// * `then_val`: [QuantumCell] returned if `cond` is 1.
+    /// * `else_val`: [QuantumCell] returned if `cond` is 0.
+    fn if_then_else(
+        &self,
+        ctx: &mut Context<F>,
+        cond: AssignedValue<F>,
+        then_val: impl Into<QuantumCell<F>>,
+        else_val: impl Into<QuantumCell<F>>,
+    ) -> AssignedValue<F> {
+        debug_assert!(
+            *cond.value() == F::ZERO || *cond.value() == F::ONE,
+            "if_then_else: cond must be boolean"
+        );
+        self.select(ctx, then_val, else_val, cond)
+    }
+
+    /// Constrains and returns one of `a`, `b`, `c` chosen by the two selector bits `(s0, s1)`:
+    /// `(0, 0)` selects `a`, `(1, 0)` selects `b` and `(0, 1)` selects `c`.
+    ///
+    /// Constrains `s0` and `s1` to be bits with `s0 + s1 <= 1`, then uses two [GateInstructions::select].
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `a`: [QuantumCell] selected by `(0, 0)`
+    /// * `b`: [QuantumCell] selected by `(1, 0)`
+    /// * `c`: [QuantumCell] selected by `(0, 1)`
+    /// * `s0`: [AssignedValue] first selector bit
+    /// * `s1`: [AssignedValue] second selector bit
+    fn ternary_select(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<QuantumCell<F>>,
+        b: impl Into<QuantumCell<F>>,
+        c: impl Into<QuantumCell<F>>,
+        s0: AssignedValue<F>,
+        s1: AssignedValue<F>,
+    ) -> AssignedValue<F> {
+        self.assert_bit(ctx, s0);
+        self.assert_bit(ctx, s1);
+        // for bits, `s0 + s1 <= 1` is the same as `s0 * s1 = 0`
+        let both = self.mul(ctx, s0, s1);
+        self.assert_is_const(ctx, &both, &F::ZERO);
+        let a_or_b = self.select(ctx, b, a, s0);
+        self.select(ctx, c, a_or_b, s1)
+    }
+
     /// Constains and returns `a || (b && c)`, assuming `a`, `b` and `c` are boolean.
     ///
     /// Defines a vertical gate of form `| 1 - b c | b | c | 1 | a - 1 | 1 - b c | out | a - 1 | 1 | 1 | a |`, where out = a + b * c - a * b * c.
@@ -735,6 +1036,9 @@ pub trait GateInstructions<F: ScalarField> {
 
     /// Constrains the inner product of `a` and `indicator` and returns `a[idx]` (e.g. the value of `a` at `idx`).
     ///
+    /// Together with [GateInstructions::idx_to_indicator], this replaces a tree of [GateInstructions::select] calls on
+    /// the bits of `idx`: it works for any length of `a` and does not need to decompose `idx` into bits.
+    ///
     /// Assumes that `a` and `indicator` are non-empty iterators of the same length, the values of `indicator` are boolean,
     /// and that `indicator` has at most one `1` bit.
     /// * `ctx`: [Context] to add the constraints to
@@ -856,6 +1160,142 @@ pub trait GateInstructions<F: ScalarField> {
         self.is_zero(ctx, diff)
     }
 
+    /// Returns `1` if `a = b`, otherwise `0`, using a single region instead of [GateInstructions::sub] followed by [GateInstructions::is_zero].
+    ///
+    /// Defines a vertical gate of form `| a | b | -1 | diff | 1 | diff | -inv | out | diff | out | out |`, where
+    /// * `a - b = diff`
+    /// * `1 + diff * (-inv) = out`
+    /// * `out + diff * out = out`, i.e. `diff * out = 0`
+    ///
+    /// The last two gates share exactly one cell, so the region may be split across a column break.
+    /// This uses 11 cells instead of the 12 cells of [GateInstructions::is_equal].
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `a`: [AssignedValue] value
+    /// * `b`: [AssignedValue] value to compare to `a`
+    fn is_equal_raw(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        b: AssignedValue<F>,
+    ) -> AssignedValue<F> {
+        let diff = *a.value() - b.value();
+        let (is_zero, neg_inv) = if diff.is_zero_vartime() {
+            (F::ONE, Assigned::Trivial(F::ZERO))
+        } else {
+            (F::ZERO, Assigned::Rational(-F::ONE, diff))
+        };
+
+        let cells = [
+            Existing(a),
+            Existing(b),
+            Constant(-F::ONE),
+            Witness(diff),
+            Constant(F::ONE),
+            Witness(diff),
+            WitnessFraction(neg_inv),
+            Witness(is_zero),
+            Witness(diff),
+            Witness(is_zero),
+            Witness(is_zero),
+        ];
+        ctx.assign_region_smart(cells, [0, 4, 7], [(3, 5), (3, 8), (7, 9), (7, 10)], []);
+        ctx.get(-4)
+    }
+
+    /// Constrains that `a != 0` and returns `a`<sup>-1</sup>.
+    ///
+    /// Defines a vertical gate of form `| 0 | a | inv | 1 |`, where a * inv = 1. If `a = 0`, the inverse is witnessed
+    /// as `0` so that the constraint fails instead of witness generation panicking.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `a`: [AssignedValue] value to be constrained
+    fn assert_not_zero(&self, ctx: &mut Context<F>, a: AssignedValue<F>) -> AssignedValue<F> {
+        let x = a.value();
+        let inv = if x.is_zero_vartime() {
+            Assigned::Trivial(F::ZERO)
+        } else {
+            Assigned::Rational(F::ONE, *x)
+        };
+        ctx.assign_region(
+            [Constant(F::ZERO), Existing(a), WitnessFraction(inv), Constant(F::ONE)],
+            [0],
+        );
+        ctx.get(-2)
+    }
+
+    /// Constrains that `a != b`.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `a`: [QuantumCell] value
+    /// * `b`: [QuantumCell] value to compare to `a`
+    fn assert_not_equal(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<QuantumCell<F>>,
+        b: impl Into<QuantumCell<F>>,
+    ) {
+        let diff = self.sub(ctx, a, b);
+        self.assert_not_zero(ctx, diff);
+    }
+
+    /// Constrains that every element of `vals` is non-zero.
+    ///
+    /// Constrains the product of `vals` to be non-zero, so only a single inverse is witnessed.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `vals`: slice of [AssignedValue]s to be constrained
+    fn assert_nonzero_batch(&self, ctx: &mut Context<F>, vals: &[AssignedValue<F>]) {
+        if let Some((first, rest)) = vals.split_first() {
+            let prod = rest.iter().fold(*first, |prod, val| self.mul(ctx, prod, *val));
+            self.assert_not_zero(ctx, prod);
+        }
+    }
+
+    /// Constrains that every element of `vals` is non-zero and returns their inverses, using Montgomery's trick.
+    ///
+    /// Only the product of all elements is inverted, with [GateInstructions::assert_not_zero], and each inverse is
+    /// recovered from it with multiplications, so this costs `3 * (len(vals) - 1)` multiplications and one inversion.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `vals`: slice of [AssignedValue]s to invert
+    fn batch_invert(
+        &self,
+        ctx: &mut Context<F>,
+        vals: &[AssignedValue<F>],
+    ) -> Vec<AssignedValue<F>> {
+        let (first, rest) = match vals.split_first() {
+            Some(split) => split,
+            None => return vec![],
+        };
+        // prefix[i] = vals[0] * ... * vals[i]
+        let mut prefix = Vec::with_capacity(vals.len());
+        prefix.push(*first);
+        for val in rest {
+            let prod = self.mul(ctx, *prefix.last().unwrap(), *val);
+            prefix.push(prod);
+        }
+        // inv = (vals[0] * ... * vals[i])^{-1} going backwards
+        let mut inv = self.assert_not_zero(ctx, *prefix.last().unwrap());
+        let mut invs = vec![inv; vals.len()];
+        for i in (1..vals.len()).rev() {
+            invs[i] = self.mul(ctx, inv, prefix[i - 1]);
+            inv = self.mul(ctx, inv, vals[i]);
+        }
+        invs[0] = inv;
+        invs
+    }
+
+    /// Constrains that the elements of `vals` are pairwise distinct.
+    ///
+    /// Uses `n * (n - 1) / 2` differences whose product is constrained to be non-zero.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `vals`: slice of [AssignedValue]s to be constrained
+    fn assert_pairwise_not_equal(&self, ctx: &mut Context<F>, vals: &[AssignedValue<F>]) {
+        let diffs = vals
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| vals[i + 1..].iter().map(move |b| (*a, *b)))
+            .map(|(a, b)| self.sub(ctx, a, b))
+            .collect::<Vec<_>>();
+        self.assert_nonzero_batch(ctx, &diffs);
+    }
+
     /// Constrains and returns little-endian bit vector representation of `a`.
     ///
     /// Assumes `range_bits <= number of bits in a`.
@@ -868,6 +1308,52 @@ pub trait GateInstructions<F: ScalarField> {
         range_bits: usize,
     ) -> Vec<AssignedValue<F>>;
 
+    /// Constrains that every element of `bits` is a bit and returns `sum_i bits[i] * 2^i`, the inverse of
+    /// [GateInstructions::num_to_bits].
+    ///
+    /// Uses Horner's rule from the most significant bit, so each bit costs a bit check and one [GateInstructions::mul_add].
+    ///
+    /// Assumes `bits.len() <= F::CAPACITY`, otherwise the sum may overflow.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `bits`: little-endian bits of the value
+    fn bits_to_num(&self, ctx: &mut Context<F>, bits: &[AssignedValue<F>]) -> AssignedValue<F> {
+        let (last, rest) = match bits.split_last() {
+            Some(split) => split,
+            None => return ctx.load_zero(),
+        };
+        self.assert_bit(ctx, *last);
+        rest.iter().rev().fold(*last, |acc, bit| {
+            self.assert_bit(ctx, *bit);
+            self.mul_add(ctx, acc, Constant(F::from(2)), *bit)
+        })
+    }
+
+    /// Constrains and returns `sum_i coeffs[i] * x^i`, the evaluation at `x` of the polynomial with coefficients `coeffs`.
+    ///
+    /// Uses Horner's method from the leading coefficient, so a polynomial with `n >= 2` coefficients costs `n - 1`
+    /// [GateInstructions::mul_add] gates, compared to the `n - 2` multiplications for the powers of `x` and the
+    /// [GateInstructions::inner_product] of the naive approach.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `coeffs`: coefficients of the polynomial, lowest degree first
+    /// * `x`: [AssignedValue] point to evaluate at
+    fn horner_eval(
+        &self,
+        ctx: &mut Context<F>,
+        coeffs: &[QuantumCell<F>],
+        x: AssignedValue<F>,
+    ) -> AssignedValue<F> {
+        let (leading, rest) = match coeffs.split_last() {
+            Some(split) => split,
+            None => return ctx.load_zero(),
+        };
+        let (next, rest) = match rest.split_last() {
+            Some(split) => split,
+            None => return ctx.assign_region_last([*leading], []),
+        };
+        let init = self.mul_add(ctx, *leading, x, *next);
+        rest.iter().rev().fold(init, |acc, coeff| self.mul_add(ctx, acc, x, *coeff))
+    }
+
     /// Constrains and computes `a`<sup>`exp`</sup> where both `a, exp` are witnesses. The exponent is computed in the native field `F`.
     ///
     /// Constrains that `exp` has at most `max_bits` bits.
@@ -879,6 +1365,58 @@ pub trait GateInstructions<F: ScalarField> {
         max_bits: usize,
     ) -> AssignedValue<F>;
 
+    /// Constrains and computes `a`<sup>`exp`</sup> where `exp` is a constant given by its little-endian bits `exp_bits`.
+    ///
+    /// Since the exponent is known at circuit construction, square-and-multiply only multiplies for set bits
+    /// and needs no [GateInstructions::select], unlike [GateInstructions::pow_var].
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `a`: [AssignedValue] base
+    /// * `exp_bits`: little-endian bits of the exponent
+    fn pow_by_repeated_squaring(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        exp_bits: &[bool],
+    ) -> AssignedValue<F> {
+        let top = match exp_bits.iter().rposition(|bit| *bit) {
+            Some(top) => top,
+            None => return ctx.load_constant(F::ONE),
+        };
+        let mut acc = a;
+        for bit in exp_bits[..top].iter().rev() {
+            acc = self.mul(ctx, acc, acc);
+            if *bit {
+                acc = self.mul(ctx, acc, a);
+            }
+        }
+        acc
+    }
+
+    /// Constrains and computes `base`<sup>`exp`</sup> for a constant `exp` using a short addition chain.
+    ///
+    /// The chain is computed natively by [addition_chain], so each step is a single [GateInstructions::mul]
+    /// and the number of gates is the chain length. This never uses more gates than
+    /// [GateInstructions::pow_by_repeated_squaring], e.g. 10 instead of 14 multiplications for `exp = 255`.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `base`: [AssignedValue] base
+    /// * `exp`: constant exponent
+    fn field_pow_const(
+        &self,
+        ctx: &mut Context<F>,
+        base: AssignedValue<F>,
+        exp: u64,
+    ) -> AssignedValue<F> {
+        if exp == 0 {
+            return ctx.load_constant(F::ONE);
+        }
+        let mut powers = vec![base];
+        for (i, j) in addition_chain(exp) {
+            let power = self.mul(ctx, powers[i], powers[j]);
+            powers.push(power);
+        }
+        powers.pop().unwrap()
+    }
+
     /// Performs and constrains Lagrange interpolation on `coords` and evaluates the resulting polynomial at `x`.
     ///
     /// Given pairs `coords[i] = (x_i, y_i)`, let `f` be the unique degree `len(coords) - 1` polynomial such that `f(x_i) = y_i` for all `i`.
@@ -903,32 +1441,176 @@ pub trait GateInstructions<F: ScalarField> {
             let sub = self.sub(ctx, Existing(x), Existing(coord.0));
             z = self.mul(ctx, Existing(z), Existing(sub));
         }
-        let mut eval = None;
-        for i in 0..coords.len() {
-            // compute (x - x_i) * Prod_{j != i} (x_i - x_j)
-            let mut denom = self.sub(ctx, Existing(x), Existing(coords[i].0));
-            for j in 0..coords.len() {
-                if i == j {
-                    continue;
+        let denoms = (0..coords.len())
+            .map(|i| {
+                // compute (x - x_i) * Prod_{j != i} (x_i - x_j)
+                let mut denom = self.sub(ctx, Existing(x), Existing(coords[i].0));
+                for j in 0..coords.len() {
+                    if i == j {
+                        continue;
+                    }
+                    let sub = self.sub(ctx, coords[i].0, coords[j].0);
+                    denom = self.mul(ctx, denom, sub);
+                }
+                denom
+            })
+            .collect::<Vec<_>>();
+        // also constrains every denom to be non-zero
+        let denom_invs = self.batch_invert(ctx, &denoms);
+        // sum_i y_i / denom_i
+        let eval = self.inner_product(
+            ctx,
+            coords.iter().map(|coord| Existing(coord.1)),
+            denom_invs.into_iter().map(Existing),
+        );
+        let out = self.mul(ctx, eval, z);
+        (out, z)
+    }
+
+    /// Constrains and returns `f(x)`, where `f` is the unique polynomial of degree less than `domain.len()`
+    /// with `f(domain[i]) = evals[i]` for all `i`.
+    ///
+    /// Unlike [GateInstructions::lagrange_and_eval], the interpolation points are constants, so the Lagrange
+    /// denominators are computed outside the circuit and no inversion is constrained.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `domain`: distinct interpolation points
+    /// * `evals`: values of `f` on `domain`
+    /// * `x`: point to evaluate `f` at
+    ///
+    /// # Assumptions
+    /// * `domain` is non-empty and `domain.len() == evals.len()`
+    fn lagrange_eval_on_domain(
+        &self,
+        ctx: &mut Context<F>,
+        domain: &[F],
+        evals: &[AssignedValue<F>],
+        x: AssignedValue<F>,
+    ) -> AssignedValue<F> {
+        assert!(!domain.is_empty(), "domain should not be empty");
+        assert_eq!(domain.len(), evals.len(), "domain and evals should have the same length");
+        let diffs = domain.iter().map(|x_j| self.sub(ctx, x, Constant(*x_j))).collect_vec();
+        // prefix[i] = Prod_{j < i} (x - x_j) and suffix[i] = Prod_{j > i} (x - x_j), `None` for empty products
+        let mut partial_products = |diffs: &mut dyn Iterator<Item = &AssignedValue<F>>| {
+            let mut acc: Option<AssignedValue<F>> = None;
+            diffs
+                .map(|diff| {
+                    let prev = acc;
+                    acc = Some(acc.map_or(*diff, |acc| self.mul(ctx, acc, *diff)));
+                    prev
+                })
+                .collect_vec()
+        };
+        let prefix = partial_products(&mut diffs.iter());
+        let mut suffix = partial_products(&mut diffs.iter().rev());
+        suffix.reverse();
+
+        let coeffs = domain
+            .iter()
+            .enumerate()
+            .map(|(i, x_i)| {
+                let denom = domain
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .fold(F::ONE, |acc, (_, x_j)| acc * (*x_i - x_j));
+                let weight = Option::<F>::from(denom.invert()).expect("domain should be distinct");
+                let numer = match (prefix[i], suffix[i]) {
+                    (Some(a), Some(b)) => Some(self.mul(ctx, a, b)),
+                    (a, b) => a.or(b),
+                };
+                match numer {
+                    Some(numer) => Existing(self.mul(ctx, numer, Constant(weight))),
+                    None => Constant(weight),
                 }
-                let sub = self.sub(ctx, coords[i].0, coords[j].0);
-                denom = self.mul(ctx, denom, sub);
+            })
+            .collect_vec();
+        self.inner_product(ctx, evals.iter().copied(), coeffs)
+    }
+
+    /// Constrains and returns `(b, a)` if `swap == 1` and `(a, b)` if `swap == 0`, assuming `swap` is boolean.
+    ///
+    /// Shares `b - a` between both outputs: the first is `a + swap * (b - a)` and the second `b - swap * (b - a)`,
+    /// which takes three basic gates instead of two [GateInstructions::select]s.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `a`: first [AssignedValue]
+    /// * `b`: second [AssignedValue]
+    /// * `swap`: [AssignedValue] bit deciding whether to swap `a` and `b`
+    fn conditional_swap(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        b: AssignedValue<F>,
+        swap: AssignedValue<F>,
+    ) -> (AssignedValue<F>, AssignedValue<F>) {
+        let diff = self.sub(ctx, b, a);
+        let first = self.mul_add(ctx, diff, swap, a);
+        let second = self.sub_mul(ctx, b, diff, swap);
+        (first, second)
+    }
+
+    /// Constrains and returns `inputs` routed through a Benes network configured by `control_bits`.
+    ///
+    /// The network on `N` wires consists of an input layer of `N / 2` switches, two Benes networks on `N / 2` wires
+    /// (the top one receives the first output of every switch, the bottom one the second), and an output layer of
+    /// `N / 2` switches which recombine the `i`-th outputs of the two subnetworks into wires `2i, 2i + 1`.
+    /// A switch with control bit `1` swaps its two wires, otherwise it passes them through.
+    ///
+    /// Control bits are consumed in the order: input layer, top subnetwork, bottom subnetwork, output layer.
+    /// Every permutation of `N` wires can be realized by some choice of control bits.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `inputs`: array of [AssignedValue]s to permute
+    /// * `control_bits`: slice of `N * log2(N) - N / 2` [AssignedValue]s, each constrained to be boolean
+    ///
+    /// # Assumptions
+    /// * `N` is a power of two and `N >= 2`
+    fn benes_network<const N: usize>(
+        &self,
+        ctx: &mut Context<F>,
+        inputs: [AssignedValue<F>; N],
+        control_bits: &[AssignedValue<F>],
+    ) -> [AssignedValue<F>; N] {
+        assert!(N >= 2 && N.is_power_of_two(), "benes_network: N must be a power of two >= 2");
+        let log_n = N.trailing_zeros() as usize;
+        assert_eq!(control_bits.len(), N * log_n - N / 2, "benes_network: wrong number of bits");
+
+        fn switch<F: ScalarField>(
+            gate: &(impl GateInstructions<F> + ?Sized),
+            ctx: &mut Context<F>,
+            a: AssignedValue<F>,
+            b: AssignedValue<F>,
+            bit: AssignedValue<F>,
+        ) -> (AssignedValue<F>, AssignedValue<F>) {
+            gate.assert_bit(ctx, bit);
+            gate.conditional_swap(ctx, a, b, bit)
+        }
+
+        fn route<F: ScalarField>(
+            gate: &(impl GateInstructions<F> + ?Sized),
+            ctx: &mut Context<F>,
+            wires: &[AssignedValue<F>],
+            bits: &mut impl Iterator<Item = AssignedValue<F>>,
+        ) -> Vec<AssignedValue<F>> {
+            if wires.len() == 2 {
+                let (a, b) = switch(gate, ctx, wires[0], wires[1], bits.next().unwrap());
+                return vec![a, b];
             }
-            // TODO: batch inversion
-            let is_zero = self.is_zero(ctx, denom);
-            self.assert_is_const(ctx, &is_zero, &F::ZERO);
-
-            // y_i / denom
-            let quot = self.div_unsafe(ctx, coords[i].1, denom);
-            eval = if let Some(eval) = eval {
-                let eval = self.add(ctx, eval, quot);
-                Some(eval)
-            } else {
-                Some(quot)
-            };
+            let (top, bottom): (Vec<_>, Vec<_>) = wires
+                .chunks(2)
+                .map(|pair| switch(gate, ctx, pair[0], pair[1], bits.next().unwrap()))
+                .unzip();
+            let top = route(gate, ctx, &top, bits);
+            let bottom = route(gate, ctx, &bottom, bits);
+            top.into_iter()
+                .zip(bottom)
+                .flat_map(|(a, b)| {
+                    let (a, b) = switch(gate, ctx, a, b, bits.next().unwrap());
+                    [a, b]
+                })
+                .collect()
         }
-        let out = self.mul(ctx, eval.unwrap(), z);
-        (out, z)
+
+        let mut bits = control_bits.iter().copied();
+        route(self, ctx, &inputs, &mut bits).try_into().unwrap()
     }
 }
 
@@ -971,7 +1653,7 @@ impl<F: ScalarField> GateChip<F> {
     /// * `ctx`: [Context] of the circuit
     /// * `a`: Iterator of [QuantumCell] values
     /// * `b`: Iterator of [QuantumCell] values to take inner product of `a` by
-    fn inner_product_simple<QA>(
+    fn inner_product_impl<QA>(
         &self,
         ctx: &mut Context<F>,
         a: impl IntoIterator<Item = QA>,
@@ -1034,7 +1716,7 @@ impl<F: ScalarField> GateInstructions<F> for GateChip<F> {
     where
         QA: Into<QuantumCell<F>>,
     {
-        self.inner_product_simple(ctx, a, b);
+        self.inner_product_impl(ctx, a, b);
         ctx.last().unwrap()
     }
 
@@ -1060,8 +1742,8 @@ impl<F: ScalarField> GateInstructions<F> for GateChip<F> {
         let a = a.into_iter();
         let (len, hi) = a.size_hint();
         assert_eq!(Some(len), hi);
-        let row_offset = ctx.advice.len();
-        let b_starts_with_one = self.inner_product_simple(ctx, a, b);
+        let row_offset = ctx.advice_len();
+        let b_starts_with_one = self.inner_product_impl(ctx, a, b);
         let a_last = if b_starts_with_one {
             if len == 1 {
                 ctx.get(row_offset as isize)
@@ -1096,8 +1778,8 @@ impl<F: ScalarField> GateInstructions<F> for GateChip<F> {
     {
         let a = a.into_iter().collect_vec();
         let len = a.len();
-        let row_offset = ctx.advice.len();
-        let b_starts_with_one = self.inner_product_simple(ctx, a, b);
+        let row_offset = ctx.advice_len();
+        let b_starts_with_one = self.inner_product_impl(ctx, a, b);
         let a_assigned = (0..len)
             .map(|i| {
                 if b_starts_with_one {
@@ -1131,13 +1813,13 @@ impl<F: ScalarField> GateInstructions<F> for GateChip<F> {
     where
         QA: Into<QuantumCell<F>>,
     {
-        let row_offset = ctx.advice.len();
-        let b_starts_with_one = self.inner_product_simple(ctx, a, b);
+        let row_offset = ctx.advice_len();
+        let b_starts_with_one = self.inner_product_impl(ctx, a, b);
         if b_starts_with_one {
-            Box::new((row_offset..ctx.advice.len()).step_by(3).map(|i| ctx.get(i as isize)))
+            Box::new((row_offset..ctx.advice_len()).step_by(3).map(|i| ctx.get(i as isize)))
         } else {
             // in this case the first assignment is 0 so we skip it
-            Box::new((row_offset..ctx.advice.len()).step_by(3).skip(1).map(|i| ctx.get(i as isize)))
+            Box::new((row_offset..ctx.advice_len()).step_by(3).skip(1).map(|i| ctx.get(i as isize)))
         }
     }
 
@@ -1255,7 +1937,7 @@ impl<F: ScalarField> GateInstructions<F> for GateChip<F> {
         let bits = a.value().to_u64_limbs(range_bits, 1).into_iter().map(|x| Witness(F::from(x)));
 
         let mut bit_cells = Vec::with_capacity(range_bits);
-        let row_offset = ctx.advice.len();
+        let row_offset = ctx.advice_len();
         let acc = self.inner_product(
             ctx,
             bits,
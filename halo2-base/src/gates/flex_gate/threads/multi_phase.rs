@@ -4,7 +4,10 @@ use itertools::Itertools;
 use crate::{
     gates::{circuit::CircuitBuilderStage, flex_gate::FlexGateConfigParams},
     utils::ScalarField,
-    virtual_region::copy_constraints::SharedCopyConstraintManager,
+    virtual_region::{
+        copy_constraints::SharedCopyConstraintManager,
+        manager::{CircuitStats, VirtualRegionManager},
+    },
     Context,
 };
 
@@ -124,6 +127,20 @@ impl<F: ScalarField> MultiPhaseCoreManager<F> {
         GateStatistics { total_advice_per_phase, total_fixed }
     }
 
+    /// Returns a [CircuitStats] summary of the virtual regions of all phases, see [VirtualRegionManager::statistics].
+    ///
+    /// Constants used in several phases are only counted once.
+    pub fn circuit_stats(&self) -> CircuitStats {
+        let type_ids = self.phase_manager.iter().map(|pm| pm.type_of()).collect_vec();
+        let (num_copy_constraints, num_constants) =
+            self.copy_manager.lock().unwrap().count_region_equalities(&type_ids);
+        let (num_advice_cells, num_selector_on) =
+            self.phase_manager.iter().map(|pm| pm.statistics()).fold((0, 0), |(a, q), stats| {
+                (a + stats.num_advice_cells, q + stats.num_selector_on)
+            });
+        CircuitStats { num_advice_cells, num_selector_on, num_copy_constraints, num_constants }
+    }
+
     /// Auto-calculates configuration parameters for the circuit
     ///
     /// * `k`: The number of in the circuit (i.e. numeber of rows = 2<sup>k</sup>)
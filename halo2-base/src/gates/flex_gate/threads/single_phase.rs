@@ -1,4 +1,4 @@
-use std::{any::TypeId, cell::RefCell};
+use std::{any::TypeId, cell::RefCell, sync::Arc};
 
 use getset::CopyGetters;
 
@@ -17,7 +17,7 @@ use crate::{
         circuit::{Region, Value},
         plonk::{FirstPhase, SecondPhase, ThirdPhase},
     },
-    virtual_region::manager::VirtualRegionManager,
+    virtual_region::manager::{CircuitStats, VirtualRegionManager},
 };
 
 /// Virtual region manager for [Vec<BasicGateConfig>] in a single challenge phase.
@@ -125,6 +125,11 @@ impl<F: ScalarField> SinglePhaseCoreManager<F> {
 
     /// Creates new context but does not append to `self.threads`
     pub fn new_context(&self, context_id: usize) -> Context<F> {
+        #[cfg(feature = "serde")]
+        crate::utils::serialization::register_region(
+            self.type_of(),
+            format!("SinglePhaseCoreManager<{}>/phase{}", F::MODULUS, self.phase),
+        );
         Context::new(
             self.witness_gen_only,
             self.phase,
@@ -142,9 +147,45 @@ impl<F: ScalarField> SinglePhaseCoreManager<F> {
         self.threads.last_mut().unwrap()
     }
 
+    /// Merges `other` into `self` by appending the threads of `other` after those of `self`.
+    ///
+    /// The threads of `other` are renumbered to follow the threads of `self`, and the copy constraints of `other` are
+    /// moved into the copy manager of `self` with their cells renumbered accordingly, see [CopyConstraintManager::append].
+    /// Any [AssignedValue](crate::AssignedValue) previously obtained from `other` refers to the old numbering and
+    /// should not be used after merging.
+    ///
+    /// Only this virtual region is merged: lookups requested by the threads of `other` must be handled separately.
+    /// The break points are reset, so the merged region must be laid out again during keygen.
+    ///
+    /// # Panics
+    /// If `self` and `other` are in different phases, differ in `witness_gen_only`, share the same copy manager, or if
+    /// the copy manager of `other` has cells in another virtual region.
+    pub fn merge(mut self, other: Self) -> Self {
+        assert_eq!(self.phase, other.phase, "cannot merge regions of different phases");
+        assert_eq!(self.witness_gen_only, other.witness_gen_only);
+        // cells of two regions of the same type cannot be told apart in a shared copy manager
+        assert!(
+            !Arc::ptr_eq(&self.copy_manager, &other.copy_manager),
+            "cannot merge regions sharing a copy manager"
+        );
+        let context_id_offset = self.thread_count();
+        self.copy_manager.lock().unwrap().append(
+            &mut other.copy_manager.lock().unwrap(),
+            self.type_of(),
+            context_id_offset,
+        );
+        for mut ctx in other.threads {
+            ctx.renumber(ctx.id() + context_id_offset);
+            ctx.copy_manager = self.copy_manager.clone();
+            self.threads.push(ctx);
+        }
+        self.break_points = RefCell::new(None);
+        self
+    }
+
     /// Returns total advice cells
     pub fn total_advice(&self) -> usize {
-        self.threads.iter().map(|ctx| ctx.advice.len()).sum::<usize>()
+        self.threads.iter().map(|ctx| ctx.advice_len()).sum::<usize>()
     }
 }
 
@@ -174,6 +215,24 @@ impl<F: ScalarField> VirtualRegionManager<F> for SinglePhaseCoreManager<F> {
             }
         }
     }
+
+    /// An advice equality is attributed to the region of its first cell, and a constant equality to the region of its
+    /// advice cell.
+    fn statistics(&self) -> CircuitStats {
+        let num_selector_on = self
+            .threads
+            .iter()
+            .map(|ctx| ctx.selector().iter().filter(|&&q| q).count())
+            .sum::<usize>();
+        let (num_copy_constraints, num_constants) =
+            self.copy_manager.lock().unwrap().count_region_equalities(&[self.type_of()]);
+        CircuitStats {
+            num_advice_cells: self.total_advice(),
+            num_selector_on,
+            num_copy_constraints,
+            num_constants,
+        }
+    }
 }
 
 /// Assigns all virtual `threads` to the physical columns in `basic_gates` and returns the break points.
@@ -204,15 +263,15 @@ pub fn assign_with_constraints<F: ScalarField, const ROTATIONS: usize>(
     let mut gate_index = 0;
     let mut row_offset = 0;
     for ctx in threads {
-        if ctx.advice.is_empty() {
+        if ctx.advice().is_empty() {
             continue;
         }
         let mut basic_gate = basic_gates
                         .get(gate_index)
                         .unwrap_or_else(|| panic!("NOT ENOUGH ADVICE COLUMNS. Perhaps blinding factors were not taken into account. The max non-poisoned rows is {max_rows}"));
-        assert_eq!(ctx.selector.len(), ctx.advice.len());
+        assert_eq!(ctx.selector_len(), ctx.advice_len());
 
-        for (i, (advice, &q)) in ctx.advice.iter().zip(ctx.selector.iter()).enumerate() {
+        for (i, (advice, &q)) in ctx.advice().iter().zip(ctx.selector().iter()).enumerate() {
             let column = basic_gate.value;
             let value = if use_unknown { Value::unknown() } else { Value::known(advice) };
             #[cfg(feature = "halo2-axiom")]
@@ -237,7 +296,7 @@ pub fn assign_with_constraints<F: ScalarField, const ROTATIONS: usize>(
                 if ROTATIONS > 1 && i + 2 >= ROTATIONS {
                     for delta in 1..ROTATIONS - 1 {
                         assert!(
-                            !ctx.selector[i - delta],
+                            !ctx.selector()[i - delta],
                             "We do not support overlaps with delta = {delta}"
                         );
                     }
@@ -284,7 +343,7 @@ pub fn assign_witnesses<F: ScalarField>(
 ) {
     if basic_gates.is_empty() {
         assert_eq!(
-            threads.iter().map(|ctx| ctx.advice.len()).sum::<usize>(),
+            threads.iter().map(|ctx| ctx.advice_len()).sum::<usize>(),
             0,
             "Trying to assign threads in a phase with no columns"
         );
@@ -300,7 +359,7 @@ pub fn assign_witnesses<F: ScalarField>(
 
     for ctx in threads {
         // Assign advice values to the advice columns in each [Context]
-        for advice in &ctx.advice {
+        for advice in ctx.advice() {
             raw_assign_advice(region, column, row_offset, Value::known(advice));
 
             if break_point == Some(row_offset) {
@@ -0,0 +1,54 @@
+use crate::{gates::GateInstructions, utils::ScalarField, AssignedValue, Context};
+
+use getset::{CopyGetters, Getters};
+
+/// Scalar part of a Nova-style relaxed instance, folded in-circuit by a verifier.
+///
+/// A relaxed instance is `(u, x)` together with commitments to the witness and error vectors.
+/// Folding a fresh instance `(1, x_new)` with challenge `r` gives `(u + r, x + r * x_new)`.
+/// The commitments are curve points and must be folded separately by the caller, e.g. with `halo2-ecc`.
+#[derive(Clone, Debug, CopyGetters, Getters)]
+pub struct FoldingAccumulator<F: ScalarField> {
+    /// The relaxation scalar `u`.
+    #[getset(get_copy = "pub")]
+    u: AssignedValue<F>,
+    /// The accumulated public instance `x`.
+    #[getset(get = "pub")]
+    instance: Vec<AssignedValue<F>>,
+}
+
+impl<F: ScalarField> FoldingAccumulator<F> {
+    /// Creates an accumulator from a relaxed instance `(u, instance)`.
+    pub fn new(u: AssignedValue<F>, instance: Vec<AssignedValue<F>>) -> Self {
+        Self { u, instance }
+    }
+
+    /// Creates an accumulator from a fresh (non-relaxed) instance, i.e. with `u = 1`.
+    pub fn from_instance(ctx: &mut Context<F>, instance: Vec<AssignedValue<F>>) -> Self {
+        Self::new(ctx.load_constant(F::ONE), instance)
+    }
+
+    /// Constrains and returns the accumulator `(u + challenge, x + challenge * new_instance)`
+    /// obtained by folding the fresh instance `new_instance` into `self`.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `gate`: [GateInstructions] to use
+    /// * `new_instance`: public instance of the fresh instance, with the same length as [`Self::instance`]
+    /// * `challenge`: [AssignedValue] folding challenge
+    pub fn fold(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        new_instance: &[AssignedValue<F>],
+        challenge: AssignedValue<F>,
+    ) -> Self {
+        assert_eq!(self.instance.len(), new_instance.len(), "instance length mismatch");
+        let u = gate.add(ctx, self.u, challenge);
+        let instance = self
+            .instance
+            .iter()
+            .zip(new_instance)
+            .map(|(acc, new)| gate.mul_add(ctx, challenge, *new, *acc))
+            .collect();
+        Self { u, instance }
+    }
+}
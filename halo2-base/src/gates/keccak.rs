@@ -0,0 +1,208 @@
+use crate::{
+    ff::Field,
+    gates::{flex_gate::GateInstructions, xor::XorInstructions, GateChip},
+    safe_types::{SafeByte, SafeBytes32, SafeTypeChip},
+    utils::ScalarField,
+    Context,
+    QuantumCell::Constant,
+};
+
+/// Number of input bytes absorbed by each Keccak-f permutation in Keccak-256.
+pub const KECCAK256_RATE: usize = 136;
+
+/// Number of bytes in a Keccak-256 digest.
+pub const KECCAK256_OUTPUT_LEN: usize = 32;
+
+/// Round constants of the iota step, one per round of Keccak-f\[1600\].
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation offsets of the rho step, for the lane at `(x, y)` stored at index `x + 5 * y`.
+const ROTATIONS: [usize; 25] =
+    [0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14];
+
+/// A 64-bit lane of the Keccak state as 8 little-endian bytes.
+pub type KeccakLane<F> = [SafeByte<F>; 8];
+
+/// Keccak state of 25 lanes, with the lane at `(x, y)` stored at index `x + 5 * y`.
+pub type KeccakState<F> = [KeccakLane<F>; 25];
+
+/// Chip for the Keccak-256 hash of a fixed-length byte string.
+///
+/// Lanes are kept as bytes so that every XOR is a single call to [XorInstructions::xor_byte],
+/// a single lookup with [XorChip](super::xor::XorChip). AND is derived from XOR through
+/// `a + b = (a ^ b) + 2 * (a & b)`, and rotating a lane moves whole bytes for free and splits each byte once
+/// for the remaining bit shift.
+///
+/// The split parts of each byte are constrained to be bytes by XOR with zero, so this relies on
+/// [XorInstructions::xor_byte] constraining its inputs to be bytes.
+#[derive(Clone, Debug)]
+pub struct KeccakChip<'a, F: ScalarField, X: XorInstructions<F>> {
+    gate: &'a GateChip<F>,
+    xor: &'a X,
+}
+
+impl<'a, F: ScalarField, X: XorInstructions<F>> KeccakChip<'a, F, X> {
+    /// Creates a new [KeccakChip] using `gate` for arithmetic and `xor` for XOR of bytes.
+    pub fn new(gate: &'a GateChip<F>, xor: &'a X) -> Self {
+        Self { gate, xor }
+    }
+
+    /// Constrains and returns the Keccak-256 digest of `input`.
+    ///
+    /// The length of `input` is fixed at circuit construction, so the padding is made of constants.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `input`: bytes to hash
+    pub fn keccak256(
+        &self,
+        ctx: &mut Context<F>,
+        input: &[SafeByte<F>],
+    ) -> [SafeByte<F>; KECCAK256_OUTPUT_LEN] {
+        let num_blocks = input.len() / KECCAK256_RATE + 1;
+        let mut padding = vec![0u8; num_blocks * KECCAK256_RATE - input.len()];
+        padding[0] = 0x01;
+        *padding.last_mut().unwrap() |= 0x80;
+        let padded = input
+            .iter()
+            .copied()
+            .chain(padding.into_iter().map(|byte| self.load_constant_byte(ctx, byte)))
+            .collect::<Vec<_>>();
+
+        let zero = self.load_constant_byte(ctx, 0);
+        let mut state: KeccakState<F> = [[zero; 8]; 25];
+        for (block_idx, block) in padded.chunks(KECCAK256_RATE).enumerate() {
+            for (i, byte) in block.iter().enumerate() {
+                let lane = &mut state[i / 8][i % 8];
+                // the state starts as zero, so the first block is copied in
+                *lane = if block_idx == 0 { *byte } else { self.xor.xor_byte(ctx, *lane, *byte) };
+            }
+            state = self.keccak_f(ctx, state);
+        }
+        core::array::from_fn(|i| state[i / 8][i % 8])
+    }
+
+    /// Constrains and returns the Keccak-256 digest of `input` as a [SafeBytes32].
+    pub fn keccak256_bytes32(&self, ctx: &mut Context<F>, input: &[SafeByte<F>]) -> SafeBytes32<F> {
+        SafeBytes32::from_bytes(self.keccak256(ctx, input))
+    }
+
+    /// Constrains and returns the Keccak-f\[1600\] permutation of `state`.
+    pub fn keccak_f(&self, ctx: &mut Context<F>, mut state: KeccakState<F>) -> KeccakState<F> {
+        for rc in ROUND_CONSTANTS {
+            // theta
+            let c: [KeccakLane<F>; 5] = core::array::from_fn(|x| {
+                (1..5).fold(state[x], |acc, y| self.xor_lane(ctx, acc, state[x + 5 * y]))
+            });
+            for x in 0..5 {
+                let rotated = self.rotate_lane(ctx, c[(x + 1) % 5], 1);
+                let d = self.xor_lane(ctx, c[(x + 4) % 5], rotated);
+                for y in 0..5 {
+                    state[x + 5 * y] = self.xor_lane(ctx, state[x + 5 * y], d);
+                }
+            }
+            // rho and pi
+            let mut b = state;
+            for x in 0..5 {
+                for y in 0..5 {
+                    b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                        self.rotate_lane(ctx, state[x + 5 * y], ROTATIONS[x + 5 * y]);
+                }
+            }
+            // chi
+            for x in 0..5 {
+                for y in 0..5 {
+                    let (b1, b2) = (b[(x + 1) % 5 + 5 * y], b[(x + 2) % 5 + 5 * y]);
+                    let t: KeccakLane<F> = core::array::from_fn(|i| {
+                        let not_b1 =
+                            self.gate.sub(ctx, Constant(F::from(0xffu64)), *b1[i].as_ref());
+                        self.and_byte(ctx, SafeTypeChip::unsafe_to_byte(not_b1), b2[i])
+                    });
+                    state[x + 5 * y] = self.xor_lane(ctx, b[x + 5 * y], t);
+                }
+            }
+            // iota: XOR with zero bytes of the round constant is the identity
+            for (i, rc_byte) in rc.to_le_bytes().into_iter().enumerate() {
+                if rc_byte != 0 {
+                    let rc_byte = self.load_constant_byte(ctx, rc_byte);
+                    state[0][i] = self.xor.xor_byte(ctx, state[0][i], rc_byte);
+                }
+            }
+        }
+        state
+    }
+
+    fn load_constant_byte(&self, ctx: &mut Context<F>, byte: u8) -> SafeByte<F> {
+        SafeTypeChip::unsafe_to_byte(ctx.load_constant(F::from(byte as u64)))
+    }
+
+    fn xor_lane(&self, ctx: &mut Context<F>, a: KeccakLane<F>, b: KeccakLane<F>) -> KeccakLane<F> {
+        core::array::from_fn(|i| self.xor.xor_byte(ctx, a[i], b[i]))
+    }
+
+    /// Constrains and returns `a & b`, using `a + b = (a ^ b) + 2 * (a & b)`.
+    fn and_byte(&self, ctx: &mut Context<F>, a: SafeByte<F>, b: SafeByte<F>) -> SafeByte<F> {
+        let (a_val, b_val) = (*a.as_ref(), *b.as_ref());
+        let xor = self.xor.xor_byte(ctx, a, b);
+        let sum = self.gate.add(ctx, a_val, b_val);
+        let diff = self.gate.sub(ctx, sum, *xor.as_ref());
+        let and = self.gate.mul(ctx, diff, Constant(F::from(2u64).invert().unwrap()));
+        // `a ^ b` is constrained, so this is exactly `a & b`
+        SafeTypeChip::unsafe_to_byte(and)
+    }
+
+    /// Constrains and returns the left rotation of the 64-bit `lane` by `bits`.
+    fn rotate_lane(&self, ctx: &mut Context<F>, lane: KeccakLane<F>, bits: usize) -> KeccakLane<F> {
+        let (shift_bytes, shift) = (bits / 8, bits % 8);
+        let lane: KeccakLane<F> = core::array::from_fn(|i| lane[(i + 8 - shift_bytes) % 8]);
+        if shift == 0 {
+            return lane;
+        }
+        // split each byte `b` into `hi = b >> (8 - shift)` and `lo = (b << shift) & 0xff`,
+        // constrained by `b * 2^shift = hi * 2^8 + lo` with both parts bytes
+        let zero = self.load_constant_byte(ctx, 0);
+        let parts = lane
+            .iter()
+            .map(|byte| {
+                let b = byte.as_ref().value().get_lower_32() as u8;
+                let [hi, lo] = [b >> (8 - shift), b << shift].map(|v| {
+                    let v = SafeTypeChip::unsafe_to_byte(ctx.load_witness(F::from(v as u64)));
+                    self.xor.xor_byte(ctx, v, zero)
+                });
+                let recomposed =
+                    self.gate.mul_add(ctx, *hi.as_ref(), Constant(F::from(256u64)), *lo.as_ref());
+                let shifted = self.gate.mul(ctx, *byte.as_ref(), Constant(F::from(1u64 << shift)));
+                ctx.constrain_equal(&recomposed, &shifted);
+                (hi, lo)
+            })
+            .collect::<Vec<_>>();
+        // the low `shift` bits of `lo` are zero and `hi < 2^shift`, so the sum is a byte
+        core::array::from_fn(|i| {
+            let out = self.gate.add(ctx, *parts[i].1.as_ref(), *parts[(i + 7) % 8].0.as_ref());
+            SafeTypeChip::unsafe_to_byte(out)
+        })
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    utils::ScalarField, virtual_region::lookups::LookupAnyManager, AssignedValue, Context,
+};
+
+/// Number of columns of the S-box lookup: a tag that is `1` on every table row, the input byte, and the output byte.
+pub const AES_SBOX_LOOKUP_WIDTH: usize = 3;
+
+/// Multiplication in `GF(2^8)` modulo the AES polynomial `x^8 + x^4 + x^3 + x + 1`.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut prod = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            prod ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    prod
+}
+
+/// Returns the AES S-box of `x`: its inverse in `GF(2^8)` (with `0` mapping to `0`), followed by the AES affine map.
+pub fn aes_sbox(x: u8) -> u8 {
+    let inv = if x == 0 { 0 } else { (1..=255).find(|&y| gf_mul(x, y) == 1).unwrap() };
+    inv ^ inv.rotate_left(1) ^ inv.rotate_left(2) ^ inv.rotate_left(3) ^ inv.rotate_left(4) ^ 0x63
+}
+
+/// Returns the rows `(1, x, aes_sbox(x))` of the S-box lookup table, for all bytes `x`.
+///
+/// The tag column separates the table from the all-zero row, since `aes_sbox(0) != 0`.
+pub fn aes_sbox_table<F: ScalarField>() -> Vec<[F; AES_SBOX_LOOKUP_WIDTH]> {
+    (0..=255u8).map(|x| [F::ONE, F::from(x as u64), F::from(aes_sbox(x) as u64)]).collect()
+}
+
+/// Chip for the AES S-box, using a lookup into [aes_sbox_table].
+///
+/// The lookup should be configured with a [LookupArgument](super::LookupArgument) of
+/// [AES_SBOX_LOOKUP_WIDTH] table columns, and `lookup_manager` assigned to its input columns.
+#[derive(Clone, Debug)]
+pub struct AesSboxChip<F: ScalarField> {
+    /// Manager for the cells to look up in the S-box table.
+    pub lookup_manager: LookupAnyManager<F, AES_SBOX_LOOKUP_WIDTH>,
+}
+
+impl<F: ScalarField> AesSboxChip<F> {
+    /// Creates a new [AesSboxChip] that adds lookups to `lookup_manager`.
+    pub fn new(lookup_manager: LookupAnyManager<F, AES_SBOX_LOOKUP_WIDTH>) -> Self {
+        Self { lookup_manager }
+    }
+
+    /// Constrains and returns the AES S-box of the byte `x`.
+    ///
+    /// Also constrains `x` to be a byte.
+    pub fn sbox(&self, ctx: &mut Context<F>, x: AssignedValue<F>) -> AssignedValue<F> {
+        let y = aes_sbox(x.value().get_lower_32() as u8);
+        let y = ctx.load_witness(F::from(y as u64));
+        let tag = ctx.load_constant(F::ONE);
+        self.lookup_manager.add_lookup(ctx.tag(), [tag, x, y]);
+        y
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{
+    safe_types::{SafeByte, SafeBytes32, SafeTypeChip},
+    utils::ScalarField,
+    virtual_region::lookups::LookupAnyManager,
+    Context,
+};
+
+/// Number of columns of the bitwise lookup: the operation, the two input bytes, and the output byte.
+pub const BITWISE_LOOKUP_WIDTH: usize = 4;
+
+/// Bitwise operation on bytes supported by [BitwiseChip].
+///
+/// The discriminant is the value of the operation column of [bitwise_table]. It is never `0`,
+/// which separates the table from the all-zero row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitwiseOp {
+    /// Bitwise AND
+    And = 1,
+    /// Bitwise OR
+    Or = 2,
+    /// Bitwise XOR
+    Xor = 3,
+}
+
+impl BitwiseOp {
+    /// All supported operations, in the order they appear in [bitwise_table].
+    pub const ALL: [BitwiseOp; 3] = [BitwiseOp::And, BitwiseOp::Or, BitwiseOp::Xor];
+
+    /// Returns the result of the operation on `a` and `b`.
+    pub fn eval(self, a: u8, b: u8) -> u8 {
+        match self {
+            BitwiseOp::And => a & b,
+            BitwiseOp::Or => a | b,
+            BitwiseOp::Xor => a ^ b,
+        }
+    }
+}
+
+/// Returns the rows `(op, a, b, op(a, b))` of the bitwise lookup table, for all operations and all bytes `a, b`.
+///
+/// The table has `3 * 2^16` rows, so the circuit needs `k >= 18`.
+pub fn bitwise_table<F: ScalarField>() -> Vec<[F; BITWISE_LOOKUP_WIDTH]> {
+    BitwiseOp::ALL
+        .into_iter()
+        .flat_map(|op| {
+            (0..=255u8).flat_map(move |a| {
+                (0..=255u8).map(move |b| {
+                    [op as u64, a as u64, b as u64, op.eval(a, b) as u64].map(F::from)
+                })
+            })
+        })
+        .collect()
+}
+
+/// Chip for bitwise operations on bytes, using a lookup into [bitwise_table].
+///
+/// The lookup should be configured with a [LookupArgument](super::LookupArgument) of
+/// [BITWISE_LOOKUP_WIDTH] table columns, and `lookup_manager` assigned to its input columns.
+#[derive(Clone, Debug)]
+pub struct BitwiseChip<F: ScalarField> {
+    /// Manager for the cells to look up in the bitwise table.
+    pub lookup_manager: LookupAnyManager<F, BITWISE_LOOKUP_WIDTH>,
+}
+
+impl<F: ScalarField> BitwiseChip<F> {
+    /// Creates a new [BitwiseChip] that adds lookups to `lookup_manager`.
+    pub fn new(lookup_manager: LookupAnyManager<F, BITWISE_LOOKUP_WIDTH>) -> Self {
+        Self { lookup_manager }
+    }
+
+    /// Constrains and returns `op(a, b)` with a single lookup.
+    pub fn byte_op(
+        &self,
+        ctx: &mut Context<F>,
+        op: BitwiseOp,
+        a: SafeByte<F>,
+        b: SafeByte<F>,
+    ) -> SafeByte<F> {
+        let (a, b) = (*a.as_ref(), *b.as_ref());
+        let out = op.eval(a.value().get_lower_32() as u8, b.value().get_lower_32() as u8);
+        let out = ctx.load_witness(F::from(out as u64));
+        let op = ctx.load_constant(F::from(op as u64));
+        self.lookup_manager.add_lookup(ctx.tag(), [op, a, b, out]);
+        // the table only contains bytes
+        SafeTypeChip::unsafe_to_byte(out)
+    }
+
+    /// Constrains and returns `a & b`.
+    pub fn and_byte(&self, ctx: &mut Context<F>, a: SafeByte<F>, b: SafeByte<F>) -> SafeByte<F> {
+        self.byte_op(ctx, BitwiseOp::And, a, b)
+    }
+
+    /// Constrains and returns `a | b`.
+    pub fn or_byte(&self, ctx: &mut Context<F>, a: SafeByte<F>, b: SafeByte<F>) -> SafeByte<F> {
+        self.byte_op(ctx, BitwiseOp::Or, a, b)
+    }
+
+    /// Constrains and returns `a ^ b`.
+    pub fn xor_byte(&self, ctx: &mut Context<F>, a: SafeByte<F>, b: SafeByte<F>) -> SafeByte<F> {
+        self.byte_op(ctx, BitwiseOp::Xor, a, b)
+    }
+
+    /// Constrains and returns `op` applied to `a` and `b` byte by byte, with 32 lookups.
+    pub fn bytes32_op(
+        &self,
+        ctx: &mut Context<F>,
+        op: BitwiseOp,
+        a: &SafeBytes32<F>,
+        b: &SafeBytes32<F>,
+    ) -> SafeBytes32<F> {
+        let (a, b) = (a.to_bytes(), b.to_bytes());
+        SafeBytes32::from_bytes(core::array::from_fn(|i| self.byte_op(ctx, op, a[i], b[i])))
+    }
+
+    /// Constrains and returns `a & b`.
+    pub fn and_bytes32(
+        &self,
+        ctx: &mut Context<F>,
+        a: &SafeBytes32<F>,
+        b: &SafeBytes32<F>,
+    ) -> SafeBytes32<F> {
+        self.bytes32_op(ctx, BitwiseOp::And, a, b)
+    }
+
+    /// Constrains and returns `a | b`.
+    pub fn or_bytes32(
+        &self,
+        ctx: &mut Context<F>,
+        a: &SafeBytes32<F>,
+        b: &SafeBytes32<F>,
+    ) -> SafeBytes32<F> {
+        self.bytes32_op(ctx, BitwiseOp::Or, a, b)
+    }
+
+    /// Constrains and returns `a ^ b`.
+    pub fn xor_bytes32(
+        &self,
+        ctx: &mut Context<F>,
+        a: &SafeBytes32<F>,
+        b: &SafeBytes32<F>,
+    ) -> SafeBytes32<F> {
+        self.bytes32_op(ctx, BitwiseOp::Xor, a, b)
+    }
+}
@@ -0,0 +1,135 @@
+use std::marker::PhantomData;
+
+use crate::halo2_proofs::{
+    circuit::Region,
+    plonk::{Advice, Column, ConstraintSystem, Fixed},
+    poly::Rotation,
+};
+use crate::utils::{halo2::raw_assign_fixed, ScalarField};
+
+/// Module for a sample AES S-box lookup gadget
+pub mod aes;
+/// Module for bitwise operations on bytes using a lookup table
+pub mod bitwise;
+
+/// Builder for a [LookupArgument] of advice columns into a table of fixed columns.
+///
+/// Input columns are grouped in consecutive chunks of as many columns as the table has, and every row of each
+/// chunk is constrained to be a row of the table. Adding more chunks gives more rows to copy cells to, as the
+/// lookup is always on and needs no selector.
+#[derive(Clone, Debug)]
+pub struct LookupArgumentBuilder<F: ScalarField> {
+    name: &'static str,
+    inputs: Vec<Column<Advice>>,
+    table: Vec<Column<Fixed>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: ScalarField> LookupArgumentBuilder<F> {
+    /// Creates a new [LookupArgumentBuilder] with no columns.
+    /// * name: name of the lookup argument in the [ConstraintSystem]
+    pub fn new(name: &'static str) -> Self {
+        Self { name, inputs: vec![], table: vec![], _marker: PhantomData }
+    }
+
+    /// Adds an advice column whose cells are looked up in the table.
+    pub fn add_input_column(mut self, col: Column<Advice>) -> Self {
+        self.inputs.push(col);
+        self
+    }
+
+    /// Adds a fixed column to the table.
+    pub fn add_table_column(mut self, col: Column<Fixed>) -> Self {
+        self.table.push(col);
+        self
+    }
+
+    /// Returns the [LookupArgument] with the added columns.
+    ///
+    /// Panics if there are no table columns or the number of input columns is not a positive multiple of it.
+    pub fn build(self) -> LookupArgument<F> {
+        assert!(!self.table.is_empty(), "lookup table must have at least one column");
+        assert!(
+            !self.inputs.is_empty() && self.inputs.len() % self.table.len() == 0,
+            "number of input columns must be a positive multiple of the number of table columns"
+        );
+        LookupArgument {
+            name: self.name,
+            inputs: self.inputs,
+            table: self.table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Lookup of chunks of advice columns into a table of fixed columns, created with [LookupArgumentBuilder].
+///
+/// Unassigned rows of the input columns are zero, so the table must contain the all-zero row, which
+/// every fixed column has past its assigned rows. Gadgets for which a zero row is a valid input should
+/// add a tag column that is `1` on every row of the table and look up a constant `1` alongside their cells.
+#[derive(Clone, Debug)]
+pub struct LookupArgument<F: ScalarField> {
+    name: &'static str,
+    inputs: Vec<Column<Advice>>,
+    table: Vec<Column<Fixed>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: ScalarField> LookupArgument<F> {
+    /// Enables equality on the input columns and adds a lookup for each chunk of input columns to `meta`.
+    pub fn configure(&self, meta: &mut ConstraintSystem<F>) {
+        for &col in &self.inputs {
+            meta.enable_equality(col);
+        }
+        for chunk in self.inputs.chunks(self.table.len()) {
+            meta.lookup_any(self.name, |meta| {
+                chunk
+                    .iter()
+                    .zip(&self.table)
+                    .map(|(&input, &table)| {
+                        (
+                            meta.query_advice(input, Rotation::cur()),
+                            meta.query_fixed(table, Rotation::cur()),
+                        )
+                    })
+                    .collect()
+            });
+        }
+    }
+
+    /// Returns the number of columns of the table.
+    pub fn width(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns the chunks of input columns, in the format expected by
+    /// [LookupAnyManager](crate::virtual_region::lookups::LookupAnyManager).
+    ///
+    /// Panics if `WIDTH` is not the number of table columns.
+    pub fn input_columns<const WIDTH: usize>(&self) -> Vec<[Column<Advice>; WIDTH]> {
+        assert_eq!(WIDTH, self.width(), "WIDTH must equal the number of table columns");
+        self.inputs.chunks(WIDTH).map(|chunk| chunk.try_into().unwrap()).collect()
+    }
+
+    /// Returns the table columns.
+    pub fn table_columns(&self) -> &[Column<Fixed>] {
+        &self.table
+    }
+
+    /// Assigns `rows` to the table, starting from offset 0.
+    ///
+    /// Panics if a row does not have one value per table column.
+    pub fn assign_table<R: AsRef<[F]>>(
+        &self,
+        region: &mut Region<F>,
+        rows: impl IntoIterator<Item = R>,
+    ) {
+        for (offset, row) in rows.into_iter().enumerate() {
+            let row = row.as_ref();
+            assert_eq!(row.len(), self.width(), "table row has the wrong number of columns");
+            for (&col, &value) in self.table.iter().zip(row) {
+                raw_assign_fixed(region, col, offset, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    gates::{GateInstructions, RangeInstructions},
+    poseidon::{hasher::PoseidonHasher, PoseidonChip},
+    utils::{BigPrimeField, ScalarField},
+    AssignedValue, Context,
+};
+
+/// Domain separation tag absorbed before the two inputs of [two_to_one_compress].
+///
+/// Keeps internal nodes of a binary hash tree from colliding with other fixed-length Poseidon hashes of two elements.
+pub const TWO_TO_ONE_DOMAIN_TAG: u64 = 2;
+
+/// Constrains and returns the Poseidon hash of `[TWO_TO_ONE_DOMAIN_TAG, left, right]`.
+///
+/// Assumes `hasher` has been initialized with [PoseidonHasher::initialize_consts].
+/// * `ctx`: [Context] to add the constraints to
+/// * `gate`: [GateInstructions] to use
+/// * `hasher`: [PoseidonHasher] to hash with
+/// * `left`: left child
+/// * `right`: right child
+pub fn two_to_one_compress<F: ScalarField, const T: usize, const RATE: usize>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    hasher: &PoseidonHasher<F, T, RATE>,
+    left: AssignedValue<F>,
+    right: AssignedValue<F>,
+) -> AssignedValue<F>
+where
+    F: BigPrimeField,
+{
+    let tag = ctx.load_constant(F::from(TWO_TO_ONE_DOMAIN_TAG));
+    hasher.hash_fix_len_array(ctx, gate, &[tag, left, right])
+}
+
+/// Constrains and returns [two_to_one_compress] of each pair in `pairs`, e.g. to compute one level of a Merkle tree.
+///
+/// The domain tag is loaded once and shared by all hashes.
+/// * `ctx`: [Context] to add the constraints to
+/// * `gate`: [GateInstructions] to use
+/// * `hasher`: [PoseidonHasher] to hash with
+/// * `pairs`: list of (left, right) children
+pub fn two_to_one_batch<F: ScalarField, const T: usize, const RATE: usize>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    hasher: &PoseidonHasher<F, T, RATE>,
+    pairs: &[(AssignedValue<F>, AssignedValue<F>)],
+) -> Vec<AssignedValue<F>>
+where
+    F: BigPrimeField,
+{
+    let tag = ctx.load_constant(F::from(TWO_TO_ONE_DOMAIN_TAG));
+    pairs
+        .iter()
+        .map(|&(left, right)| hasher.hash_fix_len_array(ctx, gate, &[tag, left, right]))
+        .collect()
+}
+
+/// Chip for Merkle proofs in binary Poseidon hash trees, whose internal nodes are [two_to_one_compress] of their children.
+pub struct MerkleChip<'a, F: ScalarField, const T: usize, const RATE: usize> {
+    poseidon: PoseidonChip<'a, F, T, RATE>,
+}
+
+impl<'a, F: ScalarField, const T: usize, const RATE: usize> MerkleChip<'a, F, T, RATE> {
+    /// Creates a new [MerkleChip] hashing with `poseidon`.
+    pub fn new(poseidon: PoseidonChip<'a, F, T, RATE>) -> Self {
+        Self { poseidon }
+    }
+
+    /// Constrains and returns the root of the tree containing `leaf`, given its Merkle proof.
+    ///
+    /// Constrains every element of `indices` to be a bit.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `leaf`: leaf to prove membership of
+    /// * `path`: siblings of the nodes from `leaf` up to the root, starting with the sibling of `leaf`
+    /// * `indices`: `indices[i]` is 1 if the node at level `i` is a right child, and 0 otherwise
+    pub fn compute_root(
+        &self,
+        ctx: &mut Context<F>,
+        leaf: AssignedValue<F>,
+        path: &[AssignedValue<F>],
+        indices: &[AssignedValue<F>],
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        assert_eq!(path.len(), indices.len(), "path and indices must have the same length");
+        let gate = self.poseidon.range_chip().gate();
+        path.iter().zip(indices).fold(leaf, |node, (&sibling, &index)| {
+            gate.assert_bit(ctx, index);
+            let left = gate.select(ctx, sibling, node, index);
+            let sum = gate.add(ctx, node, sibling);
+            let right = gate.sub(ctx, sum, left);
+            two_to_one_compress(ctx, gate, self.poseidon.hasher(), left, right)
+        })
+    }
+
+    /// Returns 1 if `leaf` is in the tree with root `root` at the position given by `indices`, and 0 otherwise.
+    ///
+    /// See [`Self::compute_root`] for the format of `path` and `indices`.
+    pub fn verify_membership(
+        &self,
+        ctx: &mut Context<F>,
+        root: AssignedValue<F>,
+        leaf: AssignedValue<F>,
+        path: &[AssignedValue<F>],
+        indices: &[AssignedValue<F>],
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        let computed_root = self.compute_root(ctx, leaf, path, indices);
+        self.poseidon.range_chip().gate().is_equal(ctx, computed_root, root)
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    gates::GateInstructions,
+    utils::{modulus, BigPrimeField, ScalarField},
+    AssignedValue, Context,
+    QuantumCell::{Constant, Existing},
+};
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+/// Exponent of the MiMC round function. Must be coprime to `p - 1`, which holds for the BN254 scalar field.
+const ALPHA: u64 = 7;
+
+/// MiMC block cipher with `ROUNDS` rounds of `x -> (x + k + c_i)^7`, followed by a final key addition.
+///
+/// The round constants `c_i` are public and derived deterministically, with `c_0 = 0`.
+/// 91 rounds is the standard choice for the BN254 scalar field.
+#[derive(Clone, Debug)]
+pub struct MimcBlockCipher<F: ScalarField, const ROUNDS: usize> {
+    round_constants: Vec<F>,
+}
+
+impl<F: ScalarField, const ROUNDS: usize> Default for MimcBlockCipher<F, ROUNDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: ScalarField, const ROUNDS: usize> MimcBlockCipher<F, ROUNDS> {
+    /// Creates a new [MimcBlockCipher] with the default round constants.
+    pub fn new() -> Self {
+        let mut rng = ChaCha20Rng::from_seed(*b"halo2-base MiMC round constants!");
+        let round_constants =
+            (0..ROUNDS).map(|i| if i == 0 { F::ZERO } else { F::random(&mut rng) }).collect();
+        Self { round_constants }
+    }
+
+    /// Returns the round constants `c_i`.
+    pub fn round_constants(&self) -> &[F] {
+        &self.round_constants
+    }
+
+    /// Constrains and returns the encryption of `plaintext` under `key`.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `gate`: [GateInstructions] to use
+    /// * `plaintext`: [AssignedValue] block to encrypt
+    /// * `key`: [AssignedValue] secret key
+    pub fn encrypt(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        plaintext: AssignedValue<F>,
+        key: AssignedValue<F>,
+    ) -> AssignedValue<F> {
+        let mut x = plaintext;
+        for c in &self.round_constants {
+            let t = gate.sum(ctx, [Existing(x), Existing(key), Constant(*c)]);
+            x = pow7(ctx, gate, t);
+        }
+        gate.add(ctx, x, key)
+    }
+
+    /// Constrains and returns the decryption of `ciphertext` under `key`.
+    ///
+    /// Each round witnesses the 7-th root of the current state and constrains its 7-th power instead of
+    /// exponentiating by the inverse of 7 in the circuit.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `gate`: [GateInstructions] to use
+    /// * `ciphertext`: [AssignedValue] block to decrypt
+    /// * `key`: [AssignedValue] secret key
+    pub fn decrypt(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        ciphertext: AssignedValue<F>,
+        key: AssignedValue<F>,
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        let inv_alpha = inv_alpha::<F>().to_u64_digits();
+        let mut y = gate.sub(ctx, ciphertext, key);
+        for c in self.round_constants.iter().rev() {
+            let t = ctx.load_witness(y.value().pow_vartime(&inv_alpha));
+            let t7 = pow7(ctx, gate, t);
+            ctx.constrain_equal(&t7, &y);
+            let t = gate.sub(ctx, t, key);
+            y = gate.sub(ctx, t, Constant(*c));
+        }
+        y
+    }
+
+    /// Constrains and returns the encryption of `plaintext` under `key` in cipher block chaining (CBC) mode,
+    /// where `ciphertext[i] = encrypt(plaintext[i] + ciphertext[i - 1])` and `ciphertext[-1] = iv`.
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `gate`: [GateInstructions] to use
+    /// * `plaintext`: slice of [AssignedValue] blocks to encrypt
+    /// * `key`: [AssignedValue] secret key
+    /// * `iv`: [AssignedValue] initialization vector
+    pub fn encrypt_cbc(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        plaintext: &[AssignedValue<F>],
+        key: AssignedValue<F>,
+        iv: AssignedValue<F>,
+    ) -> Vec<AssignedValue<F>> {
+        let mut prev = iv;
+        plaintext
+            .iter()
+            .map(|block| {
+                let block = gate.add(ctx, *block, prev);
+                prev = self.encrypt(ctx, gate, block, key);
+                prev
+            })
+            .collect()
+    }
+
+    /// Constrains and returns the decryption of `ciphertext` under `key` in cipher block chaining (CBC) mode.
+    /// See [`Self::encrypt_cbc`].
+    /// * `ctx`: [Context] to add the constraints to
+    /// * `gate`: [GateInstructions] to use
+    /// * `ciphertext`: slice of [AssignedValue] blocks to decrypt
+    /// * `key`: [AssignedValue] secret key
+    /// * `iv`: [AssignedValue] initialization vector
+    pub fn decrypt_cbc(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        ciphertext: &[AssignedValue<F>],
+        key: AssignedValue<F>,
+        iv: AssignedValue<F>,
+    ) -> Vec<AssignedValue<F>>
+    where
+        F: BigPrimeField,
+    {
+        let mut prev = iv;
+        ciphertext
+            .iter()
+            .map(|block| {
+                let decrypted = self.decrypt(ctx, gate, *block, key);
+                let plaintext = gate.sub(ctx, decrypted, prev);
+                prev = *block;
+                plaintext
+            })
+            .collect()
+    }
+}
+
+/// Constrains and returns `x^7` using 4 multiplications.
+fn pow7<F: ScalarField>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    x: AssignedValue<F>,
+) -> AssignedValue<F> {
+    let x2 = gate.mul(ctx, x, x);
+    let x4 = gate.mul(ctx, x2, x2);
+    let x6 = gate.mul(ctx, x4, x2);
+    gate.mul(ctx, x6, x)
+}
+
+/// Returns `d` such that `ALPHA * d = 1 mod (p - 1)`.
+fn inv_alpha<F: BigPrimeField>() -> BigUint {
+    let p_minus_one = modulus::<F>() - 1u64;
+    (1..ALPHA)
+        .map(|k| p_minus_one.clone() * k + 1u64)
+        .find(|n| (n % ALPHA).is_zero())
+        .expect("ALPHA must be coprime to p - 1")
+        / ALPHA
+}
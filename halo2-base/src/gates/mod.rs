@@ -1,9 +1,25 @@
 /// Module providing tools to create a circuit using our gates
 pub mod circuit;
+/// Module for in-circuit counters
+pub mod counter;
+/// Module with example gadgets built from [GateInstructions]
+pub mod examples;
 /// Module implementing our simple custom gate and common functions using it
 pub mod flex_gate;
+/// Module for folding accumulators
+pub mod folding;
+/// Module for the Keccak-256 hash on bytes
+pub mod keccak;
+/// Module for building custom lookup arguments
+pub mod lookup;
+/// Module for two-to-one Poseidon compression and Merkle proofs in binary hash trees
+pub mod merkle;
+/// Module for the MiMC block cipher
+pub mod mimc;
 /// Module using a single lookup table for range checks
 pub mod range;
+/// Module for XOR of bytes, with or without a lookup table
+pub mod xor;
 
 /// Tests
 #[cfg(test)]
@@ -12,17 +12,17 @@ use crate::{
         ScalarField,
     },
     virtual_region::lookups::LookupAnyManager,
-    AssignedValue, Context,
+    AssignedValue, Context, ContextSnapshot,
     QuantumCell::{self, Constant, Existing, Witness},
 };
 
 use super::flex_gate::{FlexGateConfigParams, GateChip};
 
 use getset::Getters;
-use num_bigint::BigUint;
+use num_bigint::{BigInt, BigUint};
 use num_integer::Integer;
-use num_traits::One;
-use std::{cmp::Ordering, ops::Shl};
+use num_traits::{One, Zero};
+use std::{cmp::Ordering, fmt, ops::Shl};
 
 /// Configuration for Range Chip
 #[derive(Clone, Debug)]
@@ -160,6 +160,29 @@ impl<F: ScalarField> RangeConfig<F> {
     }
 }
 
+/// Error returned by [`RangeInstructions::safe_decompose`] when a value does not fit in the requested limbs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OverflowError {
+    /// Number of limbs requested.
+    pub num_limbs: usize,
+    /// Number of bits per limb.
+    pub limb_bits: usize,
+    /// Actual value that overflowed.
+    pub value: BigUint,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "value {} does not fit in {} limbs of {} bits",
+            self.value, self.num_limbs, self.limb_bits
+        )
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
 /// Trait that implements methods to constrain a field element number `x` is within a range of bits.
 pub trait RangeInstructions<F: ScalarField> {
     /// The type of Gate used within the instructions.
@@ -196,6 +219,8 @@ pub trait RangeInstructions<F: ScalarField> {
 
     /// Performs a range check that `a` has at most `bit_length(b)` bits and then constrains that `a` is less than `b`.
     ///
+    /// Since `b` is a constant, `a < b` is checked as `a <= b - 1` with a single range check of `b - 1 - a`, which wraps
+    /// around to a value of more than `bit_length(b)` bits when `a >= b`.
     /// * a: [AssignedValue] value to check
     /// * b: upper bound expressed as a [u64] value
     fn check_less_than_safe(&self, ctx: &mut Context<F>, a: AssignedValue<F>, b: u64) {
@@ -203,7 +228,28 @@ pub trait RangeInstructions<F: ScalarField> {
             (bit_length(b) + self.lookup_bits() - 1) / self.lookup_bits() * self.lookup_bits();
 
         self.range_check(ctx, a, range_bits);
-        self.check_less_than(ctx, a, Constant(F::from(b)), range_bits)
+        let diff = self.gate().sub(ctx, Constant(F::from(b) - F::ONE), a);
+        self.range_check(ctx, diff, range_bits);
+    }
+
+    /// Performs a range check that `a` has at most `num_bits` bits and then constrains that `a` is greater than `b`.
+    ///
+    /// As in [RangeInstructions::check_less_than_safe], `a > b` is checked as `a - b - 1 >= 0` with a single range check.
+    ///
+    /// Assumes `num_bits < F::CAPACITY`.
+    /// * a: [AssignedValue] value to check
+    /// * b: lower bound expressed as a [u64] value
+    /// * num_bits: number of bits of `a`
+    fn check_greater_than_safe(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        b: u64,
+        num_bits: usize,
+    ) {
+        self.range_check(ctx, a, num_bits);
+        let diff = self.gate().sub(ctx, a, Constant(F::from(b) + F::ONE));
+        self.range_check(ctx, diff, num_bits);
     }
 
     /// Performs a range check that `a` has at most `bit_length(b)` bits and then constrains that `a` is less than `b`.
@@ -313,6 +359,160 @@ pub trait RangeInstructions<F: ScalarField> {
         (div, rem)
     }
 
+    /// Constrains and returns `(c, r)` such that `a = b * c + r` and `r < b`, for a divisor `b` that fits in a [u64].
+    ///
+    /// Cheaper than [`Self::div_mod`]: the quotient is only range checked to `a_num_bits - bit_length(b) + 1` bits
+    /// instead of being compared against `2^a_num_bits / b`. `a` itself is not range checked, so the caller is
+    /// responsible for ensuring that `a < 2^a_num_bits`, e.g. because `a` is the output of a previous range check.
+    ///
+    /// Assumes that `b != 0` and `a_num_bits + 2 <= F::CAPACITY`.
+    /// * a: [AssignedValue] value to divide
+    /// * b: [u64] value to divide by
+    /// * a_num_bits: number of bits needed to represent the value of `a`
+    fn div_mod_unsafe(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        b: u64,
+        a_num_bits: usize,
+    ) -> (AssignedValue<F>, AssignedValue<F>)
+    where
+        F: BigPrimeField,
+    {
+        assert_ne!(b, 0, "divisor must be non-zero");
+        assert!(a_num_bits + 2 <= F::CAPACITY as usize, "a_num_bits is too large for the field");
+        let (div, rem) = fe_to_biguint(a.value()).div_mod_floor(&BigUint::from(b));
+        let [div, rem] = [div, rem].map(|v| biguint_to_fe(&v));
+        ctx.assign_region([Witness(rem), Constant(F::from(b)), Witness(div), Existing(a)], [0]);
+        let rem = ctx.get(-4);
+        let div = ctx.get(-2);
+        // `div <= a / b < 2^(a_num_bits - bit_length(b) + 1)` since `b >= 2^(bit_length(b) - 1)`,
+        // so `b * div + rem` cannot overflow the field
+        self.range_check(ctx, div, (a_num_bits + 1).saturating_sub(bit_length(b)));
+        self.check_less_than_safe(ctx, rem, b);
+        (div, rem)
+    }
+
+    /// Constrains and returns `a mod modulus`, viewing `a` as an integer in `[0, p)` where `p` is the field modulus.
+    ///
+    /// Witnesses `a = q * modulus + r` and constrains `r < modulus` and `q <= (p - 1) / modulus`. When `q` equals this
+    /// bound, `r` is further constrained to at most `(p - 1) % modulus`, so that `q * modulus + r` cannot wrap around `p`
+    /// and the decomposition is unique for every `a`, unlike [`Self::div_mod`] with `a_num_bits = F::NUM_BITS`.
+    ///
+    /// Assumes that `modulus != 0`.
+    /// * a: [AssignedValue] value to reduce
+    /// * modulus: [u64] value to reduce by, e.g. a small prime
+    fn euclidean_reduce(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        modulus: u64,
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        assert_ne!(modulus, 0, "modulus must be non-zero");
+        let gate = self.gate();
+        let (q_max, r_max) = (crate::utils::modulus::<F>() - 1u32).div_rem(&BigUint::from(modulus));
+        let r_max = r_max.iter_u64_digits().next().unwrap_or(0);
+
+        let (q, r) = fe_to_biguint(a.value()).div_rem(&BigUint::from(modulus));
+        let [q, r] = [q, r].map(|v| biguint_to_fe(&v));
+        ctx.assign_region([Witness(r), Constant(F::from(modulus)), Witness(q), Existing(a)], [0]);
+        let r = ctx.get(-4);
+        let q = ctx.get(-2);
+
+        self.check_big_less_than_safe(ctx, q, q_max.clone() + 1u32);
+        // if `q` is maximal, `r` must also be small enough for `q * modulus + r < p`
+        let q_is_max = gate.is_equal(ctx, q, Constant(biguint_to_fe(&q_max)));
+        let r_bound =
+            gate.select(ctx, Constant(F::from(r_max + 1)), Constant(F::from(modulus)), q_is_max);
+        let num_bits = bit_length(modulus);
+        self.range_check(ctx, r, num_bits);
+        self.check_less_than(ctx, r, r_bound, num_bits);
+        r
+    }
+
+    /// Constrains and returns `(c, r)` such that `a = b * c + r` for two's-complement signed integers, where the
+    /// quotient `c` is truncated towards zero and the remainder `r` has the sign of `a`, as with `/` and `%` on [i64].
+    ///
+    /// `a`, `c` and `r` are encoded in two's complement with `a_num_bits` bits, i.e. a negative `x` is represented by
+    /// `2^a_num_bits + x`. Constrains that `a < 2^a_num_bits`, and that the quotient does not overflow, which only
+    /// happens for `b == -1` and `a == -2^(a_num_bits - 1)`.
+    ///
+    /// Assumes that `b != 0` and `0 < a_num_bits <= 64`.
+    /// * a: [AssignedValue] two's-complement value to divide
+    /// * b: [i64] value to divide by
+    /// * a_num_bits: number of bits of the two's-complement encoding
+    fn signed_div_mod(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        b: i64,
+        a_num_bits: usize,
+    ) -> (AssignedValue<F>, AssignedValue<F>)
+    where
+        F: BigPrimeField,
+    {
+        assert_ne!(b, 0, "divisor must be non-zero");
+        assert!((1..=64).contains(&a_num_bits), "a_num_bits must be between 1 and 64");
+        let gate = self.gate();
+        let modulus = Constant(gate.pow_of_two()[a_num_bits]);
+        self.range_check(ctx, a, a_num_bits);
+        // the sign bit is the most significant bit
+        let (a_neg, _) = self.div_mod_unsafe(ctx, a, 1 << (a_num_bits - 1), a_num_bits);
+        if b == -1 {
+            // -(-2^(a_num_bits - 1)) does not fit in `a_num_bits` bits
+            let is_min = gate.is_equal(ctx, a, Constant(gate.pow_of_two()[a_num_bits - 1]));
+            gate.assert_is_const(ctx, &is_min, &F::ZERO);
+        }
+        let minus_a = gate.sub(ctx, modulus, a);
+        let a_abs = gate.select(ctx, minus_a, a, a_neg);
+        let (div_abs, rem_abs) = self.div_mod_unsafe(ctx, a_abs, b.unsigned_abs(), a_num_bits);
+
+        // negates `x` in two's complement if `neg` is 1, where `0` stays `0`
+        let negate_if = |ctx: &mut Context<F>, x: AssignedValue<F>, neg: AssignedValue<F>| {
+            let x_is_zero = gate.is_zero(ctx, x);
+            let flip = gate.sub_mul(ctx, neg, neg, x_is_zero);
+            let minus_x = gate.sub(ctx, modulus, x);
+            gate.select(ctx, minus_x, x, flip)
+        };
+        // the quotient is negative iff exactly one of `a` and `b` is, the remainder has the sign of `a`
+        let div_neg = if b < 0 { gate.not(ctx, a_neg) } else { a_neg };
+        let div = negate_if(ctx, div_abs, div_neg);
+        let rem = negate_if(ctx, rem_abs, a_neg);
+        (div, rem)
+    }
+
+    /// Constrains and returns `<a, b> mod modulus`, where `b` is a vector of constants.
+    ///
+    /// The inner product is reduced with [`Self::div_mod`], so its bit length `a_num_bits + bit_length(sum_i b_i)`
+    /// bounds the witnessed quotient and must be less than `F::CAPACITY`.
+    ///
+    /// Assumes that `modulus != 0` and that each element of `a` has <= `a_num_bits` bits.
+    /// * a: slice of [AssignedValue]
+    /// * b: slice of constants, with the same length as `a`
+    /// * modulus: [BigUint] value to reduce by
+    /// * a_num_bits: number of bits needed to represent each element of `a`
+    fn dot_product_mod(
+        &self,
+        ctx: &mut Context<F>,
+        a: &[AssignedValue<F>],
+        b: &[F],
+        modulus: impl Into<BigUint>,
+        a_num_bits: usize,
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        assert_eq!(a.len(), b.len(), "a and b must have the same length");
+        let b_sum: BigUint = b.iter().map(fe_to_biguint).sum();
+        let num_bits = a_num_bits + b_sum.bits() as usize;
+        assert!(num_bits < F::CAPACITY as usize, "inner product may overflow the field");
+        let prod = self.gate().inner_product(ctx, a.to_vec(), b.iter().map(|b| Constant(*b)));
+        self.div_mod(ctx, prod, modulus, num_bits).1
+    }
+
     /// Constrains and returns `(c, r)` such that `a = b * c + r`.
     ///
     /// Assumes:
@@ -402,9 +602,535 @@ pub trait RangeInstructions<F: ScalarField> {
         self.gate().assert_bit(ctx, bit);
         bit
     }
+
+    /// Constrains and returns `(low, carry)` such that `a + b = carry * 2^bit_len + low`.
+    ///
+    /// Assumes `a` and `b` have been range checked already to `bit_len` bits, so `carry` is a single bit.
+    /// `low` is range checked to `bit_len` bits and `carry` is constrained to be boolean.
+    /// * a: [QuantumCell] value
+    /// * b: [QuantumCell] value to add to `a`
+    /// * bit_len: number of bits of `a` and `b`
+    fn carry_add(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<QuantumCell<F>>,
+        b: impl Into<QuantumCell<F>>,
+        bit_len: usize,
+    ) -> (AssignedValue<F>, AssignedValue<F>) {
+        let sum = self.gate().add(ctx, a, b);
+        let shift = self.gate().pow_of_two()[bit_len];
+        let carry_v = F::from_bytes_le(&(fe_to_biguint(sum.value()) >> bit_len).to_bytes_le());
+        let low_v = *sum.value() - carry_v * shift;
+
+        ctx.assign_region([Witness(low_v), Witness(carry_v), Constant(shift), Existing(sum)], [0]);
+        let low = ctx.get(-4);
+        let carry = ctx.get(-3);
+
+        self.range_check(ctx, low, bit_len);
+        self.gate().assert_bit(ctx, carry);
+        (low, carry)
+    }
+
+    /// Constrains and returns the little-endian decomposition of `a` into `num_limbs` limbs of `limb_bits` bits each.
+    ///
+    /// Returns an [OverflowError] without assigning any cells if the value of `a` does not fit in
+    /// `num_limbs * limb_bits` bits. Since this depends on the witness, the caller must make sure
+    /// the same branch is taken during key generation and proving.
+    ///
+    /// Assumes `num_limbs * limb_bits < F::CAPACITY`.
+    /// * a: [AssignedValue] value to decompose
+    /// * num_limbs: number of limbs
+    /// * limb_bits: number of bits in each limb
+    fn safe_decompose(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        num_limbs: usize,
+        limb_bits: usize,
+    ) -> Result<Vec<AssignedValue<F>>, OverflowError> {
+        assert!(num_limbs * limb_bits < F::CAPACITY as usize);
+        let a_big = fe_to_biguint(a.value());
+        if a_big.bits() as usize > num_limbs * limb_bits {
+            return Err(OverflowError { num_limbs, limb_bits, value: a_big });
+        }
+        Ok(self.decompose_le(ctx, a, limb_bits, num_limbs))
+    }
+
+    /// Constrains and returns the decomposition of `a` into `num_limbs` limbs of `limb_bits` bits each,
+    /// in little-endian order: `a = sum_i limbs[i] * 2^(i * limb_bits)`.
+    ///
+    /// Every limb is range checked to `limb_bits` bits, so the constraints are not satisfied if `a` does not fit in
+    /// `num_limbs * limb_bits` bits. Use [RangeInstructions::safe_decompose] to detect that case before assigning.
+    ///
+    /// Assumes `num_limbs * limb_bits < F::CAPACITY`.
+    /// * a: [AssignedValue] value to decompose
+    /// * limb_bits: number of bits in each limb
+    /// * num_limbs: number of limbs
+    fn decompose_le(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        limb_bits: usize,
+        num_limbs: usize,
+    ) -> Vec<AssignedValue<F>> {
+        assert!(num_limbs * limb_bits < F::CAPACITY as usize);
+        let a_big = fe_to_biguint(a.value());
+        let mask = (BigUint::one() << limb_bits) - 1u64;
+        let limbs = (0..num_limbs).map(|i| {
+            Witness(F::from_bytes_le(&((&a_big >> (i * limb_bits)) & &mask).to_bytes_le()))
+        });
+        let bases = (0..num_limbs).map(|i| Constant(self.gate().pow_of_two()[i * limb_bits]));
+        let (acc, limbs) = self.gate().inner_product_left(ctx, limbs, bases);
+        ctx.constrain_equal(&a, &acc);
+        for limb in &limbs {
+            self.range_check(ctx, *limb, limb_bits);
+        }
+        limbs
+    }
+
+    /// Constrains and returns the decomposition of `a` into `num_limbs` limbs of `limb_bits` bits each,
+    /// in big-endian order: `a = sum_i limbs[i] * 2^((num_limbs - 1 - i) * limb_bits)`.
+    ///
+    /// Same constraints as [RangeInstructions::decompose_le], with the limbs reversed.
+    /// * a: [AssignedValue] value to decompose
+    /// * limb_bits: number of bits in each limb
+    /// * num_limbs: number of limbs
+    fn decompose_be(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        limb_bits: usize,
+        num_limbs: usize,
+    ) -> Vec<AssignedValue<F>> {
+        let mut limbs = self.decompose_le(ctx, a, limb_bits, num_limbs);
+        limbs.reverse();
+        limbs
+    }
+
+    /// Constrains and returns `sum_i limbs[i] * 2^(i * limb_bits)`, the inverse of [RangeInstructions::decompose_le].
+    ///
+    /// Does not range check the limbs.
+    /// * limbs: little-endian limbs
+    /// * limb_bits: number of bits in each limb
+    fn recompose_le(
+        &self,
+        ctx: &mut Context<F>,
+        limbs: &[AssignedValue<F>],
+        limb_bits: usize,
+    ) -> AssignedValue<F> {
+        let bases = (0..limbs.len()).map(|i| Constant(self.gate().pow_of_two()[i * limb_bits]));
+        self.gate().inner_product(ctx, limbs.iter().copied(), bases)
+    }
+
+    /// Constrains and returns `sum_i limbs[i] * 2^((limbs.len() - 1 - i) * limb_bits)`, the inverse of
+    /// [RangeInstructions::decompose_be].
+    ///
+    /// Does not range check the limbs.
+    /// * limbs: big-endian limbs
+    /// * limb_bits: number of bits in each limb
+    fn recompose_be(
+        &self,
+        ctx: &mut Context<F>,
+        limbs: &[AssignedValue<F>],
+        limb_bits: usize,
+    ) -> AssignedValue<F> {
+        let le = limbs.iter().rev().copied().collect::<Vec<_>>();
+        self.recompose_le(ctx, &le, limb_bits)
+    }
+
+    /// Constrains and returns `|a - b|`.
+    ///
+    /// Witnesses whether `a < b` as a bit `s` and returns `(1 - 2s) * (a - b)` range checked to `num_bits` bits.
+    /// A wrong `s` yields `p - |a - b|`, which fails the range check unless `a = b`, where both choices agree.
+    ///
+    /// Assumes `a` and `b` are known to have `<= num_bits` bits.
+    /// * a: first [AssignedValue]
+    /// * b: second [AssignedValue]
+    /// * num_bits: number of bits to represent the values
+    fn abs_diff(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        b: AssignedValue<F>,
+        num_bits: usize,
+    ) -> AssignedValue<F> {
+        let gate = self.gate();
+        let diff = gate.sub(ctx, a, b);
+        let is_neg = ctx.load_witness(F::from(fe_to_biguint(a.value()) < fe_to_biguint(b.value())));
+        gate.assert_bit(ctx, is_neg);
+        let neg_part = gate.mul(ctx, is_neg, diff);
+        let out = gate.sub_mul(ctx, diff, Constant(F::from(2u64)), neg_part);
+        self.range_check(ctx, out, num_bits);
+        out
+    }
+
+    /// Constrains that the integer `a = sum_i limbs[i] * 2^(i * limb_bits)` is less than the
+    /// `modulus = sum_i modulus_limbs[i] * 2^(i * limb_bits)`, i.e., that `a` is in canonical form.
+    ///
+    /// Computes `modulus - 1 - a` limb by limb with witnessed borrow bits and range checks every limb of the difference.
+    ///
+    /// Assumes each of `limbs` has been range checked to `limb_bits` bits and `limb_bits <= 64`.
+    /// * limbs: little-endian limbs of `a`
+    /// * limb_bits: number of bits in each limb
+    /// * modulus_limbs: little-endian limbs of the modulus, each less than `2^limb_bits`, with the same length as `limbs`
+    fn check_limbs_canonical(
+        &self,
+        ctx: &mut Context<F>,
+        limbs: &[AssignedValue<F>],
+        limb_bits: usize,
+        modulus_limbs: &[u64],
+    ) {
+        assert_eq!(
+            limbs.len(),
+            modulus_limbs.len(),
+            "limbs and modulus_limbs must have the same length"
+        );
+        assert!(limb_bits <= 64);
+        let modulus =
+            modulus_limbs.iter().rev().fold(BigUint::zero(), |acc, m| (acc << limb_bits) + *m);
+        assert!(!modulus.is_zero(), "modulus must be non-zero");
+        let max = modulus - 1u64;
+        let mask = (BigUint::one() << limb_bits) - 1u64;
+        let gate = self.gate();
+
+        let mut borrow: Option<AssignedValue<F>> = None;
+        let mut borrow_v = 0i128;
+        for (i, a) in limbs.iter().enumerate() {
+            let max_i = ((&max >> (i * limb_bits)) & &mask).iter_u64_digits().next().unwrap_or(0);
+            let mut diff = gate.sub(ctx, Constant(F::from(max_i)), *a);
+            let mut diff_v = max_i as i128 - a.value().get_lower_64() as i128;
+            if let Some(borrow) = borrow {
+                diff = gate.sub(ctx, diff, borrow);
+                diff_v -= borrow_v;
+            }
+            // the most significant limb cannot borrow
+            if i + 1 < limbs.len() {
+                borrow_v = (diff_v < 0) as i128;
+                let next_borrow = ctx.load_witness(F::from(diff_v < 0));
+                gate.assert_bit(ctx, next_borrow);
+                diff = gate.mul_add(ctx, next_borrow, Constant(gate.pow_of_two()[limb_bits]), diff);
+                borrow = Some(next_borrow);
+            }
+            self.range_check(ctx, diff, limb_bits);
+        }
+    }
+
+    /// Constrains and returns 1 if the multi-limb integer `a` is less than `b`, otherwise 0.
+    ///
+    /// Limbs are in big-endian order, i.e., `a = sum_i a[i] * 2^((a.len() - 1 - i) * limb_bits)`.
+    /// The result is decided by the most significant limb where `a` and `b` differ: scanning from the top,
+    /// we track whether all limbs so far are equal and add in the comparison of the current limbs only
+    /// while they are, so no borrow chain or full subtraction is needed.
+    ///
+    /// Assumes each limb of `a` and `b` has been range checked to `limb_bits` bits.
+    /// * a: big-endian limbs of the first integer
+    /// * b: big-endian limbs of the second integer, with the same non-zero length as `a`
+    /// * limb_bits: number of bits in each limb
+    fn check_big_less_than(
+        &self,
+        ctx: &mut Context<F>,
+        a: &[AssignedValue<F>],
+        b: &[AssignedValue<F>],
+        limb_bits: usize,
+    ) -> AssignedValue<F> {
+        assert_eq!(a.len(), b.len(), "a and b must have the same number of limbs");
+        assert!(!a.is_empty(), "a and b must have at least one limb");
+        let gate = self.gate();
+
+        let mut lt = self.is_less_than(ctx, a[0], b[0], limb_bits);
+        let mut eq = gate.is_equal(ctx, a[0], b[0]);
+        for (i, (a_i, b_i)) in a.iter().zip(b.iter()).enumerate().skip(1) {
+            let lt_i = self.is_less_than(ctx, *a_i, *b_i, limb_bits);
+            // `lt` and `eq` are never both 1, so this is `lt || (eq && lt_i)`
+            lt = gate.mul_add(ctx, eq, lt_i, lt);
+            if i + 1 < a.len() {
+                let eq_i = gate.is_equal(ctx, *a_i, *b_i);
+                eq = gate.mul(ctx, eq, eq_i);
+            }
+        }
+        lt
+    }
+
+    /// Constrains and returns the index of the minimum of `vals`. Ties resolve to the first occurrence.
+    ///
+    /// Assumes all elements of `vals` are known to have `<= num_bits` bits.
+    /// * vals: non-empty slice of [AssignedValue] to search
+    /// * num_bits: number of bits to represent the values
+    fn argmin(
+        &self,
+        ctx: &mut Context<F>,
+        vals: &[AssignedValue<F>],
+        num_bits: usize,
+    ) -> AssignedValue<F> {
+        assert!(!vals.is_empty(), "vals should not be empty");
+        let mut min = vals[0];
+        let mut idx = ctx.load_zero();
+        for (i, val) in vals.iter().enumerate().skip(1) {
+            let is_less = self.is_less_than(ctx, *val, min, num_bits);
+            min = self.gate().select(ctx, *val, min, is_less);
+            idx = self.gate().select(ctx, Constant(F::from(i as u64)), idx, is_less);
+        }
+        idx
+    }
+
+    /// Constrains and returns the minimum of `vals`.
+    ///
+    /// Assumes all elements of `vals` are known to have `<= num_bits` bits.
+    /// * vals: non-empty slice of [AssignedValue] to search
+    /// * num_bits: number of bits to represent the values
+    fn min_val(
+        &self,
+        ctx: &mut Context<F>,
+        vals: &[AssignedValue<F>],
+        num_bits: usize,
+    ) -> AssignedValue<F> {
+        assert!(!vals.is_empty(), "vals should not be empty");
+        vals.iter().skip(1).fold(vals[0], |min, val| {
+            let is_less = self.is_less_than(ctx, *val, min, num_bits);
+            self.gate().select(ctx, *val, min, is_less)
+        })
+    }
+
+    /// Constrains and returns `(min, max)` of `vals` in a single pass.
+    ///
+    /// Assumes all elements of `vals` are known to have `<= num_bits` bits.
+    /// * vals: non-empty slice of [AssignedValue] to search
+    /// * num_bits: number of bits to represent the values
+    fn minmax(
+        &self,
+        ctx: &mut Context<F>,
+        vals: &[AssignedValue<F>],
+        num_bits: usize,
+    ) -> (AssignedValue<F>, AssignedValue<F>) {
+        assert!(!vals.is_empty(), "vals should not be empty");
+        vals.iter().skip(1).fold((vals[0], vals[0]), |(min, max), val| {
+            let is_less = self.is_less_than(ctx, *val, min, num_bits);
+            let is_greater = self.is_less_than(ctx, max, *val, num_bits);
+            (
+                self.gate().select(ctx, *val, min, is_less),
+                self.gate().select(ctx, *val, max, is_greater),
+            )
+        })
+    }
+
+    /// Constrains that `lo <= a <= hi` by range checking `a - lo` and `hi - a` to `num_bits` bits.
+    ///
+    /// Assumes `lo <= hi` and `num_bits < F::NUM_BITS - 1`, so that neither difference can wrap around the field
+    /// into `num_bits` bits.
+    /// * a: [AssignedValue] to check
+    /// * lo: lower bound, inclusive
+    /// * hi: upper bound, inclusive
+    /// * num_bits: number of bits of `hi - lo`
+    fn assert_in_range(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        lo: F,
+        hi: F,
+        num_bits: usize,
+    ) {
+        let above_lo = self.gate().sub(ctx, a, Constant(lo));
+        let below_hi = self.gate().sub(ctx, Constant(hi), a);
+        self.range_check(ctx, above_lo, num_bits);
+        self.range_check(ctx, below_hi, num_bits);
+    }
+
+    /// Constrains that `lo <= a <= hi` for every `a` in `values`.
+    ///
+    /// See [`Self::assert_in_range`] for the assumptions.
+    fn batch_check_in_range(
+        &self,
+        ctx: &mut Context<F>,
+        values: &[AssignedValue<F>],
+        lo: F,
+        hi: F,
+        num_bits: usize,
+    ) {
+        for a in values {
+            self.assert_in_range(ctx, *a, lo, hi, num_bits);
+        }
+    }
+
+    /// Returns 1 if `lo <= a <= hi`, otherwise 0.
+    ///
+    /// Assumes `a`, `lo` and `hi` are known to have `<= num_bits` bits, and `lo <= hi`.
+    /// * a: [AssignedValue] to check
+    /// * lo: lower bound, inclusive
+    /// * hi: upper bound, inclusive
+    /// * num_bits: number of bits to represent the values
+    fn is_in_range(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        lo: F,
+        hi: F,
+        num_bits: usize,
+    ) -> AssignedValue<F> {
+        let below = self.is_less_than(ctx, a, Constant(lo), num_bits);
+        let above = self.is_less_than(ctx, Constant(hi), a, num_bits);
+        // `a` cannot be both below `lo` and above `hi`, so the sum is a bit
+        let out_of_range = self.gate().add(ctx, below, above);
+        self.gate().not(ctx, out_of_range)
+    }
+
+    /// Returns 1 if `lo <= a <= hi` for every `a` in `values`, otherwise 0.
+    ///
+    /// See [`Self::is_in_range`] for the assumptions.
+    fn all_in_range(
+        &self,
+        ctx: &mut Context<F>,
+        values: &[AssignedValue<F>],
+        lo: F,
+        hi: F,
+        num_bits: usize,
+    ) -> AssignedValue<F> {
+        let in_range =
+            values.iter().map(|a| self.is_in_range(ctx, *a, lo, hi, num_bits)).collect::<Vec<_>>();
+        let num_in_range = self.gate().sum(ctx, in_range);
+        self.gate().is_equal(ctx, num_in_range, Constant(F::from(values.len() as u64)))
+    }
+
+    /// Constrains that `gcd` is the greatest common divisor of the integers `a` and `b`.
+    ///
+    /// Constrains `a = gcd * a_div_gcd`, `b = gcd * b_div_gcd`, and that `a_div_gcd` and `b_div_gcd` are coprime.
+    /// Coprimality is shown by Bezout coefficients `s, t >= 0` with `s * a_div_gcd - t * b_div_gcd = ±1`, which are
+    /// computed natively and witnessed. All witnesses are range checked to `num_bits` bits, so no product wraps around the field.
+    ///
+    /// Assumes `2 * num_bits < F::CAPACITY`.
+    /// * a, b: [AssignedValue] values whose gcd is checked
+    /// * gcd: claimed gcd of `a` and `b`
+    /// * a_div_gcd: claimed quotient `a / gcd`
+    /// * b_div_gcd: claimed quotient `b / gcd`
+    /// * num_bits: number of bits of `gcd`, `a_div_gcd` and `b_div_gcd`
+    fn assert_gcd(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        b: AssignedValue<F>,
+        gcd: AssignedValue<F>,
+        a_div_gcd: AssignedValue<F>,
+        b_div_gcd: AssignedValue<F>,
+        num_bits: usize,
+    ) where
+        F: BigPrimeField,
+    {
+        assert!(2 * num_bits < F::CAPACITY as usize, "products may overflow the field");
+        let gate = self.gate();
+        let [x, y] = [a_div_gcd, b_div_gcd].map(|v| BigInt::from(fe_to_biguint(v.value())));
+        // the coefficients have opposite signs, so `|s| * x - |t| * y = ±gcd(x, y)`
+        let bezout = x.extended_gcd(&y);
+        let [s, t] = [bezout.x, bezout.y].map(|c| ctx.load_witness(biguint_to_fe(c.magnitude())));
+        for v in [gcd, a_div_gcd, b_div_gcd, s, t] {
+            self.range_check(ctx, v, num_bits);
+        }
+
+        let a_prod = gate.mul(ctx, gcd, a_div_gcd);
+        ctx.constrain_equal(&a_prod, &a);
+        let b_prod = gate.mul(ctx, gcd, b_div_gcd);
+        ctx.constrain_equal(&b_prod, &b);
+
+        let sx = gate.mul(ctx, s, a_div_gcd);
+        let diff = gate.sub_mul(ctx, sx, t, b_div_gcd);
+        let diff_sq = gate.mul(ctx, diff, diff);
+        gate.assert_is_const(ctx, &diff_sq, &F::ONE);
+    }
+
+    /// Constrains and returns the unique `x < prod_i moduli[i]` with `x = residues[i] mod moduli[i]` for all `i`.
+    ///
+    /// Uses Garner's algorithm: with `x_0 = residues[0]` and `M_i = moduli[0] * ... * moduli[i - 1]`, each step computes the
+    /// mixed radix digit `v_i = (residues[i] - x_{i-1}) * M_i^{-1} mod moduli[i]` with [`Self::div_mod`]
+    /// and sets `x_i = x_{i-1} + v_i * M_i`.
+    ///
+    /// Assumes `residues[i] < moduli[i]` for all `i`.
+    /// * residues: non-empty slice of [AssignedValue] residues
+    /// * moduli: pairwise coprime moduli, with the same length as `residues`, whose product times 4 is less than `2^F::CAPACITY`
+    fn chinese_remainder_combine(
+        &self,
+        ctx: &mut Context<F>,
+        residues: &[AssignedValue<F>],
+        moduli: &[F],
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        assert_eq!(residues.len(), moduli.len(), "residues and moduli must have the same length");
+        assert!(!residues.is_empty(), "residues should not be empty");
+        let moduli = moduli.iter().map(fe_to_biguint).collect::<Vec<_>>();
+        let total: BigUint = moduli.iter().product();
+        assert!((total << 2usize).bits() < F::CAPACITY as u64, "product of moduli is too large");
+        let gate = self.gate();
+
+        let mut x = residues[0];
+        let mut prod = moduli[0].clone();
+        for (a, m) in residues.iter().zip(&moduli).skip(1) {
+            let m_int = BigInt::from(m.clone());
+            let inv = BigInt::from(&prod % m).extended_gcd(&m_int);
+            assert!(inv.gcd.is_one(), "moduli must be pairwise coprime");
+            let prod_inv = inv.x.mod_floor(&m_int).to_biguint().unwrap();
+            // shift by a multiple of `m` larger than `x_{i-1} < M_i` so the difference is nonnegative
+            let shift = (&prod / m + 1u64) * m;
+            let diff = gate.sub(ctx, *a, x);
+            let t = gate.mul_add(
+                ctx,
+                diff,
+                Constant(biguint_to_fe(&prod_inv)),
+                Constant(biguint_to_fe(&(&shift * &prod_inv))),
+            );
+            let t_num_bits = ((shift + m) * m).bits() as usize;
+            let (_, v) = self.div_mod(ctx, t, m.clone(), t_num_bits);
+            x = gate.mul_add(ctx, v, Constant(biguint_to_fe(&prod)), x);
+            prod *= m;
+        }
+        x
+    }
+
+    /// Constrains and returns the cubic Bezier curve with `control_points` evaluated at the fixed point number
+    /// `t / 2^precision_bits`, using de Casteljau's algorithm.
+    ///
+    /// `t` is constrained to lie in `[0, 2^precision_bits]`, so the curve parameter lies in `[0, 1]`.
+    /// Each of the three levels linearly interpolates adjacent points as `p_i + t * (p_{i+1} - p_i)`.
+    /// The interpolation is exact in the field, so the result is a fraction with denominator dividing `2^(3 * precision_bits)`.
+    ///
+    /// Assumes `precision_bits < 64`.
+    /// * control_points: the four control points of the curve
+    /// * t: [AssignedValue] of the curve parameter, with `precision_bits` fractional bits
+    /// * precision_bits: number of fractional bits of `t`
+    fn cubic_bezier_eval(
+        &self,
+        ctx: &mut Context<F>,
+        control_points: [AssignedValue<F>; 4],
+        t: AssignedValue<F>,
+        precision_bits: usize,
+    ) -> AssignedValue<F> {
+        assert!(precision_bits < 64);
+        self.check_less_than_safe(ctx, t, (1u64 << precision_bits) + 1);
+        let gate = self.gate();
+        let scale_inv = gate.pow_of_two()[precision_bits].invert().unwrap();
+        let t = gate.mul(ctx, t, Constant(scale_inv));
+
+        let mut points = control_points.to_vec();
+        while points.len() > 1 {
+            points = points
+                .windows(2)
+                .map(|pair| {
+                    let diff = gate.sub(ctx, pair[1], pair[0]);
+                    gate.mul_add(ctx, t, diff, pair[0])
+                })
+                .collect();
+        }
+        points[0]
+    }
 }
 
 /// # RangeChip
+/// Position in the assignment of a [Context] and in its range checks, returned by [RangeChip::snapshot]
+/// to later [RangeChip::rollback] to it.
+#[derive(Clone, Copy, Debug)]
+pub struct RangeSnapshot<F: ScalarField> {
+    ctx: ContextSnapshot<F>,
+    lookups_len: usize,
+}
+
 /// This chip provides methods that rely on "range checking" that a field element `x` is within a range of bits.
 /// Range checks are done using a lookup table with the numbers [0, 2<sup>lookup_bits</sup>).
 #[derive(Clone, Debug, Getters)]
@@ -443,10 +1169,31 @@ impl<F: ScalarField> RangeChip<F> {
         Self { gate, lookup_bits, lookup_manager, limb_bases }
     }
 
-    fn add_cell_to_lookup(&self, ctx: &Context<F>, a: AssignedValue<F>) {
+    /// Returns a [RangeSnapshot] of `ctx` and of the range checks it has added so far,
+    /// to undo any assignments and range checks made after it with [RangeChip::rollback].
+    pub fn snapshot(&self, ctx: &Context<F>) -> RangeSnapshot<F> {
+        let lookups_len = self.lookup_manager[ctx.phase()].snapshot(ctx.tag());
+        RangeSnapshot { ctx: ctx.snapshot(), lookups_len }
+    }
+
+    /// Rolls back `ctx` to `snapshot` with [Context::rollback], and removes the range checks added by `ctx` after it.
+    ///
+    /// Use this instead of [Context::rollback] whenever `ctx` may have been range checked since the snapshot,
+    /// otherwise the lookups keep pointing to the removed cells.
+    /// * `snapshot`: a [RangeSnapshot] previously returned by [RangeChip::snapshot] on `ctx`
+    pub fn rollback(&self, ctx: &mut Context<F>, snapshot: RangeSnapshot<F>) {
+        self.lookup_manager[ctx.phase()].rollback(ctx.tag(), snapshot.lookups_len);
+        ctx.rollback(snapshot.ctx);
+    }
+
+    fn add_cells_to_lookup(
+        &self,
+        ctx: &Context<F>,
+        cells: impl IntoIterator<Item = AssignedValue<F>>,
+    ) {
         let phase = ctx.phase();
         let manager = &self.lookup_manager[phase];
-        manager.add_lookup(ctx.tag(), [a]);
+        manager.add_lookups(ctx.tag(), cells.into_iter().map(|a| [a]));
     }
 
     /// Checks and constrains that `a` lies in the range [0, 2<sup>range_bits</sup>).
@@ -469,6 +1216,21 @@ impl<F: ScalarField> RangeChip<F> {
         ctx: &mut Context<F>,
         a: AssignedValue<F>,
         range_bits: usize,
+    ) -> AssignedValue<F> {
+        let mut to_lookup = Vec::new();
+        let last_limb = self._range_check_deferred(ctx, a, range_bits, &mut to_lookup);
+        self.add_cells_to_lookup(ctx, to_lookup);
+        last_limb
+    }
+
+    /// Same as [`Self::_range_check`], but pushes the cells to look up onto `to_lookup` instead of adding them to the
+    /// lookup manager, so that callers range checking many values can add them all at once.
+    fn _range_check_deferred(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        range_bits: usize,
+        to_lookup: &mut Vec<AssignedValue<F>>,
     ) -> AssignedValue<F> {
         if range_bits == 0 {
             self.gate.assert_is_const(ctx, &a, &F::ZERO);
@@ -482,20 +1244,20 @@ impl<F: ScalarField> RangeChip<F> {
         debug_assert!(self.limb_bases.len() >= num_limbs);
 
         let last_limb = if num_limbs == 1 {
-            self.add_cell_to_lookup(ctx, a);
+            to_lookup.push(a);
             a
         } else {
             let limbs = decompose_fe_to_u64_limbs(a.value(), num_limbs, self.lookup_bits)
                 .into_iter()
                 .map(|x| Witness(F::from(x)));
-            let row_offset = ctx.advice.len() as isize;
+            let row_offset = ctx.advice_len() as isize;
             let acc = self.gate.inner_product(ctx, limbs, self.limb_bases[..num_limbs].to_vec());
             // the inner product above must equal `a`
             ctx.constrain_equal(&a, &acc);
             // we fetch the cells to lookup by getting the indices where `limbs` were assigned in `inner_product`. Because `limb_bases[0]` is 1, the progression of indices is 0,1,4,...,4+3*i
-            self.add_cell_to_lookup(ctx, ctx.get(row_offset));
+            to_lookup.push(ctx.get(row_offset));
             for i in 0..num_limbs - 1 {
-                self.add_cell_to_lookup(ctx, ctx.get(row_offset + 1 + 3 * i as isize));
+                to_lookup.push(ctx.get(row_offset + 1 + 3 * i as isize));
             }
             ctx.get(row_offset + 1 + 3 * (num_limbs - 2) as isize)
         };
@@ -511,7 +1273,7 @@ impl<F: ScalarField> RangeChip<F> {
             Ordering::Greater => {
                 let mult_val = self.gate.pow_of_two[self.lookup_bits - rem_bits];
                 let check = self.gate.mul(ctx, last_limb, Constant(mult_val));
-                self.add_cell_to_lookup(ctx, check);
+                to_lookup.push(check);
             }
             _ => {}
         }
@@ -548,6 +1310,28 @@ impl<F: ScalarField> RangeInstructions<F> for RangeChip<F> {
         self._range_check(ctx, a, range_bits);
     }
 
+    /// Constrains that `lo <= a <= hi` for every `a` in `values`.
+    ///
+    /// Same as the default implementation, except the cells to look up for all values are added to the lookup
+    /// manager at once.
+    fn batch_check_in_range(
+        &self,
+        ctx: &mut Context<F>,
+        values: &[AssignedValue<F>],
+        lo: F,
+        hi: F,
+        num_bits: usize,
+    ) {
+        let mut to_lookup = Vec::new();
+        for a in values {
+            let above_lo = self.gate.sub(ctx, *a, Constant(lo));
+            let below_hi = self.gate.sub(ctx, Constant(hi), *a);
+            self._range_check_deferred(ctx, above_lo, num_bits, &mut to_lookup);
+            self._range_check_deferred(ctx, below_hi, num_bits, &mut to_lookup);
+        }
+        self.add_cells_to_lookup(ctx, to_lookup);
+    }
+
     /// Constrains that 'a' is less than 'b'.
     ///
     /// Assumes that`a` and `b` are known to have <= num_bits bits.
@@ -0,0 +1,75 @@
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use test_case::test_case;
+
+use super::lookup::LookupCircuit;
+use crate::{
+    gates::lookup::bitwise::{bitwise_table, BitwiseChip, BitwiseOp, BITWISE_LOOKUP_WIDTH},
+    safe_types::{SafeBytes32, SafeTypeChip},
+};
+
+// the bitwise table has 3 * 2^16 rows
+const K: usize = 18;
+
+#[test]
+fn test_bitwise_table() {
+    let table = bitwise_table::<Fr>();
+    assert_eq!(table.len(), 3 << 16);
+    assert!(table.iter().all(|row| row[0] != Fr::from(0)));
+    assert_eq!(table[(2 << 16) + (0xf0 << 8) + 0x3c], [3, 0xf0, 0x3c, 0xcc].map(Fr::from));
+}
+
+#[test]
+fn test_bitwise_bytes() {
+    let mut circuit = LookupCircuit::<Fr, BITWISE_LOOKUP_WIDTH>::new(bitwise_table());
+    let chip = BitwiseChip::new(circuit.lookup_manager.clone());
+    let ctx = circuit.cpu.main();
+    for (a, b) in [(0x00u8, 0x00u8), (0xf0, 0x3c), (0xff, 0x01), (0x5a, 0xa5)] {
+        let [a_byte, b_byte] =
+            [a, b].map(|x| SafeTypeChip::unsafe_to_byte(ctx.load_witness(Fr::from(x as u64))));
+        for op in BitwiseOp::ALL {
+            let out = chip.byte_op(ctx, op, a_byte, b_byte);
+            assert_eq!(*out.as_ref().value(), Fr::from(op.eval(a, b) as u64));
+        }
+        let xor = chip.xor_byte(ctx, a_byte, b_byte);
+        assert_eq!(*xor.as_ref().value(), Fr::from((a ^ b) as u64));
+    }
+    circuit.assert_satisfied(K, true);
+}
+
+#[test]
+fn test_bitwise_bytes32() {
+    let a_native: [u8; 32] = core::array::from_fn(|i| (i * 7 + 3) as u8);
+    let b_native: [u8; 32] = core::array::from_fn(|i| (255 - i * 5) as u8);
+    let mut circuit = LookupCircuit::<Fr, BITWISE_LOOKUP_WIDTH>::new(bitwise_table());
+    let chip = BitwiseChip::new(circuit.lookup_manager.clone());
+    let ctx = circuit.cpu.main();
+    let [a, b] = [a_native, b_native].map(|bytes| {
+        SafeBytes32::from_bytes(
+            bytes.map(|x| SafeTypeChip::unsafe_to_byte(ctx.load_witness(Fr::from(x as u64)))),
+        )
+    });
+    let results = [
+        (chip.and_bytes32(ctx, &a, &b), BitwiseOp::And),
+        (chip.or_bytes32(ctx, &a, &b), BitwiseOp::Or),
+        (chip.xor_bytes32(ctx, &a, &b), BitwiseOp::Xor),
+    ];
+    for (out, op) in results {
+        for (i, byte) in out.to_assigned_bytes().iter().enumerate() {
+            assert_eq!(*byte.value(), Fr::from(op.eval(a_native[i], b_native[i]) as u64));
+        }
+    }
+    circuit.assert_satisfied(K, true);
+}
+
+#[test_case(3, 0xf0, 0x3c, 0xcd ; "wrong output")]
+#[test_case(0, 0, 0, 0 ; "zero row")]
+#[test_case(3, 0x100, 0x00, 0x100 ; "input not a byte")]
+#[test_case(4, 0x01, 0x01, 0x01 ; "unknown operation")]
+fn test_bitwise_lookup_invalid(op: u64, a: u64, b: u64, out: u64) {
+    let mut circuit = LookupCircuit::<Fr, BITWISE_LOOKUP_WIDTH>::new(bitwise_table());
+    let ctx = circuit.cpu.main();
+    let op = ctx.load_constant(Fr::from(op));
+    let [a, b, out] = [a, b, out].map(|v| ctx.load_witness(Fr::from(v)));
+    circuit.lookup_manager.add_lookup(ctx.tag(), [op, a, b, out]);
+    circuit.assert_satisfied(K, false);
+}
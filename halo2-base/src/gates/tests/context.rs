@@ -0,0 +1,291 @@
+use std::any::TypeId;
+use std::collections::HashSet;
+
+use crate::gates::{flex_gate::threads::SinglePhaseCoreManager, GateChip, GateInstructions};
+use crate::halo2_proofs::{halo2curves::bn256::Fr, plonk::Assigned};
+use crate::utils::testing::base_test;
+use crate::virtual_region::copy_constraints::{
+    SharedCopyConstraintCounts, SharedCopyConstraintManager,
+};
+use crate::{
+    AssignedValue, Context,
+    QuantumCell::{self, Constant},
+};
+use rayon::prelude::*;
+use test_case::test_case;
+
+fn new_context(witness_gen_only: bool) -> Context<Fr> {
+    Context::new(witness_gen_only, 0, TypeId::of::<Context<Fr>>(), 0, Default::default())
+}
+
+fn assert_same_cells(a: &[AssignedValue<Fr>], b: &[AssignedValue<Fr>]) {
+    assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b) {
+        assert_eq!(a.value(), b.value());
+        assert_eq!(a.cell, b.cell);
+    }
+}
+
+#[test_case(true ; "witness gen only")]
+#[test_case(false ; "keygen")]
+fn test_assign_witnesses_into(witness_gen_only: bool) {
+    let witnesses = (1..=5u64).map(Fr::from).collect::<Vec<_>>();
+    let mut expected_ctx = new_context(witness_gen_only);
+    let first = expected_ctx.load_witness(Fr::from(7));
+    let expected = expected_ctx.assign_witnesses(witnesses.clone());
+
+    let mut ctx = new_context(witness_gen_only);
+    let mut out = Vec::with_capacity(6);
+    out.push(ctx.load_witness(Fr::from(7)));
+    ctx.assign_witnesses_into(witnesses, &mut out);
+    // existing elements of `out` are kept
+    assert_same_cells(&out[..1], &[first]);
+    assert_same_cells(&out[1..], &expected);
+    assert_eq!(out[1].cell.is_none(), witness_gen_only);
+    assert_eq!(ctx.advice(), expected_ctx.advice());
+    assert_eq!(ctx.selector(), expected_ctx.selector());
+}
+
+#[test_case(true ; "witness gen only")]
+#[test_case(false ; "keygen")]
+fn test_load_witnesses_constant_time(witness_gen_only: bool) {
+    let witnesses = [2u64, 7, 1, 8].map(Fr::from);
+    let mut expected_ctx = new_context(witness_gen_only);
+    expected_ctx.load_zero();
+    let expected = expected_ctx.assign_witnesses(witnesses);
+
+    let mut ctx = new_context(witness_gen_only);
+    ctx.load_zero();
+    let assigned = ctx.load_witnesses_constant_time(&witnesses);
+    assert_same_cells(&assigned, &expected);
+    assert_eq!(ctx.advice(), expected_ctx.advice());
+    // the selector column is extended in both modes, with no gates enabled
+    assert_eq!(ctx.selector_len(), ctx.advice_len());
+    assert!(ctx.selector().iter().all(|q| !q));
+    assert!(ctx.load_witnesses_constant_time(&[]).is_empty());
+}
+
+#[test_case(true ; "witness gen only")]
+#[test_case(false ; "keygen")]
+fn test_num_active_gates(witness_gen_only: bool) {
+    let gate = GateChip::default();
+    let mut ctx = new_context(witness_gen_only);
+    let [a, b] = [3u64, 4].map(|x| ctx.load_witness(Fr::from(x)));
+    assert_eq!(ctx.num_active_gates(), 0);
+    gate.mul(&mut ctx, a, b);
+    gate.add(&mut ctx, a, b);
+    let expected = if witness_gen_only { 0 } else { 2 };
+    assert_eq!(ctx.num_active_gates(), expected);
+    assert_eq!(ctx.advice_len(), 10);
+}
+
+#[test_case(true ; "witness gen only")]
+#[test_case(false ; "keygen")]
+fn test_assign_witnesses_exact(witness_gen_only: bool) {
+    let mut expected_ctx = new_context(witness_gen_only);
+    expected_ctx.load_zero();
+    let expected = expected_ctx.assign_witnesses([3u64, 1, 4].map(Fr::from));
+
+    let mut ctx = new_context(witness_gen_only);
+    ctx.load_zero();
+    let assigned: [AssignedValue<Fr>; 3] = ctx.assign_witnesses_exact([3u64, 1, 4].map(Fr::from));
+    assert_same_cells(&assigned, &expected);
+    assert_eq!(assigned[2].cell.map(|c| c.offset), (!witness_gen_only).then_some(3));
+    assert_eq!(ctx.advice(), expected_ctx.advice());
+    assert_eq!(ctx.selector(), expected_ctx.selector());
+
+    let empty: [AssignedValue<Fr>; 0] = ctx.assign_witnesses_exact([]);
+    assert!(empty.is_empty());
+    assert_eq!(ctx.advice_len(), 4);
+}
+
+#[test]
+fn test_assigned_value_eq_by_cell() {
+    let mut ctx = new_context(false);
+    let a = ctx.load_witness(Fr::from(5));
+    let b = ctx.load_witness(Fr::from(5));
+    // aliases of the same cell
+    let a_copy = a;
+    let a_get = ctx.get(0);
+    assert_eq!(a, a_copy);
+    assert_eq!(a, a_get);
+    // equal values in different cells
+    assert_ne!(a, b);
+
+    let set = HashSet::from([a, a_copy, a_get, b]);
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&a_get));
+}
+
+#[test]
+fn test_assigned_value_eq_across_contexts() {
+    let mut ctx0 = new_context(false);
+    let mut ctx1 = Context::new(false, 0, TypeId::of::<Context<Fr>>(), 1, Default::default());
+    let a = ctx0.load_witness(Fr::from(5));
+    let b = ctx1.load_witness(Fr::from(5));
+    assert_eq!(a.cell.unwrap().offset, b.cell.unwrap().offset);
+    assert_ne!(a, b);
+    assert_eq!(HashSet::from([a, b]).len(), 2);
+}
+
+#[test]
+fn test_assigned_value_eq_witness_gen_only() {
+    let mut ctx = new_context(true);
+    let a = ctx.load_witness(Fr::from(5));
+    let b = ctx.load_witness(Fr::from(5));
+    let c = ctx.load_witness(Fr::from(6));
+    // without cells, values are compared
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_eq!(HashSet::from([a, b, c]).len(), 2);
+}
+
+#[test_case(true ; "witness gen only")]
+#[test_case(false ; "keygen")]
+fn test_rollback(witness_gen_only: bool) {
+    let gate = GateChip::<Fr>::default();
+    let build = |ctx: &mut Context<Fr>, speculate: bool| {
+        let a = ctx.load_witness(Fr::from(3));
+        let b = ctx.load_constant(Fr::from(4));
+        if speculate {
+            let snapshot = ctx.snapshot();
+            let c = gate.mul_add(ctx, a, b, Constant(Fr::from(5)));
+            ctx.constrain_equal(&a, &c);
+            ctx.load_zero();
+            ctx.rollback(snapshot);
+        }
+        let c = gate.add(ctx, a, b);
+        let zero = ctx.load_zero();
+        gate.mul(ctx, c, zero);
+    };
+
+    let mut expected_ctx = new_context(witness_gen_only);
+    build(&mut expected_ctx, false);
+    let mut ctx = new_context(witness_gen_only);
+    build(&mut ctx, true);
+
+    assert_eq!(ctx.advice(), expected_ctx.advice());
+    assert_eq!(ctx.selector(), expected_ctx.selector());
+    let copy_manager = ctx.copy_manager.lock().unwrap();
+    let expected_copy_manager = expected_ctx.copy_manager.lock().unwrap();
+    assert_eq!(copy_manager.advice_equalities, expected_copy_manager.advice_equalities);
+    assert_eq!(copy_manager.constant_equalities, expected_copy_manager.constant_equalities);
+    assert_eq!(copy_manager.advice_equalities.is_empty(), witness_gen_only);
+}
+
+#[test]
+fn test_rollback_keeps_other_contexts() {
+    let mut ctx = new_context(false);
+    let mut other =
+        Context::new(false, 0, TypeId::of::<Context<Fr>>(), 1, ctx.copy_manager.clone());
+    let a = ctx.load_witness(Fr::from(1));
+    let snapshot = ctx.snapshot();
+    let b = ctx.load_witness(Fr::from(1));
+    let c = other.load_witness(Fr::from(1));
+    other.constrain_equal(&a, &c);
+    other.constrain_equal(&b, &c);
+    ctx.rollback(snapshot);
+
+    assert_eq!(ctx.advice_len(), 1);
+    let copy_manager = ctx.copy_manager.lock().unwrap();
+    assert_eq!(copy_manager.advice_equalities, vec![(a.cell.unwrap(), c.cell.unwrap())]);
+}
+
+#[test]
+fn test_quantum_cell_from_bool() {
+    assert!(matches!(QuantumCell::<Fr>::from_bool(false), Constant(c) if c == Fr::from(0)));
+    assert!(matches!(QuantumCell::<Fr>::from_bool(true), Constant(c) if c == Fr::from(1)));
+}
+
+#[test_case(Fr::from(0), true ; "zero")]
+#[test_case(Fr::from(1), false ; "one")]
+#[test_case(-Fr::from(1), false ; "minus one")]
+fn test_is_zero_cell(value: Fr, expected: bool) {
+    let mut ctx = new_context(false);
+    assert_eq!(ctx.load_witness(value).is_zero_cell(), expected);
+    assert_eq!(ctx.load_constant(value).is_zero_cell(), expected);
+}
+
+#[test]
+#[should_panic]
+fn test_is_zero_cell_fraction() {
+    let mut ctx = new_context(false);
+    ctx.assign_cell(QuantumCell::WitnessFraction(Assigned::Rational(Fr::from(1), Fr::from(2))));
+    ctx.last().unwrap().is_zero_cell();
+}
+
+#[test_case(true ; "witness gen only")]
+#[test_case(false ; "keygen")]
+#[allow(deprecated)] // checks the capacity of the columns
+fn test_reserve(witness_gen_only: bool) {
+    let mut ctx = new_context(witness_gen_only);
+    ctx.load_zero();
+    ctx.reserve(100);
+    assert!(ctx.advice.capacity() >= 101);
+    assert_eq!(ctx.selector.capacity() >= 100, !witness_gen_only);
+
+    let advice_ptr = ctx.advice.as_ptr();
+    let gate = GateChip::default();
+    let a = ctx.load_witness(Fr::from(2));
+    for _ in 0..24 {
+        gate.mul(&mut ctx, a, a);
+    }
+    assert_eq!(ctx.advice_len(), 98);
+    // no reallocation happened
+    assert_eq!(ctx.advice.as_ptr(), advice_ptr);
+}
+
+#[test_case(Fr::from(7), true ; "equal")]
+#[test_case(Fr::from(8), false ; "tampered")]
+fn test_constrain_constant(value: Fr, expected: bool) {
+    base_test().expect_satisfied(expected).run_gate(|ctx, _| {
+        let advice_len = ctx.advice_len();
+        let a = ctx.load_witness(value);
+        ctx.constrain_constant(&a, Fr::from(7));
+        // no new cell is assigned
+        assert_eq!(ctx.advice_len(), advice_len + 1);
+    });
+}
+
+#[test]
+fn test_constrain_constant_witness_gen_only() {
+    let copy_manager = SharedCopyConstraintManager::default();
+    let mut ctx = Context::new(true, 0, TypeId::of::<Context<Fr>>(), 0, copy_manager.clone());
+    let a = ctx.load_witness(Fr::from(8));
+    ctx.constrain_constant(&a, Fr::from(7));
+    assert_eq!(copy_manager.count_constant_equalities(), 0);
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_context_send_sync() {
+    assert_send_sync::<Context<Fr>>();
+    assert_send_sync::<AssignedValue<Fr>>();
+}
+
+#[test_case(true ; "witness gen only")]
+#[test_case(false ; "keygen")]
+fn test_contexts_across_rayon_threads(witness_gen_only: bool) {
+    let copy_manager = SharedCopyConstraintManager::<Fr>::default();
+    let mut pool = SinglePhaseCoreManager::new(witness_gen_only, copy_manager.clone());
+    let a = pool.main().load_witness(Fr::from(7));
+    // contexts are created up front so their ids do not depend on scheduling
+    let ctxs = (1..=8).map(|i| pool.new_context(i)).collect::<Vec<_>>();
+    let ctxs = ctxs
+        .into_par_iter()
+        .map(|mut ctx| {
+            let b = ctx.load_witness(Fr::from(7));
+            ctx.constrain_equal(&a, &b);
+            ctx.constrain_constant(&b, Fr::from(7));
+            ctx
+        })
+        .collect::<Vec<_>>();
+    pool.threads.extend(ctxs);
+
+    assert_eq!(pool.thread_count(), 9);
+    assert!(pool.threads.iter().enumerate().all(|(i, ctx)| ctx.id() == i));
+    let expected = if witness_gen_only { 0 } else { 8 };
+    assert_eq!(copy_manager.count_advice_equalities(), expected);
+    assert_eq!(copy_manager.count_constant_equalities(), expected);
+}
@@ -0,0 +1,49 @@
+use crate::gates::{counter::CounterGadget, GateInstructions};
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use crate::utils::testing::base_test;
+use test_case::test_case;
+
+#[test_case(0 => Fr::from(0); "counter 0 times")]
+#[test_case(10 => Fr::from(10); "counter 10 times")]
+pub fn test_counter_increment(n: usize) -> Fr {
+    base_test().run(|ctx, range| {
+        let mut counter = CounterGadget::zero(ctx);
+        for _ in 0..n {
+            counter.increment(ctx, range.gate());
+        }
+        counter.assert_less_than(ctx, range, 11);
+        *counter.current().value()
+    })
+}
+
+#[test_case(&[1, 0, 1, 1] => Fr::from(3); "counter increment_if")]
+pub fn test_counter_increment_if(conds: &[u64]) -> Fr {
+    base_test().run_gate(|ctx, gate| {
+        let mut counter = CounterGadget::zero(ctx);
+        for cond in conds {
+            let cond = ctx.load_witness(Fr::from(*cond));
+            counter.increment_if(ctx, gate, cond);
+        }
+        *counter.current().value()
+    })
+}
+
+#[test]
+pub fn test_counter_beyond_max() {
+    base_test().expect_satisfied(false).run(|ctx, range| {
+        let mut counter = CounterGadget::zero(ctx);
+        for _ in 0..11 {
+            counter.increment(ctx, range.gate());
+        }
+        counter.assert_less_than(ctx, range, 11);
+    })
+}
+
+#[test]
+pub fn test_counter_increment_if_not_bit() {
+    base_test().expect_satisfied(false).run_gate(|ctx, gate| {
+        let mut counter = CounterGadget::zero(ctx);
+        let cond = ctx.load_witness(Fr::from(2));
+        counter.increment_if(ctx, gate, cond);
+    })
+}
@@ -0,0 +1,45 @@
+use super::*;
+use crate::ff::Field;
+use crate::gates::examples::fibonacci::{fib_circuit, fib_circuit_bounded};
+use crate::utils::testing::base_test;
+use test_case::test_case;
+
+fn fib(n: u64) -> Fr {
+    let (mut a, mut b) = (Fr::ZERO, Fr::ONE);
+    for _ in 0..n {
+        (a, b) = (b, a + b);
+    }
+    a
+}
+
+#[test_case(0; "fib_circuit(): 0")]
+#[test_case(1; "fib_circuit(): 1")]
+#[test_case(10; "fib_circuit(): 10")]
+#[test_case(64; "fib_circuit(): table max")]
+pub fn test_fib_circuit(n: u64) {
+    let out = base_test().run_gate(|ctx, chip| {
+        let n = ctx.load_witness(Fr::from(n));
+        *fib_circuit(ctx, chip, n).value()
+    });
+    assert_eq!(out, fib(n));
+}
+
+#[test_case(65, 100; "fib_circuit_bounded(): first recurrence step")]
+#[test_case(100, 100; "fib_circuit_bounded(): max")]
+#[test_case(300, 300; "fib_circuit_bounded(): wraps modulus")]
+#[test_case(2, 3; "fib_circuit_bounded(): max below table")]
+pub fn test_fib_circuit_bounded(n: u64, max_n: usize) {
+    let out = base_test().run_gate(|ctx, chip| {
+        let n = ctx.load_witness(Fr::from(n));
+        *fib_circuit_bounded(ctx, chip, n, max_n).value()
+    });
+    assert_eq!(out, fib(n));
+}
+
+#[test]
+pub fn test_fib_circuit_out_of_range() {
+    base_test().expect_satisfied(false).run_gate(|ctx, chip| {
+        let n = ctx.load_witness(Fr::from(65));
+        fib_circuit(ctx, chip, n);
+    });
+}
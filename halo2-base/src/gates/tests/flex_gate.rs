@@ -1,11 +1,13 @@
 #![allow(clippy::type_complexity)]
 use super::*;
-use crate::utils::biguint_to_fe;
+use crate::ff::{Field, PrimeField};
 use crate::utils::testing::base_test;
-use crate::QuantumCell::{Constant, Witness};
+use crate::utils::{biguint_to_fe, fe_to_biguint};
+use crate::QuantumCell::{Constant, Existing, Witness};
 use crate::{gates::flex_gate::GateInstructions, QuantumCell};
 use itertools::Itertools;
 use num_bigint::BigUint;
+use rand::rngs::OsRng;
 use test_case::test_case;
 
 #[test_case(&[10, 12].map(Fr::from).map(Witness)=> Fr::from(22); "add(): 10 + 12 == 22")]
@@ -42,6 +44,21 @@ pub fn test_neg(a: QuantumCell<Fr>) -> Fr {
     base_test().run_gate(|ctx, chip| *chip.neg(ctx, a).value())
 }
 
+#[test_case(Fr::zero(); "neg(): -0 + 0 == 0")]
+#[test_case(Fr::from(7); "neg(): -7 + 7 == 0")]
+#[test_case(Fr::random(OsRng); "neg(): random")]
+pub fn test_neg_add_is_zero(a: Fr) {
+    base_test().run_gate(|ctx, chip| {
+        let a = ctx.load_witness(a);
+        let start = ctx.advice_len();
+        let neg = chip.neg(ctx, a);
+        // the zero is a constant in the gate, not an extra advice cell
+        assert_eq!(ctx.advice_len() - start, 4);
+        let sum = chip.add(ctx, neg, a);
+        chip.assert_is_const(ctx, &sum, &Fr::zero());
+    })
+}
+
 #[test_case(&[10, 12].map(Fr::from).map(Witness) => Fr::from(120) ; "mul(): 10 * 12 == 120")]
 #[test_case(&[1, 1].map(Fr::from).map(Witness) => Fr::from(1) ; "mul(): 1 * 1 == 1")]
 pub fn test_mul(inputs: &[QuantumCell<Fr>]) -> Fr {
@@ -53,12 +70,49 @@ pub fn test_mul_add(inputs: &[QuantumCell<Fr>]) -> Fr {
     base_test().run_gate(|ctx, chip| *chip.mul_add(ctx, inputs[0], inputs[1], inputs[2]).value())
 }
 
+#[test]
+pub fn test_mul_add_cell_kinds() {
+    // every combination of `Existing`, `Witness` and `Constant` inputs
+    for kinds in (0..3).map(|_| 0..3).multi_cartesian_product() {
+        base_test().run_gate(|ctx, chip| {
+            let inputs = kinds
+                .iter()
+                .zip([2u64, 3, 5].map(Fr::from))
+                .map(|(kind, v)| match kind {
+                    0 => Existing(ctx.load_witness(v)),
+                    1 => Witness(v),
+                    _ => Constant(v),
+                })
+                .collect_vec();
+            let (advice_len, num_gates) = (ctx.advice_len(), ctx.num_active_gates());
+            let out = chip.mul_add(ctx, inputs[0], inputs[1], inputs[2]);
+            assert_eq!(*out.value(), Fr::from(11), "kinds = {kinds:?}");
+            // exactly one gate row
+            assert_eq!(ctx.advice_len() - advice_len, 4);
+            assert_eq!(ctx.num_active_gates() - num_gates, 1);
+        });
+    }
+}
+
 #[test_case(&[0, 10].map(Fr::from).map(Witness) => Fr::from(10); "mul_not(): (1 - 0) * 10 == 10")]
 #[test_case(&[1, 10].map(Fr::from).map(Witness) => Fr::from(0); "mul_not(): (1 - 1) * 10 == 0")]
 pub fn test_mul_not(inputs: &[QuantumCell<Fr>]) -> Fr {
     base_test().run_gate(|ctx, chip| *chip.mul_not(ctx, inputs[0], inputs[1]).value())
 }
 
+#[test_case(0 => Fr::from(5); "conditional_add(): false")]
+#[test_case(1 => Fr::from(12); "conditional_add(): true")]
+pub fn test_conditional_add(cond: u64) -> Fr {
+    base_test().run_gate(|ctx, chip| {
+        let [a, b, cond] = [5, 7, cond].map(|x| ctx.load_witness(Fr::from(x)));
+        let advice_len = ctx.advice_len();
+        let out = chip.conditional_add(ctx, a, b, cond);
+        // a single gate, instead of the 12 cells of `add` followed by `select`
+        assert_eq!(ctx.advice_len() - advice_len, 4);
+        *out.value()
+    })
+}
+
 #[test_case(Fr::from(0), true; "assert_bit(0)")]
 #[test_case(Fr::from(1), true; "assert_bit(1)")]
 #[test_case(Fr::from(2), false; "assert_bit(2)")]
@@ -84,11 +138,137 @@ pub fn test_assert_is_const(inputs: &[Fr]) {
     });
 }
 
+#[test]
+pub fn test_assert_is_const_rows() {
+    const NUM_ASSERTS: usize = 1 << 10;
+    base_test().k(12).run_gate(|ctx, chip| {
+        let vals = ctx.assign_witnesses((0..NUM_ASSERTS as u64).map(Fr::from));
+        let advice_len = ctx.advice_len();
+        // loading each constant into a cell first
+        for (i, val) in vals.iter().enumerate() {
+            let c = ctx.load_constant(Fr::from(i as u64));
+            ctx.constrain_equal(val, &c);
+        }
+        assert_eq!(ctx.advice_len() - advice_len, NUM_ASSERTS);
+        let advice_len = ctx.advice_len();
+        for (i, val) in vals.into_iter().enumerate() {
+            chip.assert_is_const(ctx, &val, &Fr::from(i as u64));
+        }
+        // saves one advice cell per assertion
+        assert_eq!(ctx.advice_len(), advice_len);
+    });
+}
+
+#[test_case(&[3, 3, 3], true; "assert_all_equal(): equal")]
+#[test_case(&[3, 3, 4], false; "assert_all_equal(): last differs")]
+#[test_case(&[3], true; "assert_all_equal(): single element")]
+pub fn test_assert_all_equal(vals: &[u64], expect_satisfied: bool) {
+    base_test().expect_satisfied(expect_satisfied).run_gate(|ctx, chip| {
+        let vals = ctx.assign_witnesses(vals.iter().map(|v| Fr::from(*v)));
+        chip.assert_all_equal(ctx, &vals);
+    });
+}
+
+#[test_case(0, 1, true ; "boolean_assert_ne(): 0 != 1")]
+#[test_case(1, 0, true ; "boolean_assert_ne(): 1 != 0")]
+#[test_case(0, 0, false ; "boolean_assert_ne(): 0 == 0")]
+#[test_case(1, 1, false ; "boolean_assert_ne(): 1 == 1")]
+pub fn test_boolean_assert_ne(a: u64, b: u64, expect_satisfied: bool) {
+    base_test().expect_satisfied(expect_satisfied).run_gate(|ctx, chip| {
+        let [a, b] = [a, b].map(|x| ctx.load_witness(Fr::from(x)));
+        chip.boolean_assert_ne(ctx, a, b);
+    });
+}
+
+#[test]
+#[should_panic(expected = "boolean_assert_ne inputs must be bits")]
+#[cfg(debug_assertions)]
+pub fn test_boolean_assert_ne_not_bits() {
+    base_test().run_gate(|ctx, chip| {
+        // satisfies `a + b = 1` without being bits
+        let [a, b] = [Fr::from(2), -Fr::from(1)].map(|x| ctx.load_witness(x));
+        chip.boolean_assert_ne(ctx, a, b);
+    });
+}
+
+#[test_case(&[3, 3, 3], true; "assert_all_equal_to(): equal")]
+#[test_case(&[4, 3, 3], false; "assert_all_equal_to(): first differs")]
+pub fn test_assert_all_equal_to(vals: &[u64], expect_satisfied: bool) {
+    base_test().expect_satisfied(expect_satisfied).run_gate(|ctx, chip| {
+        let vals = ctx.assign_witnesses(vals.iter().map(|v| Fr::from(*v)));
+        chip.assert_all_equal_to(ctx, &vals, &Fr::from(3));
+    });
+}
+
+#[test_case(vec![0, 0, 1, 0], true, true; "assert_bits_sum_to_one(): one hot")]
+#[test_case(vec![1], true, true; "assert_bits_sum_to_one(): single bit set")]
+#[test_case(vec![0, 0, 0], false, true; "assert_bits_sum_to_one(): all zero")]
+#[test_case(vec![], false, true; "assert_bits_sum_to_one(): empty")]
+#[test_case(vec![1, 0, 1], false, false; "assert_bits_sum_to_one(): two bits set")]
+#[test_case(vec![0, 2, 0], false, false; "assert_bits_sum_to_one(): not a bit")]
+pub fn test_assert_bits_sum_to_one(bits: Vec<u64>, one_hot: bool, at_most_one: bool) {
+    for (sum_to_one, expect_satisfied) in [(true, one_hot), (false, at_most_one)] {
+        base_test().expect_satisfied(expect_satisfied).run_gate(|ctx, chip| {
+            let bits = ctx.assign_witnesses(bits.iter().map(|b| Fr::from(*b)));
+            if sum_to_one {
+                chip.assert_bits_sum_to_one(ctx, &bits);
+            } else {
+                chip.assert_bits_sum_to_at_most_one(ctx, &bits);
+            }
+        });
+    }
+}
+
+#[test]
+pub fn test_assert_bits_sum_to_one_non_boolean() {
+    // the sum is 1, but the elements are not bits
+    for sum_to_one in [true, false] {
+        base_test().expect_satisfied(false).run_gate(|ctx, chip| {
+            let bits = ctx.assign_witnesses([Fr::from(2), -Fr::one()]);
+            if sum_to_one {
+                chip.assert_bits_sum_to_one(ctx, &bits);
+            } else {
+                chip.assert_bits_sum_to_at_most_one(ctx, &bits);
+            }
+        });
+    }
+}
+
+#[test_case(vec![0, 0, 1, 0], true; "assert_at_least_one_true(): one bit set")]
+#[test_case(vec![1, 1, 1], true; "assert_at_least_one_true(): all set")]
+#[test_case(vec![1], true; "assert_at_least_one_true(): single bit set")]
+#[test_case(vec![0, 0, 0], false; "assert_at_least_one_true(): all zero")]
+#[test_case(vec![], false; "assert_at_least_one_true(): empty")]
+#[test_case(vec![0, 2, 0], false; "assert_at_least_one_true(): not a bit")]
+pub fn test_assert_at_least_one_true(bits: Vec<u64>, expect_satisfied: bool) {
+    base_test().expect_satisfied(expect_satisfied).run_gate(|ctx, chip| {
+        let bits = ctx.assign_witnesses(bits.into_iter().map(Fr::from));
+        chip.assert_at_least_one_true(ctx, &bits);
+    });
+}
+
 #[test_case((vec![Witness(Fr::one()); 5], vec![Witness(Fr::one()); 5]) => Fr::from(5) ; "inner_product(): 1 * 1 + ... + 1 * 1 == 5")]
 pub fn test_inner_product(input: (Vec<QuantumCell<Fr>>, Vec<QuantumCell<Fr>>)) -> Fr {
     base_test().run_gate(|ctx, chip| *chip.inner_product(ctx, input.0, input.1).value())
 }
 
+#[test_case(vec![], vec![] => (Fr::zero(), Fr::zero()) ; "inner_product_simple(): empty")]
+#[test_case([1u64, 2, 3].map(Fr::from).to_vec(), [4u64, 5, 6].map(Fr::from).to_vec() => (Fr::from(32), Fr::from(32)) ; "inner_product_simple(): <[1,2,3],[4,5,6]> == 32")]
+#[test_case([7u64, 8].map(Fr::from).to_vec(), [1u64, 1].map(Fr::from).to_vec() => (Fr::from(15), Fr::from(15)) ; "inner_product_simple(): b starts with 1")]
+pub fn test_inner_product_simple(a: Vec<Fr>, b: Vec<Fr>) -> (Fr, Fr) {
+    base_test().run_gate(|ctx, chip| {
+        let a = ctx.assign_witnesses(a);
+        let b = ctx.assign_witnesses(b);
+        let start = ctx.advice_len();
+        let simple = chip.inner_product_simple(ctx, &a, &b);
+        let num_cells = ctx.advice_len() - start;
+        let general = chip.inner_product(ctx, a, b.into_iter().map(Existing));
+        // the general version saves cells only when `b` starts with a constant one
+        assert_eq!(ctx.advice_len() - start - num_cells, num_cells);
+        (*simple.value(), *general.value())
+    })
+}
+
 #[test_case((vec![Witness(Fr::one()); 5], vec![Witness(Fr::one()); 5]) => (Fr::from(5), Fr::from(1)); "inner_product_left_last(): 1 * 1 + ... + 1 * 1 == (5, 1)")]
 pub fn test_inner_product_left_last(
     input: (Vec<QuantumCell<Fr>>, Vec<QuantumCell<Fr>>),
@@ -127,6 +307,23 @@ pub fn test_sum_products_with_coeff_and_var(
         .run_gate(|ctx, chip| *chip.sum_products_with_coeff_and_var(ctx, input.0, input.1).value())
 }
 
+#[test_case(&[(2, 3), (4, 5)], (6, 7) => Fr::from(68) ; "sum_products_with_var_product(): 2 * 3 + 4 * 5 + 6 * 7 == 68")]
+#[test_case(&[(2, 3)], (6, 1) => Fr::from(12) ; "sum_products_with_var_product(): d == 1")]
+#[test_case(&[], (6, 7) => Fr::from(42) ; "sum_products_with_var_product(): no pairs")]
+pub fn test_sum_products_with_var_product(ab_pairs: &[(u64, u64)], cd: (u64, u64)) -> Fr {
+    base_test().run_gate(|ctx, chip| {
+        let ab_pairs = ab_pairs
+            .iter()
+            .map(|&(a, b)| {
+                let [a, b] = [a, b].map(|x| Existing(ctx.load_witness(Fr::from(x))));
+                (a, b)
+            })
+            .collect_vec();
+        let [c, d] = [cd.0, cd.1].map(|x| Existing(ctx.load_witness(Fr::from(x))));
+        *chip.sum_products_with_var_product(ctx, &ab_pairs, c, d).value()
+    })
+}
+
 #[test_case(&[1, 0].map(Fr::from).map(Witness) => Fr::from(0) ; "and(): 1 && 0 == 0")]
 #[test_case(&[1, 1].map(Fr::from).map(Witness) => Fr::from(1) ; "and(): 1 && 1 == 1")]
 pub fn test_and(inputs: &[QuantumCell<Fr>]) -> Fr {
@@ -139,11 +336,60 @@ pub fn test_not(a: QuantumCell<Fr>) -> Fr {
     base_test().run_gate(|ctx, chip| *chip.not(ctx, a).value())
 }
 
+#[test_case(vec![] => (Fr::from(1), Fr::from(0)); "multi_and/or(): empty")]
+#[test_case(vec![0] => (Fr::from(0), Fr::from(0)); "multi_and/or(): single 0")]
+#[test_case(vec![1] => (Fr::from(1), Fr::from(1)); "multi_and/or(): single 1")]
+#[test_case(vec![1, 1, 1, 1] => (Fr::from(1), Fr::from(1)); "multi_and/or(): all ones")]
+#[test_case(vec![0, 0, 0, 0, 0, 0, 0, 0] => (Fr::from(0), Fr::from(0)); "multi_and/or(): all zeros")]
+#[test_case(vec![1, 1, 1, 1, 1, 1, 1, 0] => (Fr::from(0), Fr::from(1)); "multi_and/or(): last zero")]
+#[test_case(vec![0, 0, 1] => (Fr::from(0), Fr::from(1)); "multi_and/or(): odd length")]
+pub fn test_multi_and_or(bits: Vec<u64>) -> (Fr, Fr) {
+    base_test().run_gate(|ctx, chip| {
+        let bits = ctx.assign_witnesses(bits.into_iter().map(Fr::from));
+        let and = chip.multi_and(ctx, &bits);
+        let or = chip.multi_or(ctx, &bits);
+        (*and.value(), *or.value())
+    })
+}
+
+#[test_case(true; "multi_and(): not a bit")]
+#[test_case(false; "multi_or(): not a bit")]
+pub fn test_multi_and_or_not_bits(is_and: bool) {
+    base_test().expect_satisfied(false).run_gate(|ctx, chip| {
+        let bits = ctx.assign_witnesses([1, 2].map(Fr::from));
+        if is_and {
+            chip.multi_and(ctx, &bits);
+        } else {
+            chip.multi_or(ctx, &bits);
+        }
+    });
+}
+
 #[test_case(&[2, 3, 1].map(Fr::from).map(Witness) => Fr::from(2); "select(): 2 ? 3 : 1 == 2")]
 pub fn test_select(inputs: &[QuantumCell<Fr>]) -> Fr {
     base_test().run_gate(|ctx, chip| *chip.select(ctx, inputs[0], inputs[1], inputs[2]).value())
 }
 
+#[test_case(1, 3, 7 => Fr::from(3); "if_then_else(): true")]
+#[test_case(0, 3, 7 => Fr::from(7); "if_then_else(): false")]
+pub fn test_if_then_else(cond: u64, then_val: u64, else_val: u64) -> Fr {
+    base_test().run_gate(|ctx, chip| {
+        let cond = ctx.load_witness(Fr::from(cond));
+        let [then_val, else_val] = [then_val, else_val].map(|v| Witness(Fr::from(v)));
+        *chip.if_then_else(ctx, cond, then_val, else_val).value()
+    })
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "if_then_else: cond must be boolean")]
+pub fn test_if_then_else_non_bool() {
+    base_test().run_gate(|ctx, chip| {
+        let cond = ctx.load_witness(Fr::from(2));
+        chip.if_then_else(ctx, cond, Constant(Fr::one()), Constant(Fr::zero()));
+    })
+}
+
 #[test_case(&[0, 1, 0].map(Fr::from).map(Witness) => Fr::from(0); "or_and(): 0 || (1 && 0) == 0")]
 #[test_case(&[1, 0, 1].map(Fr::from).map(Witness) => Fr::from(1); "or_and(): 1 || (0 && 1) == 1")]
 #[test_case(&[1, 1, 1].map(Fr::from).map(Witness) => Fr::from(1); "or_and(): 1 || (1 && 1) == 1")]
@@ -161,6 +407,8 @@ pub fn test_bits_to_indicator(bits: &[u8]) -> Vec<Fr> {
 }
 
 #[test_case(Witness(Fr::from(0)),3 => [1,0,0].map(Fr::from).to_vec(); "idx_to_indicator(): 0 -> [1, 0, 0]")]
+#[test_case(Witness(Fr::from(2)),3 => [0,0,1].map(Fr::from).to_vec(); "idx_to_indicator(): 2 -> [0, 0, 1]")]
+#[test_case(Witness(Fr::from(3)),3 => [0,0,0].map(Fr::from).to_vec(); "idx_to_indicator(): 3 -> [0, 0, 0]")]
 pub fn test_idx_to_indicator(idx: QuantumCell<Fr>, len: usize) -> Vec<Fr> {
     base_test().run_gate(|ctx, chip| {
         chip.idx_to_indicator(ctx, idx, len).iter().map(|a| *a.value()).collect()
@@ -168,6 +416,8 @@ pub fn test_idx_to_indicator(idx: QuantumCell<Fr>, len: usize) -> Vec<Fr> {
 }
 
 #[test_case((0..3).map(Fr::from).map(Witness).collect(), Witness(Fr::one()) => Fr::from(1); "select_by_indicator(1): [0, 1, 2] -> 1")]
+#[test_case((5..9).map(Fr::from).map(Witness).collect(), Witness(Fr::from(3)) => Fr::from(8); "select_by_indicator(3): [5, 6, 7, 8] -> 8")]
+#[test_case((5..9).map(Fr::from).map(Witness).collect(), Witness(Fr::from(4)) => Fr::from(0); "select_by_indicator(4): [5, 6, 7, 8] -> 0")]
 pub fn test_select_by_indicator(array: Vec<QuantumCell<Fr>>, idx: QuantumCell<Fr>) -> Fr {
     base_test().run_gate(|ctx, chip| {
         let a = chip.idx_to_indicator(ctx, idx, array.len());
@@ -175,6 +425,25 @@ pub fn test_select_by_indicator(array: Vec<QuantumCell<Fr>>, idx: QuantumCell<Fr
     })
 }
 
+#[test_case(0; "select_by_indicator() matches select tree: 0")]
+#[test_case(5; "select_by_indicator() matches select tree: 5")]
+#[test_case(7; "select_by_indicator() matches select tree: 7")]
+pub fn test_select_by_indicator_matches_select_tree(idx: u64) {
+    base_test().run_gate(|ctx, chip| {
+        let array = ctx.assign_witnesses((0..8u64).map(|i| Fr::from(10 * i + 1)));
+        let idx = ctx.load_witness(Fr::from(idx));
+        let ind = chip.idx_to_indicator(ctx, idx, array.len());
+        let selected = chip.select_by_indicator(ctx, array.clone(), ind);
+        // binary tree of `select`s, from the least significant bit of `idx`
+        let bits = chip.num_to_bits(ctx, idx, 3);
+        let mut layer = array;
+        for bit in bits {
+            layer = layer.chunks(2).map(|pair| chip.select(ctx, pair[1], pair[0], bit)).collect();
+        }
+        assert_eq!(selected.value(), layer[0].value());
+    })
+}
+
 #[test_case((0..3).map(Fr::from).map(Witness).collect(), Witness(Fr::from(1)) => Fr::from(1); "select_from_idx(): [0, 1, 2] -> 1")]
 pub fn test_select_from_idx(array: Vec<QuantumCell<Fr>>, idx: QuantumCell<Fr>) -> Fr {
     base_test().run_gate(|ctx, chip| *chip.select_from_idx(ctx, array, idx).value())
@@ -194,6 +463,8 @@ pub fn test_select_array_by_indicator(array2d: Vec<Vec<Fr>>, idx: Fr) -> Vec<Fr>
 }
 
 #[test_case(Fr::zero() => Fr::from(1); "is_zero(): 0 -> 1")]
+#[test_case(Fr::one() => Fr::from(0); "is_zero(): 1 -> 0")]
+#[test_case(-Fr::one() => Fr::from(0); "is_zero(): -1 -> 0")]
 pub fn test_is_zero(input: Fr) -> Fr {
     base_test().run_gate(|ctx, chip| {
         let input = ctx.load_witness(input);
@@ -201,11 +472,82 @@ pub fn test_is_zero(input: Fr) -> Fr {
     })
 }
 
+#[test]
+pub fn test_is_zero_random_is_bit() {
+    let inputs = [Fr::zero(), Fr::random(OsRng), Fr::random(OsRng), Fr::random(OsRng)];
+    base_test().run_gate(|ctx, chip| {
+        for input in inputs {
+            let input = ctx.load_witness(input);
+            let out = chip.is_zero(ctx, input);
+            let expected = if input.value().is_zero_vartime() { Fr::one() } else { Fr::zero() };
+            assert_eq!(*out.value(), expected);
+            chip.assert_bit(ctx, out);
+        }
+    })
+}
+
+// `out` is witnessed at offsets 0 and 6 of the `is_zero` gate, so prank both copies
+#[test_case(Fr::zero(), Fr::zero(); "is_zero(): 0 -> 0 unsatisfied")]
+#[test_case(Fr::zero(), Fr::from(2); "is_zero(): 0 -> 2 unsatisfied")]
+#[test_case(Fr::from(5), Fr::one(); "is_zero(): 5 -> 1 unsatisfied")]
+#[test_case(Fr::from(5), Fr::from(2); "is_zero(): 5 -> 2 unsatisfied")]
+pub fn test_is_zero_prank_output(input: Fr, prank: Fr) {
+    base_test().expect_satisfied(false).run_gate(|ctx, chip| {
+        let input = ctx.load_witness(input);
+        let offset = ctx.advice_len();
+        chip.is_zero(ctx, input);
+        ctx.debug_prank(offset, prank);
+        ctx.debug_prank(offset + 6, prank);
+    })
+}
+
 #[test_case(&[1, 1].map(Fr::from).map(Witness) => Fr::one(); "is_equal(): 1 == 1")]
 pub fn test_is_equal(inputs: &[QuantumCell<Fr>]) -> Fr {
     base_test().run_gate(|ctx, chip| *chip.is_equal(ctx, inputs[0], inputs[1]).value())
 }
 
+#[test_case(Fr::from(1), Fr::from(1); "is_equal_raw(): 1 == 1")]
+#[test_case(Fr::from(1), Fr::from(2); "is_equal_raw(): 1 != 2")]
+#[test_case(Fr::zero(), -Fr::one(); "is_equal_raw(): 0 != -1")]
+#[test_case(Fr::random(OsRng), Fr::random(OsRng); "is_equal_raw(): random")]
+pub fn test_is_equal_raw(a: Fr, b: Fr) {
+    base_test().run_gate(|ctx, chip| {
+        let [a, b] = [a, b].map(|x| ctx.load_witness(x));
+        let start = ctx.advice_len();
+        let raw = chip.is_equal_raw(ctx, a, b);
+        assert_eq!(ctx.advice_len() - start, 11);
+        let expected = chip.is_equal(ctx, a, b);
+        assert_eq!(raw.value(), expected.value());
+        chip.assert_bit(ctx, raw);
+    })
+}
+
+// `out` is witnessed at offsets 7, 9 and 10 of the `is_equal_raw` gate, so prank every copy
+#[test_case(Fr::from(3), Fr::from(3), Fr::zero(); "is_equal_raw(): 3 == 3 -> 0 unsatisfied")]
+#[test_case(Fr::from(3), Fr::from(4), Fr::one(); "is_equal_raw(): 3 != 4 -> 1 unsatisfied")]
+pub fn test_is_equal_raw_prank_output(a: Fr, b: Fr, prank: Fr) {
+    base_test().expect_satisfied(false).run_gate(|ctx, chip| {
+        let [a, b] = [a, b].map(|x| ctx.load_witness(x));
+        let offset = ctx.advice_len();
+        chip.is_equal_raw(ctx, a, b);
+        for i in [7, 9, 10] {
+            ctx.debug_prank(offset + i, prank);
+        }
+    })
+}
+
+// with k = 6 each advice column only has 55 usable rows, so consecutive regions straddle column breaks
+#[test]
+pub fn test_is_equal_raw_break_points() {
+    base_test().k(6).run_gate(|ctx, chip| {
+        for i in 0..20u64 {
+            let [a, b] = [i, i % 3].map(|x| ctx.load_witness(Fr::from(x)));
+            let raw = chip.is_equal_raw(ctx, a, b);
+            assert_eq!(*raw.value(), Fr::from(i == i % 3));
+        }
+    })
+}
+
 #[test_case(6, 3 => [0,1,1].map(Fr::from).to_vec(); "num_to_bits(): 6")]
 pub fn test_num_to_bits(num: usize, bits: usize) -> Vec<Fr> {
     base_test().run_gate(|ctx, chip| {
@@ -214,6 +556,115 @@ pub fn test_num_to_bits(num: usize, bits: usize) -> Vec<Fr> {
     })
 }
 
+#[test_case(&[0, 1, 1] => Fr::from(6); "bits_to_num(): 6")]
+#[test_case(&[1] => Fr::from(1); "bits_to_num(): 1")]
+#[test_case(&[] => Fr::from(0); "bits_to_num(): empty")]
+pub fn test_bits_to_num(bits: &[u64]) -> Fr {
+    base_test().run_gate(|ctx, chip| {
+        let bits = ctx.assign_witnesses(bits.iter().map(|b| Fr::from(*b)));
+        *chip.bits_to_num(ctx, &bits).value()
+    })
+}
+
+#[test]
+fn test_bits_to_num_not_bit() {
+    base_test().expect_satisfied(false).run_gate(|ctx, chip| {
+        let bits = ctx.assign_witnesses([1, 2, 0].map(Fr::from));
+        chip.bits_to_num(ctx, &bits);
+    })
+}
+
+#[test_case(1; "bits_to_num(num_to_bits()): 1 bit")]
+#[test_case(8; "bits_to_num(num_to_bits()): 8 bits")]
+#[test_case(64; "bits_to_num(num_to_bits()): 64 bits")]
+#[test_case(Fr::NUM_BITS as usize; "bits_to_num(num_to_bits()): NUM_BITS bits")]
+pub fn test_bits_to_num_round_trip(range_bits: usize) {
+    let x = if range_bits <= 64 {
+        Fr::from(rand::random::<u64>() >> (64 - range_bits))
+    } else {
+        Fr::random(rand::rngs::OsRng)
+    };
+    base_test().run_gate(|ctx, chip| {
+        let x = ctx.load_witness(x);
+        let bits = chip.num_to_bits(ctx, x, range_bits);
+        let out = chip.bits_to_num(ctx, &bits);
+        ctx.constrain_equal(&out, &x);
+    })
+}
+
+#[test_case(vec![] ; "linear_combination(): empty")]
+#[test_case(vec![1] ; "linear_combination(): single weight one")]
+#[test_case(vec![5, 0, 7] ; "linear_combination(): zero weight")]
+#[test_case(vec![3, 1, 4, 1, 5] ; "linear_combination(): weight one in the middle")]
+#[test_case((0..16).map(|i| 1 << i).collect() ; "linear_combination(): powers of two")]
+pub fn test_linear_combination(weights: Vec<u64>) {
+    let weights = weights.into_iter().map(Fr::from).collect_vec();
+    let values = weights.iter().map(|_| Fr::random(rand::rngs::OsRng)).collect_vec();
+    let expected = weights.iter().zip(&values).map(|(w, v)| *w * v).sum::<Fr>();
+    base_test().run_gate(|ctx, chip| {
+        let values = ctx.assign_witnesses(values);
+        let inputs = weights.iter().copied().zip(values.iter().copied()).collect_vec();
+        let start = ctx.advice_len();
+        let out = chip.linear_combination(ctx, &inputs);
+        let cells = ctx.advice_len() - start;
+        assert_eq!(*out.value(), expected);
+
+        // repeated `mul_add` calls
+        let start = ctx.advice_len();
+        let naive = inputs.iter().fold(Constant(Fr::zero()), |acc, (w, a)| {
+            Existing(chip.mul_add(ctx, Constant(*w), *a, acc))
+        });
+        assert_eq!(*naive.value(), expected);
+        let naive_cells = ctx.advice_len() - start;
+        assert_eq!(naive_cells, 4 * inputs.len());
+        let num_terms = weights.iter().filter(|w| **w != Fr::zero()).count();
+        assert!(cells <= 3 * num_terms + 1);
+        if inputs.len() >= 2 {
+            assert!(cells < naive_cells);
+        }
+    });
+}
+
+#[test_case(0; "horner_eval(): no coefficients")]
+#[test_case(1; "horner_eval(): constant")]
+#[test_case(2; "horner_eval(): linear")]
+#[test_case(17; "horner_eval(): degree 16")]
+#[test_case(65; "horner_eval(): degree 64")]
+pub fn test_horner_eval(num_coeffs: usize) {
+    let coeffs = (0..num_coeffs).map(|_| Fr::random(rand::rngs::OsRng)).collect_vec();
+    let x = Fr::random(rand::rngs::OsRng);
+    let expected = coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c);
+    let out = base_test().run_gate(|ctx, chip| {
+        // alternate witness and constant coefficients
+        let coeffs = coeffs
+            .iter()
+            .enumerate()
+            .map(|(i, c)| if i % 2 == 0 { Existing(ctx.load_witness(*c)) } else { Constant(*c) })
+            .collect_vec();
+        let x = ctx.load_witness(x);
+        let start = ctx.advice_len();
+        let out = chip.horner_eval(ctx, &coeffs, x);
+        if num_coeffs >= 2 {
+            assert_eq!(ctx.advice_len() - start, 4 * (num_coeffs - 1));
+        }
+
+        if num_coeffs == 0 {
+            return *out.value();
+        }
+        // naive: powers of x, then an inner product
+        let mut powers = vec![Constant(Fr::one()), Existing(x)];
+        for _ in 2..num_coeffs {
+            let next = chip.mul(ctx, *powers.last().unwrap(), x);
+            powers.push(Existing(next));
+        }
+        powers.truncate(num_coeffs);
+        let naive = chip.inner_product(ctx, coeffs, powers);
+        assert_eq!(out.value(), naive.value());
+        *out.value()
+    });
+    assert_eq!(out, expected);
+}
+
 #[test_case(Fr::from(3), BigUint::from(3u32), 4 => Fr::from(27); "pow_var(): 3^3 = 27")]
 pub fn test_pow_var(a: Fr, exp: BigUint, max_bits: usize) -> Fr {
     assert!(exp.bits() <= max_bits as u64);
@@ -223,3 +674,205 @@ pub fn test_pow_var(a: Fr, exp: BigUint, max_bits: usize) -> Fr {
         *chip.pow_var(ctx, a, exp, max_bits).value()
     })
 }
+
+#[test]
+fn test_pow_var_field_order_minus_one() {
+    let a = Fr::random(rand::rngs::OsRng);
+    let exp = fe_to_biguint(&-Fr::ONE);
+    let out = test_pow_var(a, exp.clone(), Fr::NUM_BITS as usize);
+    assert_eq!(out, a.pow_vartime(exp.to_u64_digits()));
+    // Fermat's little theorem
+    assert_eq!(out, Fr::ONE);
+}
+
+#[test_case(128; "dot_product_and_norm(): 128 elements")]
+#[test_case(1; "dot_product_and_norm(): 1 element")]
+#[test_case(0; "dot_product_and_norm(): empty")]
+pub fn test_dot_product_and_norm(len: usize) {
+    let mut rng = rand::rngs::OsRng;
+    let a = (0..len).map(|_| Fr::random(&mut rng)).collect_vec();
+    let b = (0..len).map(|_| Fr::random(&mut rng)).collect_vec();
+    base_test().k(12).run_gate(|ctx, chip| {
+        let a = ctx.assign_witnesses(a);
+        let b = ctx.assign_witnesses(b);
+        let (dot, norm) = chip.dot_product_and_norm(ctx, &a, &b);
+        let expected_dot = chip.inner_product(ctx, a.clone(), b.into_iter().map(Existing));
+        let expected_norm = chip.inner_product(ctx, a.clone(), a.into_iter().map(Existing));
+        assert_eq!(dot.value(), expected_dot.value());
+        assert_eq!(norm.value(), expected_norm.value());
+    });
+}
+
+#[test_case(3, 0xffff; "pow_by_repeated_squaring(): 3^(2^16 - 1)")]
+#[test_case(7, 0b1010; "pow_by_repeated_squaring(): 7^10")]
+#[test_case(5, 1; "pow_by_repeated_squaring(): 5^1")]
+#[test_case(5, 0; "pow_by_repeated_squaring(): 5^0")]
+pub fn test_pow_by_repeated_squaring(a: u64, exp: u64) {
+    let exp_bits = (0..64).map(|i| (exp >> i) & 1 == 1).collect_vec();
+    let out = base_test().run_gate(|ctx, chip| {
+        let a = ctx.load_witness(Fr::from(a));
+        *chip.pow_by_repeated_squaring(ctx, a, &exp_bits).value()
+    });
+    assert_eq!(out, Fr::from(a).pow_vartime([exp]));
+}
+
+#[test_case(3, 0; "field_pow_const(): 3^0")]
+#[test_case(3, 1; "field_pow_const(): 3^1")]
+#[test_case(3, 3; "field_pow_const(): 3^3")]
+#[test_case(3, 5; "field_pow_const(): 3^5")]
+#[test_case(7, 255; "field_pow_const(): 7^255")]
+#[test_case(5, u64::MAX; "field_pow_const(): 5^(2^64 - 1)")]
+pub fn test_field_pow_const(a: u64, exp: u64) {
+    let out = base_test().run_gate(|ctx, chip| {
+        let a = ctx.load_witness(Fr::from(a));
+        *chip.field_pow_const(ctx, a, exp).value()
+    });
+    assert_eq!(out, Fr::from(a).pow_vartime([exp]));
+}
+
+#[test_case(3 => (8, 8); "field_pow_const() rows: exp = 3")]
+#[test_case(5 => (12, 12); "field_pow_const() rows: exp = 5")]
+#[test_case(255 => (40, 56); "field_pow_const() rows: exp = 255")]
+pub fn test_field_pow_const_rows(exp: u64) -> (usize, usize) {
+    let exp_bits = (0..64).map(|i| (exp >> i) & 1 == 1).collect_vec();
+    base_test().run_gate(|ctx, chip| {
+        let a = ctx.load_witness(Fr::from(7));
+        let start = ctx.advice_len();
+        chip.field_pow_const(ctx, a, exp);
+        let chain_rows = ctx.advice_len() - start;
+        let start = ctx.advice_len();
+        chip.pow_by_repeated_squaring(ctx, a, &exp_bits);
+        (chain_rows, ctx.advice_len() - start)
+    })
+}
+
+#[test_case([0, 0, 0, 0, 0, 0] => [0, 1, 2, 3].map(Fr::from); "benes_network(): identity")]
+#[test_case([0, 0, 1, 1, 1, 1] => [3, 2, 1, 0].map(Fr::from); "benes_network(): reverse")]
+#[test_case([1, 1, 0, 0, 0, 0] => [1, 0, 3, 2].map(Fr::from); "benes_network(): swap pairs")]
+#[test_case([0, 0, 1, 0, 0, 0] => [2, 1, 0, 3].map(Fr::from); "benes_network(): swap 0 and 2")]
+pub fn test_benes_network(control_bits: [u64; 6]) -> [Fr; 4] {
+    base_test().run_gate(|ctx, chip| {
+        let inputs = ctx.assign_witnesses([0, 1, 2, 3].map(Fr::from)).try_into().unwrap();
+        let control_bits = ctx.assign_witnesses(control_bits.map(Fr::from));
+        chip.benes_network::<4>(ctx, inputs, &control_bits).map(|a| *a.value())
+    })
+}
+
+#[test]
+pub fn test_benes_network_non_bit_control() {
+    base_test().expect_satisfied(false).run_gate(|ctx, chip| {
+        let inputs = ctx.assign_witnesses([0, 1].map(Fr::from)).try_into().unwrap();
+        let control_bits = ctx.assign_witnesses([Fr::from(2)]);
+        chip.benes_network::<2>(ctx, inputs, &control_bits);
+    })
+}
+
+#[test_case(&[1, 2, 3, 4]; "lagrange_eval_on_domain(): small domain")]
+#[test_case(&[0, 7, 13, 100, 1000]; "lagrange_eval_on_domain(): larger domain")]
+#[test_case(&[5, 9, 2, 11]; "lagrange_eval_on_domain(): unsorted domain")]
+pub fn test_lagrange_eval_on_domain(domain: &[u64]) {
+    // f(t) = 3t^3 - 2t^2 + 5t + 7
+    let f = |t: Fr| Fr::from(3) * t * t * t - Fr::from(2) * t * t + Fr::from(5) * t + Fr::from(7);
+    let domain = domain.iter().map(|d| Fr::from(*d)).collect_vec();
+    let mut rng = rand::rngs::OsRng;
+    let points = (0..5).map(|_| Fr::random(&mut rng)).chain([domain[1]]).collect_vec();
+    base_test().k(12).run_gate(|ctx, chip| {
+        let evals = ctx.assign_witnesses(domain.iter().map(|d| f(*d)));
+        for point in points {
+            let x = ctx.load_witness(point);
+            let eval = chip.lagrange_eval_on_domain(ctx, &domain, &evals, x);
+            assert_eq!(*eval.value(), f(point));
+        }
+    })
+}
+
+#[test]
+pub fn test_lagrange_eval_on_domain_single_point() {
+    base_test().run_gate(|ctx, chip| {
+        let evals = ctx.assign_witnesses([Fr::from(42)]);
+        let x = ctx.load_witness(Fr::from(9));
+        let eval = chip.lagrange_eval_on_domain(ctx, &[Fr::from(3)], &evals, x);
+        assert_eq!(*eval.value(), Fr::from(42));
+    })
+}
+
+#[test_case(&[1, 2, 3], true; "assert_nonzero_batch(): all nonzero")]
+#[test_case(&[1, 0, 3], false; "assert_nonzero_batch(): zero at index 1")]
+#[test_case(&[], true; "assert_nonzero_batch(): empty")]
+pub fn test_assert_nonzero_batch(vals: &[u64], expect_satisfied: bool) {
+    base_test().expect_satisfied(expect_satisfied).run_gate(|ctx, chip| {
+        let vals = ctx.assign_witnesses(vals.iter().map(|v| Fr::from(*v)));
+        chip.assert_nonzero_batch(ctx, &vals);
+    })
+}
+
+#[test_case(Fr::from(5), true; "assert_not_zero(): 5")]
+#[test_case(-Fr::from(1), true; "assert_not_zero(): -1")]
+#[test_case(Fr::from(0), false; "assert_not_zero(): 0")]
+pub fn test_assert_not_zero(a: Fr, expect_satisfied: bool) {
+    base_test().expect_satisfied(expect_satisfied).run_gate(|ctx, chip| {
+        let a = ctx.load_witness(a);
+        let inv = chip.assert_not_zero(ctx, a);
+        assert_eq!(a.value() * inv.value(), Fr::from(!a.value().is_zero_vartime() as u64));
+    })
+}
+
+#[test_case(0, 0 => Fr::from(10); "ternary_select(): a")]
+#[test_case(1, 0 => Fr::from(20); "ternary_select(): b")]
+#[test_case(0, 1 => Fr::from(30); "ternary_select(): c")]
+pub fn test_ternary_select(s0: u64, s1: u64) -> Fr {
+    base_test().run_gate(|ctx, chip| {
+        let [a, b, c, s0, s1] = [10, 20, 30, s0, s1].map(|x| ctx.load_witness(Fr::from(x)));
+        *chip.ternary_select(ctx, a, b, c, s0, s1).value()
+    })
+}
+
+#[test_case(1, 1; "ternary_select(): both selectors set")]
+#[test_case(2, 0; "ternary_select(): selector not a bit")]
+pub fn test_ternary_select_invalid(s0: u64, s1: u64) {
+    base_test().expect_satisfied(false).run_gate(|ctx, chip| {
+        let [a, b, c, s0, s1] = [10, 20, 30, s0, s1].map(|x| ctx.load_witness(Fr::from(x)));
+        chip.ternary_select(ctx, a, b, c, s0, s1);
+    })
+}
+
+#[test_case(3, 5, 0 => (Fr::from(3), Fr::from(5)); "conditional_swap(): unequal, no swap")]
+#[test_case(3, 5, 1 => (Fr::from(5), Fr::from(3)); "conditional_swap(): unequal, swap")]
+#[test_case(4, 4, 0 => (Fr::from(4), Fr::from(4)); "conditional_swap(): equal, no swap")]
+#[test_case(4, 4, 1 => (Fr::from(4), Fr::from(4)); "conditional_swap(): equal, swap")]
+pub fn test_conditional_swap(a: u64, b: u64, swap: u64) -> (Fr, Fr) {
+    base_test().run_gate(|ctx, chip| {
+        let [a, b, swap] = [a, b, swap].map(|x| ctx.load_witness(Fr::from(x)));
+        let (first, second) = chip.conditional_swap(ctx, a, b, swap);
+        (*first.value(), *second.value())
+    })
+}
+
+#[test_case(&[1, 2, 3, 4], true; "batch_invert(): distinct")]
+#[test_case(&[7, 7, 7], true; "batch_invert(): repeated")]
+#[test_case(&[5], true; "batch_invert(): single element")]
+#[test_case(&[], true; "batch_invert(): empty")]
+#[test_case(&[3, 0, 9], false; "batch_invert(): zero element")]
+pub fn test_batch_invert(vals: &[u64], expect_satisfied: bool) {
+    base_test().expect_satisfied(expect_satisfied).run_gate(|ctx, chip| {
+        let vals = ctx.assign_witnesses(vals.iter().map(|v| Fr::from(*v)));
+        let invs = chip.batch_invert(ctx, &vals);
+        assert_eq!(invs.len(), vals.len());
+        if expect_satisfied {
+            for (val, inv) in vals.iter().zip(invs) {
+                assert_eq!(*inv.value(), val.value().invert().unwrap());
+            }
+        }
+    })
+}
+
+#[test_case(&[1, 2, 3, 4], true; "assert_pairwise_not_equal(): distinct")]
+#[test_case(&[1, 2, 3, 1], false; "assert_pairwise_not_equal(): pair (0, 3)")]
+#[test_case(&[1, 2, 2, 4], false; "assert_pairwise_not_equal(): pair (1, 2)")]
+#[test_case(&[5], true; "assert_pairwise_not_equal(): single element")]
+pub fn test_assert_pairwise_not_equal(vals: &[u64], expect_satisfied: bool) {
+    base_test().expect_satisfied(expect_satisfied).run_gate(|ctx, chip| {
+        let vals = ctx.assign_witnesses(vals.iter().map(|v| Fr::from(*v)));
+        chip.assert_pairwise_not_equal(ctx, &vals);
+    })
+}
@@ -0,0 +1,33 @@
+use crate::gates::folding::FoldingAccumulator;
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use crate::utils::testing::base_test;
+
+#[test]
+pub fn test_fold_once() {
+    let (u, instance) = base_test().run_gate(|ctx, gate| {
+        let acc = ctx.assign_witnesses([1, 2, 3].map(Fr::from));
+        let acc = FoldingAccumulator::from_instance(ctx, acc);
+        let new_instance = ctx.assign_witnesses([4, 5, 6].map(Fr::from));
+        let challenge = ctx.load_witness(Fr::from(10));
+        let acc = acc.fold(ctx, gate, &new_instance, challenge);
+        (*acc.u().value(), acc.instance().iter().map(|x| *x.value()).collect::<Vec<_>>())
+    });
+    assert_eq!(u, Fr::from(11));
+    assert_eq!(instance, [41, 52, 63].map(Fr::from));
+}
+
+#[test]
+pub fn test_fold_twice() {
+    let u = base_test().run_gate(|ctx, gate| {
+        let acc = ctx.assign_witnesses([Fr::from(7)]);
+        let mut acc = FoldingAccumulator::from_instance(ctx, acc);
+        for r in [2, 3] {
+            let new_instance = ctx.assign_witnesses([Fr::from(1)]);
+            let challenge = ctx.load_witness(Fr::from(r));
+            acc = acc.fold(ctx, gate, &new_instance, challenge);
+        }
+        assert_eq!(*acc.instance()[0].value(), Fr::from(12));
+        *acc.u().value()
+    });
+    assert_eq!(u, Fr::from(6));
+}
@@ -125,3 +125,29 @@ fn test_multithread_range() {
         },
     );
 }
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "Cell from phase 1 cannot be used in a context of phase 0")]
+fn test_later_phase_cell_in_earlier_phase() {
+    use crate::gates::flex_gate::threads::MultiPhaseCoreManager;
+
+    let mut builder = MultiPhaseCoreManager::<Fr>::new(false);
+    let a = builder.main(1).load_witness(Fr::ONE);
+    let chip = GateChip::default();
+    let ctx = builder.main(0);
+    chip.add(ctx, a, a);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn test_earlier_phase_cell_in_later_phase() {
+    use crate::gates::flex_gate::threads::MultiPhaseCoreManager;
+
+    let mut builder = MultiPhaseCoreManager::<Fr>::new(false);
+    let a = builder.main(0).load_witness(Fr::ONE);
+    let chip = GateChip::default();
+    let ctx = builder.main(1);
+    chip.add(ctx, a, a);
+    builder.clear();
+}
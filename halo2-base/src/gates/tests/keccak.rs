@@ -0,0 +1,105 @@
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use test_case::test_case;
+
+use super::xor::{XorCircuit, K};
+use crate::{
+    gates::{
+        keccak::KeccakChip,
+        xor::{xor_table, XorChip},
+        GateChip,
+    },
+    safe_types::{SafeBytes32, SafeTypeChip},
+};
+
+fn hex_to_bytes(hex: &str) -> Vec<u8> {
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+}
+
+#[test_case(b"", "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470" ; "keccak256(): empty")]
+#[test_case(b"abc", "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45" ; "keccak256(): abc")]
+#[test_case(&[0; 136], "3a5912a7c5faa06ee4fe906253e339467a9ce87d533c65be3c15cb231cdb25f9" ; "keccak256(): two blocks")]
+fn test_keccak256(input: &[u8], expected: &str) {
+    let mut circuit = XorCircuit::new(xor_table());
+    let xor = XorChip::new(circuit.lookup_manager.clone());
+    let gate = GateChip::default();
+    let chip = KeccakChip::new(&gate, &xor);
+    let ctx = circuit.cpu.main();
+    let input = input
+        .iter()
+        .map(|b| SafeTypeChip::unsafe_to_byte(ctx.load_witness(Fr::from(*b as u64))))
+        .collect::<Vec<_>>();
+    let digest = chip.keccak256_bytes32(ctx, &input);
+    let expected = hex_to_bytes(expected);
+    for (byte, expected) in digest.to_bytes().iter().zip(expected) {
+        assert_eq!(*byte.as_ref().value(), Fr::from(expected as u64));
+    }
+    circuit.assert_satisfied(K, true);
+}
+
+#[test]
+fn test_keccak256_wrong_digest() {
+    let mut circuit = XorCircuit::new(xor_table());
+    let xor = XorChip::new(circuit.lookup_manager.clone());
+    let gate = GateChip::default();
+    let chip = KeccakChip::new(&gate, &xor);
+    let ctx = circuit.cpu.main();
+    let input = [ctx.load_witness(Fr::from(0x61))].map(SafeTypeChip::unsafe_to_byte);
+    let digest: SafeBytes32<Fr> = chip.keccak256_bytes32(ctx, &input);
+    // keccak256("a") starts with 0x3a
+    digest.to_bytes()[0].as_ref().debug_prank(ctx, Fr::from(0));
+    circuit.assert_satisfied(K, false);
+}
+
+// `hashStruct(mail)` of the example in EIP-712, where the type hashes are constants as in a contract:
+//
+// Person(string name,address wallet)
+// Mail(Person from,Person to,string contents)
+#[test]
+fn test_keccak256_eip712_struct_hash() {
+    const MAIL_TYPEHASH: &str = "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2";
+    const PERSON_TYPEHASH: &str =
+        "b9d8c78acf9b987311de6c7b45bb6a9c8e1bf361fa7fd3467a2163f994c79500";
+    const MAIL_HASH: &str = "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e";
+
+    let mut circuit = XorCircuit::new(xor_table());
+    let xor = XorChip::new(circuit.lookup_manager.clone());
+    let gate = GateChip::default();
+    let chip = KeccakChip::new(&gate, &xor);
+    let ctx = circuit.cpu.main();
+    let mut load_bytes = |bytes: &[u8], constant: bool| {
+        bytes
+            .iter()
+            .map(|b| {
+                let b = Fr::from(*b as u64);
+                let b = if constant { ctx.load_constant(b) } else { ctx.load_witness(b) };
+                SafeTypeChip::unsafe_to_byte(b)
+            })
+            .collect::<Vec<_>>()
+    };
+    let mail_typehash = load_bytes(&hex_to_bytes(MAIL_TYPEHASH), true);
+    let person_typehash = load_bytes(&hex_to_bytes(PERSON_TYPEHASH), true);
+    // an address is encoded as a `uint160`, left padded to 32 bytes
+    let mut encode_address =
+        |hex: &str| load_bytes(&[vec![0; 12], hex_to_bytes(hex)].concat(), false);
+    let wallets = [
+        encode_address("cd2a3d9f938e13cd947ec05abc7fe734df8dd826"),
+        encode_address("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"),
+    ];
+    let names = [b"Cow".as_slice(), b"Bob".as_slice()].map(|name| load_bytes(name, false));
+    let contents = load_bytes(b"Hello, Bob!", false);
+
+    // strings are encoded as the hash of their bytes
+    let [from, to] = [0, 1].map(|i| {
+        let name_hash = chip.keccak256(ctx, &names[i]);
+        let encoded = [&person_typehash[..], &name_hash[..], &wallets[i][..]].concat();
+        chip.keccak256(ctx, &encoded)
+    });
+    let contents_hash = chip.keccak256(ctx, &contents);
+    let encoded = [&mail_typehash[..], &from[..], &to[..], &contents_hash[..]].concat();
+    let mail_hash = chip.keccak256(ctx, &encoded);
+
+    for (byte, expected) in mail_hash.iter().zip(hex_to_bytes(MAIL_HASH)) {
+        assert_eq!(*byte.as_ref().value(), Fr::from(expected as u64));
+    }
+    circuit.assert_satisfied(K, true);
+}
@@ -0,0 +1,164 @@
+use crate::halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use test_case::test_case;
+
+use crate::{
+    gates::{
+        flex_gate::{threads::SinglePhaseCoreManager, FlexGateConfig, FlexGateConfigParams},
+        lookup::{
+            aes::{aes_sbox, aes_sbox_table, AesSboxChip, AES_SBOX_LOOKUP_WIDTH},
+            LookupArgument, LookupArgumentBuilder,
+        },
+    },
+    utils::ScalarField,
+    virtual_region::{lookups::LookupAnyManager, manager::VirtualRegionManager},
+};
+
+// the S-box table has 256 rows
+const K: usize = 9;
+
+#[derive(Clone, Debug)]
+pub(super) struct LookupCircuitConfig<F: ScalarField> {
+    cpu: FlexGateConfig<F>,
+    lookup: LookupArgument<F>,
+}
+
+#[derive(Clone, Default)]
+pub(super) struct LookupCircuitParams {
+    cpu: FlexGateConfigParams,
+    copy_columns: usize,
+}
+
+/// Test circuit with the basic gate and a single lookup into a fixed `table` of `WIDTH` columns.
+///
+/// Chips under test add their lookups to a clone of `lookup_manager`.
+pub(super) struct LookupCircuit<F: ScalarField, const WIDTH: usize> {
+    pub(super) cpu: SinglePhaseCoreManager<F>,
+    pub(super) lookup_manager: LookupAnyManager<F, WIDTH>,
+    table: Vec<[F; WIDTH]>,
+    params: LookupCircuitParams,
+}
+
+impl<F: ScalarField, const WIDTH: usize> LookupCircuit<F, WIDTH> {
+    pub(super) fn new(table: Vec<[F; WIDTH]>) -> Self {
+        let cpu = SinglePhaseCoreManager::new(false, Default::default());
+        let lookup_manager = LookupAnyManager::new(false, cpu.copy_manager.clone());
+        Self { cpu, lookup_manager, table, params: Default::default() }
+    }
+
+    /// Auto-configures the circuit for degree `k` after all witnesses are assigned.
+    fn configure_params(&mut self, k: usize) {
+        let usable_rows = (1 << k) - 11; // guess
+        self.params.cpu = FlexGateConfigParams {
+            k,
+            num_advice_per_phase: vec![self.cpu.total_advice() / usable_rows + 1],
+            num_fixed: 1,
+        };
+        self.params.copy_columns = self.lookup_manager.num_advice_chunks(usable_rows);
+    }
+
+    pub(super) fn assert_satisfied(mut self, k: usize, expected: bool) {
+        self.configure_params(k);
+        let prover = MockProver::run(k as u32, &self, vec![]).unwrap();
+        if expected {
+            prover.assert_satisfied();
+        } else {
+            assert!(prover.verify().is_err());
+        }
+    }
+}
+
+impl<F: ScalarField, const WIDTH: usize> Circuit<F> for LookupCircuit<F, WIDTH> {
+    type Config = LookupCircuitConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = LookupCircuitParams;
+
+    fn params(&self) -> Self::Params {
+        self.params.clone()
+    }
+
+    fn without_witnesses(&self) -> Self {
+        unimplemented!()
+    }
+
+    fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
+        let k = params.cpu.k;
+        let mut cpu = FlexGateConfig::configure(meta, params.cpu);
+        let mut builder = LookupArgumentBuilder::new("lookup");
+        for _ in 0..params.copy_columns * WIDTH {
+            builder = builder.add_input_column(meta.advice_column());
+        }
+        for _ in 0..WIDTH {
+            builder = builder.add_table_column(meta.fixed_column());
+        }
+        let lookup = builder.build();
+        lookup.configure(meta);
+        cpu.max_rows = (1 << k) - meta.minimum_rows();
+        LookupCircuitConfig { cpu, lookup }
+    }
+
+    fn configure(_: &mut ConstraintSystem<F>) -> Self::Config {
+        unreachable!()
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "lookup",
+            |mut region| {
+                config.lookup.assign_table(&mut region, &self.table);
+                self.cpu.assign_raw(
+                    &(config.cpu.basic_gates[0].clone(), config.cpu.max_rows),
+                    &mut region,
+                );
+                let input_columns = config.lookup.input_columns::<WIDTH>();
+                self.lookup_manager.assign_raw(&input_columns, &mut region);
+                self.cpu.copy_manager.assign_raw(&config.cpu.constants, &mut region);
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn test_aes_sbox_native() {
+    for (x, y) in [(0x00, 0x63), (0x01, 0x7c), (0x53, 0xed), (0xc9, 0xdd), (0xff, 0x16)] {
+        assert_eq!(aes_sbox(x), y);
+    }
+    // the S-box is a permutation
+    let mut image = (0..=255u8).map(aes_sbox).collect::<Vec<_>>();
+    image.sort();
+    assert_eq!(image, (0..=255u8).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_aes_sbox_lookup() {
+    let mut circuit = LookupCircuit::<Fr, AES_SBOX_LOOKUP_WIDTH>::new(aes_sbox_table());
+    let chip = AesSboxChip::new(circuit.lookup_manager.clone());
+    let ctx = circuit.cpu.main();
+    for x in [0x00u8, 0x01, 0x53, 0xff, 0x53] {
+        let x_assigned = ctx.load_witness(Fr::from(x as u64));
+        let y = chip.sbox(ctx, x_assigned);
+        assert_eq!(*y.value(), Fr::from(aes_sbox(x) as u64));
+    }
+    circuit.assert_satisfied(K, true);
+}
+
+#[test_case(0x00, 0x00 ; "zero row")]
+#[test_case(0x53, 0xee ; "wrong output")]
+#[test_case(0x100, 0x63 ; "input not a byte")]
+fn test_aes_sbox_lookup_invalid(x: u64, y: u64) {
+    let mut circuit = LookupCircuit::<Fr, AES_SBOX_LOOKUP_WIDTH>::new(aes_sbox_table());
+    let ctx = circuit.cpu.main();
+    let tag = ctx.load_constant(Fr::from(1));
+    let [x, y] = [x, y].map(|v| ctx.load_witness(Fr::from(v)));
+    circuit.lookup_manager.add_lookup(ctx.tag(), [tag, x, y]);
+    circuit.assert_satisfied(K, false);
+}
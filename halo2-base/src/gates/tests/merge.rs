@@ -0,0 +1,61 @@
+use std::{mem, sync::Arc};
+
+use crate::gates::flex_gate::threads::SinglePhaseCoreManager;
+use crate::gates::{GateChip, GateInstructions};
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use crate::utils::testing::base_test;
+use crate::virtual_region::copy_constraints::{
+    SharedCopyConstraintCounts, SharedCopyConstraintManager,
+};
+use crate::QuantumCell::Constant;
+use test_case::test_case;
+
+// a sub-circuit with copy constraints within and across its two threads
+fn sub_circuit(tampered: bool) -> SinglePhaseCoreManager<Fr> {
+    let gate = GateChip::default();
+    let mut core = SinglePhaseCoreManager::new(false, SharedCopyConstraintManager::default());
+    let ctx = core.main();
+    let x = ctx.load_witness(Fr::from(7));
+    let y = ctx.load_constant(Fr::from(7));
+    ctx.constrain_equal(&x, &y);
+    let ctx = core.new_thread();
+    let z = ctx.load_witness(Fr::from(if tampered { 8 } else { 7 }));
+    ctx.constrain_equal(&x, &z);
+    gate.mul(ctx, z, Constant(Fr::from(2)));
+    core
+}
+
+#[test_case(false; "merged")]
+#[test_case(true; "merged tampered")]
+fn test_merge_core_managers(tampered: bool) {
+    base_test().expect_satisfied(!tampered).run_builder(|pool, _| {
+        let gate = GateChip::default();
+        let ctx = pool.main();
+        let a = ctx.load_witness(Fr::from(3));
+        let b = ctx.load_constant(Fr::from(5));
+        gate.add(ctx, a, b);
+
+        let other = sub_circuit(tampered);
+        let advice_equalities = pool.copy_manager.count_advice_equalities()
+            + other.copy_manager.count_advice_equalities();
+        let constant_equalities = pool.copy_manager.count_constant_equalities()
+            + other.copy_manager.count_constant_equalities();
+        let total_advice = pool.total_advice() + other.total_advice();
+        *pool = mem::take(pool).merge(other);
+
+        assert_eq!(pool.thread_count(), 3);
+        assert!(pool.threads.iter().enumerate().all(|(i, ctx)| ctx.id() == i));
+        assert!(pool.threads.iter().all(|ctx| Arc::ptr_eq(&ctx.copy_manager, &pool.copy_manager)));
+        assert_eq!(pool.total_advice(), total_advice);
+        assert_eq!(pool.copy_manager.count_advice_equalities(), advice_equalities);
+        assert_eq!(pool.copy_manager.count_constant_equalities(), constant_equalities);
+    });
+}
+
+#[test]
+#[should_panic(expected = "cannot merge regions sharing a copy manager")]
+fn test_merge_shared_copy_manager() {
+    let core = sub_circuit(false);
+    let other = SinglePhaseCoreManager::new(false, core.copy_manager.clone());
+    core.merge(other);
+}
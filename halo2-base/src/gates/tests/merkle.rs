@@ -0,0 +1,147 @@
+use crate::ff::Field;
+use crate::gates::merkle::{
+    two_to_one_batch, two_to_one_compress, MerkleChip, TWO_TO_ONE_DOMAIN_TAG,
+};
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use crate::poseidon::hasher::{spec::OptimizedPoseidonSpec, PoseidonHasher};
+use crate::poseidon::PoseidonChip;
+use crate::utils::testing::base_test;
+use itertools::Itertools;
+use pse_poseidon::Poseidon;
+use rand::rngs::OsRng;
+use test_case::test_case;
+
+const NUM_LEAVES: usize = 16;
+
+fn native_compress(left: Fr, right: Fr) -> Fr {
+    let mut sponge = Poseidon::<Fr, 3, 2>::new(8, 57);
+    sponge.update(&[Fr::from(TWO_TO_ONE_DOMAIN_TAG), left, right]);
+    sponge.squeeze()
+}
+
+fn native_root(leaves: &[Fr]) -> Fr {
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level.chunks(2).map(|pair| native_compress(pair[0], pair[1])).collect();
+    }
+    level[0]
+}
+
+fn new_hasher() -> PoseidonHasher<Fr, 3, 2> {
+    PoseidonHasher::new(OptimizedPoseidonSpec::new::<8, 57, 0>())
+}
+
+#[test]
+fn test_two_to_one_compress() {
+    let [left, right] = [Fr::random(OsRng), Fr::random(OsRng)];
+    let res = base_test().k(12).run_gate(|ctx, gate| {
+        let mut hasher = new_hasher();
+        hasher.initialize_consts(ctx, gate);
+        let [left, right] = [left, right].map(|x| ctx.load_witness(x));
+        *two_to_one_compress(ctx, gate, &hasher, left, right).value()
+    });
+    assert_eq!(res, native_compress(left, right));
+    // the tag separates the compression from a plain hash of two elements
+    let mut sponge = Poseidon::<Fr, 3, 2>::new(8, 57);
+    sponge.update(&[left, right]);
+    assert_ne!(res, sponge.squeeze());
+}
+
+#[test]
+fn test_two_to_one_merkle_root() {
+    let leaves = (0..NUM_LEAVES).map(|_| Fr::random(OsRng)).collect_vec();
+    let root = base_test().k(14).run_gate(|ctx, gate| {
+        let mut hasher = new_hasher();
+        hasher.initialize_consts(ctx, gate);
+        let mut level = ctx.assign_witnesses(leaves.clone());
+        while level.len() > 1 {
+            let pairs = level.iter().copied().tuples().collect_vec();
+            level = two_to_one_batch(ctx, gate, &hasher, &pairs);
+        }
+        *level[0].value()
+    });
+    assert_eq!(root, native_root(&leaves));
+}
+
+#[test]
+fn test_two_to_one_batch_matches_compress() {
+    let values = (0..4).map(|_| Fr::random(OsRng)).collect_vec();
+    base_test().k(12).run_gate(|ctx, gate| {
+        let mut hasher = new_hasher();
+        hasher.initialize_consts(ctx, gate);
+        let values = ctx.assign_witnesses(values);
+        let pairs = [(values[0], values[1]), (values[2], values[3])];
+        let batched = two_to_one_batch(ctx, gate, &hasher, &pairs);
+        for (&(left, right), hash) in pairs.iter().zip(batched) {
+            let single = two_to_one_compress(ctx, gate, &hasher, left, right);
+            assert_eq!(single.value(), hash.value());
+        }
+    });
+}
+
+const DEPTH: usize = 8;
+
+/// Returns all levels of the tree with `leaves`, from the leaves up to the root.
+fn native_tree(leaves: Vec<Fr>) -> Vec<Vec<Fr>> {
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let level = levels.last().unwrap();
+        let next = level.chunks(2).map(|pair| native_compress(pair[0], pair[1])).collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Returns the siblings and position bits of the leaf at `idx`.
+fn native_proof(levels: &[Vec<Fr>], idx: usize) -> (Vec<Fr>, Vec<Fr>) {
+    levels[..levels.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(level, nodes)| {
+            let node_idx = idx >> level;
+            (nodes[node_idx ^ 1], Fr::from((node_idx & 1) as u64))
+        })
+        .unzip()
+}
+
+fn verify_membership(root: Fr, leaf: Fr, path: Vec<Fr>, indices: Vec<Fr>) -> Fr {
+    base_test().k(15).lookup_bits(8).run(|ctx, range| {
+        let spec = OptimizedPoseidonSpec::<Fr, 3, 2>::new::<8, 57, 0>();
+        let chip = MerkleChip::new(PoseidonChip::new(ctx, spec, range));
+        let [root, leaf] = [root, leaf].map(|x| ctx.load_witness(x));
+        let path = ctx.assign_witnesses(path);
+        let indices = ctx.assign_witnesses(indices);
+        *chip.verify_membership(ctx, root, leaf, &path, &indices).value()
+    })
+}
+
+#[test_case(0; "first leaf")]
+#[test_case(173; "middle leaf")]
+#[test_case((1 << DEPTH) - 1; "last leaf")]
+fn test_verify_membership(idx: usize) {
+    let levels = native_tree((0..1 << DEPTH).map(|_| Fr::random(OsRng)).collect());
+    let root = levels[DEPTH][0];
+    assert_eq!(root, native_root(&levels[0]));
+    let (path, indices) = native_proof(&levels, idx);
+    assert_eq!(verify_membership(root, levels[0][idx], path.clone(), indices.clone()), Fr::ONE);
+
+    // a different leaf, or the right leaf at the wrong position, is not a member
+    assert_eq!(verify_membership(root, levels[0][idx ^ 1], path, indices.clone()), Fr::ZERO);
+    let (path, _) = native_proof(&levels, idx ^ 2);
+    assert_eq!(verify_membership(root, levels[0][idx], path, indices), Fr::ZERO);
+}
+
+#[test]
+fn test_verify_membership_index_not_bit() {
+    let levels = native_tree((0..1 << DEPTH).map(|_| Fr::random(OsRng)).collect());
+    let (path, mut indices) = native_proof(&levels, 5);
+    indices[0] = Fr::from(2);
+    base_test().k(15).lookup_bits(8).expect_satisfied(false).run(|ctx, range| {
+        let spec = OptimizedPoseidonSpec::<Fr, 3, 2>::new::<8, 57, 0>();
+        let chip = MerkleChip::new(PoseidonChip::new(ctx, spec, range));
+        let [root, leaf] = [levels[DEPTH][0], levels[0][5]].map(|x| ctx.load_witness(x));
+        let path = ctx.assign_witnesses(path);
+        let indices = ctx.assign_witnesses(indices);
+        chip.verify_membership(ctx, root, leaf, &path, &indices);
+    })
+}
@@ -0,0 +1,57 @@
+use crate::ff::Field;
+use crate::gates::mimc::MimcBlockCipher;
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use crate::utils::testing::base_test;
+use test_case::test_case;
+
+const ROUNDS: usize = 91;
+
+fn native_encrypt(cipher: &MimcBlockCipher<Fr, ROUNDS>, plaintext: Fr, key: Fr) -> Fr {
+    let mut x = plaintext;
+    for c in cipher.round_constants() {
+        x = (x + key + c).pow_vartime([7]);
+    }
+    x + key
+}
+
+#[test_case(Fr::from(0), Fr::from(0); "mimc encrypt zero")]
+#[test_case(Fr::from(12345), Fr::from(678910); "mimc encrypt")]
+#[test_case(-Fr::one(), Fr::from(3); "mimc encrypt max")]
+pub fn test_mimc_encrypt(plaintext: Fr, key: Fr) {
+    let cipher = MimcBlockCipher::<Fr, ROUNDS>::new();
+    let expected = native_encrypt(&cipher, plaintext, key);
+    let ciphertext = base_test().run_gate(|ctx, gate| {
+        let [plaintext, key] = [plaintext, key].map(|x| ctx.load_witness(x));
+        *cipher.encrypt(ctx, gate, plaintext, key).value()
+    });
+    assert_eq!(ciphertext, expected);
+}
+
+#[test_case(Fr::from(12345), Fr::from(678910); "mimc decrypt")]
+#[test_case(-Fr::one(), Fr::from(3); "mimc decrypt max")]
+pub fn test_mimc_decrypt(plaintext: Fr, key: Fr) {
+    let cipher = MimcBlockCipher::<Fr, ROUNDS>::new();
+    let ciphertext = native_encrypt(&cipher, plaintext, key);
+    let decrypted = base_test().run_gate(|ctx, gate| {
+        let [ciphertext, key] = [ciphertext, key].map(|x| ctx.load_witness(x));
+        *cipher.decrypt(ctx, gate, ciphertext, key).value()
+    });
+    assert_eq!(decrypted, plaintext);
+}
+
+#[test]
+pub fn test_mimc_cbc_round_trip() {
+    let cipher = MimcBlockCipher::<Fr, ROUNDS>::new();
+    let plaintext = [1u64, 1, 2, 3, 5].map(Fr::from);
+    let (ciphertext, decrypted) = base_test().run_gate(|ctx, gate| {
+        let blocks = ctx.assign_witnesses(plaintext);
+        let [key, iv] = [Fr::from(42), Fr::from(7)].map(|x| ctx.load_witness(x));
+        let ciphertext = cipher.encrypt_cbc(ctx, gate, &blocks, key, iv);
+        let decrypted = cipher.decrypt_cbc(ctx, gate, &ciphertext, key, iv);
+        (ciphertext.iter().map(|c| *c.value()).collect::<Vec<_>>(), decrypted)
+    });
+    // identical plaintext blocks encrypt differently under CBC
+    assert_ne!(ciphertext[0], ciphertext[1]);
+    assert_eq!(ciphertext[0], native_encrypt(&cipher, plaintext[0] + Fr::from(7), Fr::from(42)));
+    assert_eq!(decrypted.iter().map(|p| *p.value()).collect::<Vec<_>>(), plaintext);
+}
@@ -1,9 +1,21 @@
 use crate::halo2_proofs::halo2curves::bn256::Fr;
 
+mod bitwise;
+mod context;
+mod counter;
+mod fibonacci;
 mod flex_gate;
+mod folding;
 mod general;
 mod idx_to_indicator;
+mod keccak;
+mod lookup;
+mod merge;
+mod merkle;
+mod mimc;
 mod neg_prop;
 mod pos_prop;
 mod range;
+mod statistics;
 mod utils;
+mod xor;
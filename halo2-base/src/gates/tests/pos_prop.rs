@@ -94,6 +94,15 @@ prop_compose! {
     }
 }
 
+prop_compose! {
+    fn inner_product_simple_strat(max_length: usize)
+        (len in 0..=max_length)
+        (a in vec(rand_fr(), len), b in vec(rand_fr(), len))
+    -> (Vec<Fr>, Vec<Fr>) {
+        (a, b)
+    }
+}
+
 proptest! {
     // Flex Gate Positive Tests
     #[test]
@@ -131,6 +140,14 @@ proptest! {
         prop_assert_eq!(result, ground_truth);
     }
 
+    #[test]
+    fn prop_test_inner_product_simple((a, b) in inner_product_simple_strat(64)) {
+        let ground_truth = inner_product_ground_truth(&a, &b);
+        let (simple, general) = flex_gate::test_inner_product_simple(a, b);
+        prop_assert_eq!(simple, ground_truth);
+        prop_assert_eq!(simple, general);
+    }
+
     #[test]
     fn prop_test_mul_add(inputs in vec(rand_witness(), 3)) {
         let ground_truth = mul_add_ground_truth(inputs.as_slice());
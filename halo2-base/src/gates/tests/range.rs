@@ -1,9 +1,15 @@
 use super::*;
-use crate::utils::biguint_to_fe;
+use crate::ff::{Field, PrimeField};
 use crate::utils::testing::base_test;
-use crate::QuantumCell::Witness;
-use crate::{gates::range::RangeInstructions, QuantumCell};
+use crate::utils::{biguint_to_fe, fe_to_biguint, modulus};
+use crate::QuantumCell::{Constant, Witness};
+use crate::{
+    gates::range::{OverflowError, RangeInstructions},
+    QuantumCell,
+};
 use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use rand::rngs::OsRng;
 use test_case::test_case;
 
 #[test_case(16, 10, Fr::zero(), 0; "range_check() 0 bits")]
@@ -15,6 +21,24 @@ pub fn test_range_check(k: usize, lookup_bits: usize, a_val: Fr, range_bits: usi
     })
 }
 
+#[test_case(8, 255, true ; "range_check() single lookup 2^k - 1")]
+#[test_case(8, 256, false ; "range_check() single lookup 2^k")]
+#[test_case(16, 65535, true ; "range_check() chain 2^k - 1")]
+#[test_case(16, 65536, false ; "range_check() chain 2^k")]
+#[test_case(13, 8191, true ; "range_check() partial limb 2^k - 1")]
+#[test_case(13, 8192, false ; "range_check() partial limb 2^k")]
+pub fn test_range_check_pow2_boundary(k: usize, a: u64, expect_satisfied: bool) {
+    base_test().k(10).lookup_bits(8).expect_satisfied(expect_satisfied).run(|ctx, chip| {
+        let a = ctx.load_witness(Fr::from(a));
+        let start = ctx.advice_len();
+        chip.range_check(ctx, a, k);
+        if k == chip.lookup_bits() {
+            // looked up directly, without assigning any cells
+            assert_eq!(ctx.advice_len(), start);
+        }
+    })
+}
+
 #[test_case(12, 10, Witness(Fr::zero()), Witness(Fr::one()), 64; "check_less_than() pos")]
 pub fn test_check_less_than(
     k: usize,
@@ -28,7 +52,22 @@ pub fn test_check_less_than(
     })
 }
 
+#[test_case(0, 1, 8, true; "check_less_than(): 0 < 1")]
+#[test_case(99, 100, 8, true; "check_less_than(): a == b - 1")]
+#[test_case(100, 100, 8, false; "check_less_than(): a == b")]
+#[test_case(101, 100, 8, false; "check_less_than(): a > b")]
+#[test_case(u64::MAX - 1, u64::MAX, 64, true; "check_less_than(): 64 bits")]
+#[test_case(u64::MAX, u64::MAX, 64, false; "check_less_than(): 64 bits equal")]
+pub fn test_check_less_than_assigned(a: u64, b: u64, num_bits: usize, expect_satisfied: bool) {
+    base_test().k(10).lookup_bits(8).expect_satisfied(expect_satisfied).run(|ctx, chip| {
+        let [a, b] = [a, b].map(|x| ctx.load_witness(Fr::from(x)));
+        chip.check_less_than(ctx, a, b, num_bits);
+    })
+}
+
 #[test_case(10, 8, Fr::zero(), 1; "check_less_than_safe() pos")]
+#[test_case(10, 8, Fr::from(99), 100; "check_less_than_safe() a == b - 1")]
+#[test_case(10, 8, Fr::from(u64::MAX - 1), u64::MAX; "check_less_than_safe() 64 bits")]
 pub fn test_check_less_than_safe(k: usize, lookup_bits: usize, a: Fr, b: u64) {
     base_test().k(k as u32).lookup_bits(lookup_bits).run(|ctx, chip| {
         let a = ctx.load_witness(a);
@@ -36,6 +75,29 @@ pub fn test_check_less_than_safe(k: usize, lookup_bits: usize, a: Fr, b: u64) {
     })
 }
 
+#[test_case(Fr::from(100), 100; "check_less_than_safe() a == b")]
+#[test_case(Fr::from(200), 100; "check_less_than_safe() a > b")]
+#[test_case(Fr::zero(), 0; "check_less_than_safe() b == 0")]
+#[test_case(-Fr::one(), 100; "check_less_than_safe() a negative")]
+pub fn test_check_less_than_safe_fails(a: Fr, b: u64) {
+    base_test().k(10).lookup_bits(8).expect_satisfied(false).run(|ctx, chip| {
+        let a = ctx.load_witness(a);
+        chip.check_less_than_safe(ctx, a, b);
+    })
+}
+
+#[test_case(Fr::from(101), 100, 8, true; "check_greater_than_safe() a == b + 1")]
+#[test_case(Fr::from(255), 0, 8, true; "check_greater_than_safe() b == 0")]
+#[test_case(Fr::from(100), 100, 8, false; "check_greater_than_safe() a == b")]
+#[test_case(Fr::from(99), 100, 8, false; "check_greater_than_safe() a < b")]
+#[test_case(Fr::from(256), 100, 8, false; "check_greater_than_safe() a too large")]
+pub fn test_check_greater_than_safe(a: Fr, b: u64, num_bits: usize, expect_satisfied: bool) {
+    base_test().k(10).lookup_bits(8).expect_satisfied(expect_satisfied).run(|ctx, chip| {
+        let a = ctx.load_witness(a);
+        chip.check_greater_than_safe(ctx, a, b, num_bits);
+    })
+}
+
 #[test_case(10, 8, biguint_to_fe(&BigUint::from(2u64).pow(239)), BigUint::from(2u64).pow(240) - 1usize; "check_big_less_than_safe() pos")]
 pub fn test_check_big_less_than_safe(k: usize, lookup_bits: usize, a: Fr, b: BigUint) {
     base_test().k(k as u32).lookup_bits(lookup_bits).run(|ctx, chip| {
@@ -66,6 +128,22 @@ pub fn test_is_less_than_safe(k: usize, lookup_bits: usize, a: Fr, b: u64) -> Fr
     })
 }
 
+#[test_case(1; "is_less_than_safe(): b = 1")]
+#[test_case(1000; "is_less_than_safe(): b = 1000")]
+#[test_case(1 << 16; "is_less_than_safe(): power of two")]
+#[test_case((1 << 40) + 7; "is_less_than_safe(): 41 bits")]
+pub fn test_is_less_than_safe_boundary(b: u64) {
+    // values just below, at and just above `b`
+    let values = [-Fr::one(), Fr::zero(), Fr::one()].map(|d| Fr::from(b) + d);
+    let out = base_test().k(12).lookup_bits(8).run(|ctx, chip| {
+        values.map(|a| {
+            let a = ctx.load_witness(a);
+            *chip.is_less_than_safe(ctx, a, b).value()
+        })
+    });
+    assert_eq!(out, [Fr::one(), Fr::zero(), Fr::zero()]);
+}
+
 #[test_case(10, 8, biguint_to_fe(&BigUint::from(2u64).pow(239)), BigUint::from(2u64).pow(240) - 1usize => Fr::from(1); "is_big_less_than_safe() pos")]
 pub fn test_is_big_less_than_safe(k: usize, lookup_bits: usize, a: Fr, b: BigUint) -> Fr {
     base_test().k(k as u32).lookup_bits(lookup_bits).run(|ctx, chip| {
@@ -82,6 +160,98 @@ pub fn test_div_mod(a: QuantumCell<Fr>, b: u64, num_bits: usize) -> (Fr, Fr) {
     })
 }
 
+#[test_case(3, 2, 2 => (Fr::from(1), Fr::from(1)) ; "div_mod_unsafe(3, 2)")]
+#[test_case(255, 7, 8 => (Fr::from(36), Fr::from(3)) ; "div_mod_unsafe(255, 7)")]
+#[test_case(5, 1000, 3 => (Fr::from(0), Fr::from(5)) ; "div_mod_unsafe() divisor larger than 2^a_num_bits")]
+#[test_case(u64::MAX, 1 << 32, 64 => (Fr::from(u32::MAX as u64), Fr::from(u32::MAX as u64)) ; "div_mod_unsafe() 64 bits")]
+pub fn test_div_mod_unsafe(a: u64, b: u64, a_num_bits: usize) -> (Fr, Fr) {
+    base_test().run(|ctx, chip| {
+        let a = ctx.load_witness(Fr::from(a));
+        let (div, rem) = chip.div_mod_unsafe(ctx, a, b, a_num_bits);
+        (*div.value(), *rem.value())
+    })
+}
+
+#[test]
+pub fn test_div_mod_unsafe_rem_too_large() {
+    base_test().expect_satisfied(false).run(|ctx, chip| {
+        let a = ctx.load_witness(Fr::from(23));
+        let (div, rem) = chip.div_mod_unsafe(ctx, a, 5, 8);
+        // `23 = 5 * 3 + 8` still satisfies the division gate, but `8 >= 5`
+        div.debug_prank(ctx, Fr::from(3));
+        rem.debug_prank(ctx, Fr::from(8));
+    })
+}
+
+/// Two's-complement encoding of `x` with `num_bits` bits.
+fn twos_complement(x: i64, num_bits: usize) -> Fr {
+    Fr::from((x as i128).rem_euclid(1 << num_bits) as u64)
+}
+
+#[test_case(7, 2, 8; "signed_div_mod(7, 2)")]
+#[test_case(-7, 2, 8; "signed_div_mod(-7, 2)")]
+#[test_case(7, -2, 8; "signed_div_mod(7, -2)")]
+#[test_case(-7, -2, 8; "signed_div_mod(-7, -2)")]
+#[test_case(-1, 3, 8; "signed_div_mod(-1, 3) quotient zero")]
+#[test_case(-6, 3, 8; "signed_div_mod(-6, 3) remainder zero")]
+#[test_case(-128, 1, 8; "signed_div_mod(-128, 1) minimum")]
+#[test_case(127, -1, 8; "signed_div_mod(127, -1)")]
+#[test_case(-5, 1000, 8; "signed_div_mod() divisor larger than a")]
+#[test_case(i64::MIN, 7, 64; "signed_div_mod() 64 bits")]
+#[test_case(i64::MAX, i64::MIN, 64; "signed_div_mod() minimum divisor")]
+pub fn test_signed_div_mod(a: i64, b: i64, a_num_bits: usize) {
+    let (div, rem) = base_test().run(|ctx, chip| {
+        let a = ctx.load_witness(twos_complement(a, a_num_bits));
+        let (div, rem) = chip.signed_div_mod(ctx, a, b, a_num_bits);
+        (*div.value(), *rem.value())
+    });
+    assert_eq!(div, twos_complement(a / b, a_num_bits));
+    assert_eq!(rem, twos_complement(a % b, a_num_bits));
+}
+
+#[test]
+pub fn test_signed_div_mod_overflow() {
+    // -128 / -1 = 128 does not fit in 8 bits
+    base_test().expect_satisfied(false).run(|ctx, chip| {
+        let a = ctx.load_witness(twos_complement(-128, 8));
+        chip.signed_div_mod(ctx, a, -1, 8);
+    })
+}
+
+#[test]
+pub fn test_signed_div_mod_a_too_large() {
+    base_test().expect_satisfied(false).run(|ctx, chip| {
+        let a = ctx.load_witness(Fr::from(256));
+        chip.signed_div_mod(ctx, a, 3, 8);
+    })
+}
+
+const GOLDILOCKS: u64 = 0xffff_ffff_0000_0001;
+
+#[test_case(Fr::from(12345); "euclidean_reduce(): small")]
+#[test_case(Fr::from(GOLDILOCKS); "euclidean_reduce(): modulus")]
+#[test_case(Fr::from(u64::MAX); "euclidean_reduce(): u64::MAX")]
+#[test_case(-Fr::one(); "euclidean_reduce(): p - 1")]
+#[test_case(biguint_to_fe(&(BigUint::from(GOLDILOCKS) * (modulus::<Fr>() / GOLDILOCKS))); "euclidean_reduce(): largest multiple")]
+#[test_case(Fr::random(OsRng); "euclidean_reduce(): random")]
+pub fn test_euclidean_reduce_goldilocks(a: Fr) {
+    let r = base_test().k(12).lookup_bits(10).run(|ctx, chip| {
+        let a = ctx.load_witness(a);
+        *chip.euclidean_reduce(ctx, a, GOLDILOCKS).value()
+    });
+    assert_eq!(fe_to_biguint(&r), fe_to_biguint(&a) % GOLDILOCKS);
+}
+
+#[test_case(7, 3 => Fr::from(1); "euclidean_reduce(7, 3)")]
+#[test_case(6, 3 => Fr::from(0); "euclidean_reduce(6, 3)")]
+#[test_case(5, 1 => Fr::from(0); "euclidean_reduce(5, 1)")]
+pub fn test_euclidean_reduce(a: u64, modulus: u64) -> Fr {
+    base_test().k(12).lookup_bits(10).run(|ctx, chip| {
+        let a = ctx.load_witness(Fr::from(a));
+        *chip.euclidean_reduce(ctx, a, modulus).value()
+    })
+}
+
 #[test_case(Fr::from(3), 8 => Fr::one() ; "get_last_bit(): 3, 8 bits")]
 #[test_case(Fr::from(3), 2 => Fr::one() ; "get_last_bit(): 3, 2 bits")]
 #[test_case(Fr::from(0), 2 => Fr::zero() ; "get_last_bit(): 0")]
@@ -94,6 +264,23 @@ pub fn test_get_last_bit(a: Fr, bits: usize) -> Fr {
     })
 }
 
+#[test_case(2; "get_last_bit(): parity 2 bits")]
+#[test_case(64; "get_last_bit(): parity 64 bits")]
+#[test_case(Fr::CAPACITY as usize; "get_last_bit(): parity capacity")]
+pub fn test_get_last_bit_parity(num_bits: usize) {
+    for _ in 0..5 {
+        // random `k` such that `2 * k + 1` has at most `num_bits` bits
+        let k: Fr = biguint_to_fe(&(fe_to_biguint(&Fr::random(OsRng)) >> (255 - num_bits)));
+        let parities = base_test().run(|ctx, chip| {
+            [k.double(), k.double() + Fr::one()].map(|x| {
+                let x = ctx.load_witness(x);
+                *chip.get_last_bit(ctx, x, num_bits).value()
+            })
+        });
+        assert_eq!(parities, [Fr::zero(), Fr::one()]);
+    }
+}
+
 #[test_case(Witness(Fr::from(3)), Witness(Fr::from(2)), 3, 3 => (Fr::one(), Fr::one()); "div_mod_var(3 ,2)")]
 pub fn test_div_mod_var(
     a: QuantumCell<Fr>,
@@ -106,3 +293,284 @@ pub fn test_div_mod_var(
         (*a.0.value(), *a.1.value())
     })
 }
+
+#[test_case(Fr::from(100), Fr::from(155), 8 => (Fr::from(255), Fr::zero()) ; "carry_add(): no carry")]
+#[test_case(Fr::from(100), Fr::from(156), 8 => (Fr::zero(), Fr::one()) ; "carry_add(): carry, zero low")]
+#[test_case(Fr::from(255), Fr::from(255), 8 => (Fr::from(254), Fr::one()) ; "carry_add(): max inputs")]
+pub fn test_carry_add(a: Fr, b: Fr, bit_len: usize) -> (Fr, Fr) {
+    base_test().run(|ctx, chip| {
+        let [a, b] = [a, b].map(|x| ctx.load_witness(x));
+        let (low, carry) = chip.carry_add(ctx, a, b, bit_len);
+        (*low.value(), *carry.value())
+    })
+}
+
+#[test_case(Fr::from(0x1234), 4, 4 => Ok([4, 3, 2, 1].map(Fr::from).to_vec()) ; "safe_decompose(): 0x1234")]
+#[test_case(Fr::from(0xffff), 2, 8 => Ok([0xff, 0xff].map(Fr::from).to_vec()) ; "safe_decompose(): max value")]
+#[test_case(Fr::from(0x10000), 2, 8 => Err(OverflowError { num_limbs: 2, limb_bits: 8, value: BigUint::from(0x10000u64) }) ; "safe_decompose(): overflow")]
+pub fn test_safe_decompose(
+    a: Fr,
+    num_limbs: usize,
+    limb_bits: usize,
+) -> Result<Vec<Fr>, OverflowError> {
+    base_test().run(|ctx, chip| {
+        let a = ctx.load_witness(a);
+        let limbs = chip.safe_decompose(ctx, a, num_limbs, limb_bits)?;
+        Ok(limbs.iter().map(|limb| *limb.value()).collect())
+    })
+}
+
+#[test_case(8, 4 ; "decompose_le(): bytes")]
+#[test_case(16, 4 ; "decompose_le(): 64 bits")]
+#[test_case(64, 3 ; "decompose_le(): u64 limbs")]
+pub fn test_decompose_le_round_trip(limb_bits: usize, num_limbs: usize) {
+    for _ in 0..5 {
+        let x = fe_to_biguint(&Fr::random(OsRng)) >> (256 - limb_bits * num_limbs);
+        base_test().run(|ctx, chip| {
+            let x = ctx.load_witness(biguint_to_fe(&x));
+            let le = chip.decompose_le(ctx, x, limb_bits, num_limbs);
+            let be = chip.decompose_be(ctx, x, limb_bits, num_limbs);
+            assert_eq!(le.len(), num_limbs);
+            for (l, b) in le.iter().zip(be.iter().rev()) {
+                assert_eq!(l.value(), b.value());
+            }
+            let from_le = chip.recompose_le(ctx, &le, limb_bits);
+            let from_be = chip.recompose_be(ctx, &be, limb_bits);
+            ctx.constrain_equal(&from_le, &x);
+            ctx.constrain_equal(&from_be, &x);
+        });
+    }
+}
+
+#[test]
+pub fn test_decompose_le_order() {
+    base_test().run(|ctx, chip| {
+        let x = ctx.load_witness(Fr::from(0x1234));
+        let le = chip.decompose_le(ctx, x, 4, 4);
+        let be = chip.decompose_be(ctx, x, 4, 4);
+        assert_eq!(le.iter().map(|l| *l.value()).collect::<Vec<_>>(), [4, 3, 2, 1].map(Fr::from));
+        assert_eq!(be.iter().map(|l| *l.value()).collect::<Vec<_>>(), [1, 2, 3, 4].map(Fr::from));
+    })
+}
+
+#[test]
+pub fn test_decompose_le_overflow() {
+    base_test().expect_satisfied(false).run(|ctx, chip| {
+        let x = ctx.load_witness(Fr::from(0x10000));
+        chip.decompose_le(ctx, x, 8, 2);
+    })
+}
+
+#[test_case(&[1, 2, 3, 4], 8 => (Fr::zero(), Fr::from(1), Fr::from(4)) ; "argmin(): ascending")]
+#[test_case(&[4, 3, 2, 1], 8 => (Fr::from(3), Fr::from(1), Fr::from(4)) ; "argmin(): descending")]
+#[test_case(&[7, 2, 9, 2, 5], 8 => (Fr::one(), Fr::from(2), Fr::from(9)) ; "argmin(): random with tie")]
+#[test_case(&[42], 8 => (Fr::zero(), Fr::from(42), Fr::from(42)) ; "argmin(): single element")]
+pub fn test_argmin(vals: &[u64], num_bits: usize) -> (Fr, Fr, Fr) {
+    base_test().run(|ctx, chip| {
+        let vals = ctx.assign_witnesses(vals.iter().map(|v| Fr::from(*v)));
+        let idx = chip.argmin(ctx, &vals, num_bits);
+        let min = chip.min_val(ctx, &vals, num_bits);
+        let (min_2, max) = chip.minmax(ctx, &vals, num_bits);
+        assert_eq!(min.value(), min_2.value());
+        (*idx.value(), *min.value(), *max.value())
+    })
+}
+
+#[test_case(Fr::from(200), Fr::from(55), 8 => Fr::from(145) ; "abs_diff(): a > b")]
+#[test_case(Fr::from(77), Fr::from(77), 8 => Fr::zero() ; "abs_diff(): a == b")]
+#[test_case(Fr::from(0), Fr::from(255), 8 => Fr::from(255) ; "abs_diff(): a < b")]
+pub fn test_abs_diff(a: Fr, b: Fr, num_bits: usize) -> Fr {
+    base_test().run(|ctx, chip| {
+        let [a, b] = [a, b].map(|x| ctx.load_witness(x));
+        *chip.abs_diff(ctx, a, b, num_bits).value()
+    })
+}
+
+#[test_case(BigUint::zero(), true ; "check_limbs_canonical(): 0")]
+#[test_case(modulus::<Fr>() - 1u64, true ; "check_limbs_canonical(): p - 1")]
+#[test_case((BigUint::one() << 192) - 1u64, true ; "check_limbs_canonical(): borrow chain")]
+#[test_case(modulus::<Fr>(), false ; "check_limbs_canonical(): p")]
+#[test_case(modulus::<Fr>() + 1u64, false ; "check_limbs_canonical(): p + 1")]
+#[test_case((BigUint::one() << 256) - 1u64, false ; "check_limbs_canonical(): 2^256 - 1")]
+pub fn test_check_limbs_canonical(a: BigUint, expect_satisfied: bool) {
+    let to_limbs = |x: &BigUint| {
+        let mut limbs = x.to_u64_digits();
+        limbs.resize(4, 0);
+        limbs
+    };
+    let modulus_limbs = to_limbs(&modulus::<Fr>());
+    let a_limbs = to_limbs(&a);
+    base_test().expect_satisfied(expect_satisfied).run(|ctx, chip| {
+        let limbs = ctx.assign_witnesses(a_limbs.iter().map(|l| Fr::from(*l)));
+        for limb in &limbs {
+            chip.range_check(ctx, *limb, 64);
+        }
+        chip.check_limbs_canonical(ctx, &limbs, 64, &modulus_limbs);
+    })
+}
+
+#[test_case(&[1, 2, 3], &[1, 2, 4] => Fr::one() ; "check_big_less_than(): least significant limb")]
+#[test_case(&[1, 2, 3], &[1, 2, 3] => Fr::zero() ; "check_big_less_than(): equal")]
+#[test_case(&[2, 0, 0], &[1, 255, 255] => Fr::zero() ; "check_big_less_than(): most significant limb")]
+#[test_case(&[0, 255, 255], &[1, 0, 0] => Fr::one() ; "check_big_less_than(): lower limbs larger")]
+#[test_case(&[7], &[9] => Fr::one() ; "check_big_less_than(): single limb")]
+pub fn test_check_big_less_than(a: &[u64], b: &[u64]) -> Fr {
+    base_test().run(|ctx, chip| {
+        let a = ctx.assign_witnesses(a.iter().map(|x| Fr::from(*x)));
+        let b = ctx.assign_witnesses(b.iter().map(|x| Fr::from(*x)));
+        *chip.check_big_less_than(ctx, &a, &b, 8).value()
+    })
+}
+
+#[test]
+pub fn test_check_big_less_than_random() {
+    let to_limbs = |x: &BigUint| {
+        let mut limbs = x.to_u64_digits();
+        limbs.resize(4, 0);
+        limbs.reverse();
+        limbs
+    };
+    for _ in 0..10 {
+        let [a, b] = [(); 2].map(|_| fe_to_biguint(&Fr::random(OsRng)));
+        let out = base_test().run(|ctx, chip| {
+            let [a, b] =
+                [&a, &b].map(|x| ctx.assign_witnesses(to_limbs(x).into_iter().map(Fr::from)));
+            for limb in a.iter().chain(b.iter()) {
+                chip.range_check(ctx, *limb, 64);
+            }
+            *chip.check_big_less_than(ctx, &a, &b, 64).value()
+        });
+        assert_eq!(out, Fr::from(a < b));
+    }
+}
+
+#[test_case(&[1, 2, 3], &[4, 5, 6] => Fr::from(32) ; "dot_product_mod(): no reduction")]
+#[test_case(&[0xfffffffa, 0xfffffffa], &[0xffffffff, 3] => Fr::from(((0xfffffffau128 * 0xffffffff + 0xfffffffa * 3) % 0xfffffffb) as u64) ; "dot_product_mod(): reduction")]
+#[test_case(&[0xfffffffb], &[1] => Fr::zero() ; "dot_product_mod(): modulus")]
+pub fn test_dot_product_mod(a: &[u64], b: &[u64]) -> Fr {
+    base_test().run(|ctx, chip| {
+        let a = ctx.assign_witnesses(a.iter().map(|x| Fr::from(*x)));
+        let b = b.iter().map(|x| Fr::from(*x)).collect::<Vec<_>>();
+        *chip.dot_product_mod(ctx, &a, &b, 0xfffffffbu64, 32).value()
+    })
+}
+
+#[test_case(&[10, 15, 20], true ; "batch_check_in_range(): all in range")]
+#[test_case(&[10, 9, 20], false ; "batch_check_in_range(): below lo")]
+#[test_case(&[10, 21, 20], false ; "batch_check_in_range(): above hi")]
+pub fn test_batch_check_in_range(vals: &[u64], expect_satisfied: bool) {
+    base_test().expect_satisfied(expect_satisfied).run(|ctx, chip| {
+        let vals = ctx.assign_witnesses(vals.iter().map(|v| Fr::from(*v)));
+        chip.batch_check_in_range(ctx, &vals, Fr::from(10), Fr::from(20), 8);
+    })
+}
+
+#[test_case(&[10, 15, 20] => Fr::one() ; "all_in_range(): all in range")]
+#[test_case(&[10, 9, 20] => Fr::zero() ; "all_in_range(): below lo")]
+#[test_case(&[10, 21, 20] => Fr::zero() ; "all_in_range(): above hi")]
+#[test_case(&[] => Fr::one() ; "all_in_range(): empty")]
+pub fn test_all_in_range(vals: &[u64]) -> Fr {
+    base_test().run(|ctx, chip| {
+        let vals = ctx.assign_witnesses(vals.iter().map(|v| Fr::from(*v)));
+        *chip.all_in_range(ctx, &vals, Fr::from(10), Fr::from(20), 8).value()
+    })
+}
+
+#[test_case(35, 64, 1, true ; "assert_gcd(): coprime")]
+#[test_case(1, 1, 1, true ; "assert_gcd(): ones")]
+#[test_case(12, 18, 6, true ; "assert_gcd(): shared factor")]
+#[test_case(0, 5, 5, true ; "assert_gcd(): zero")]
+#[test_case(0xfffffffb * 7, 0xfffffffb * 11, 0xfffffffb, true ; "assert_gcd(): large gcd")]
+#[test_case(12, 18, 3, false ; "assert_gcd(): common divisor but not greatest")]
+#[test_case(12, 18, 1, false ; "assert_gcd(): not coprime")]
+pub fn test_assert_gcd(a: u64, b: u64, gcd: u64, expect_satisfied: bool) {
+    base_test().expect_satisfied(expect_satisfied).run(|ctx, chip| {
+        let [a, b, gcd, a_div_gcd, b_div_gcd] =
+            [a, b, gcd, a / gcd, b / gcd].map(|v| ctx.load_witness(Fr::from(v)));
+        chip.assert_gcd(ctx, a, b, gcd, a_div_gcd, b_div_gcd, 64);
+    })
+}
+
+#[test]
+pub fn test_assert_gcd_wrong_quotient() {
+    // 4 and 6 are not the quotients of 12 and 18 by 2
+    base_test().expect_satisfied(false).run(|ctx, chip| {
+        let [a, b, gcd, a_div_gcd, b_div_gcd] =
+            [12u64, 18, 2, 4, 6].map(|v| ctx.load_witness(Fr::from(v)));
+        chip.assert_gcd(ctx, a, b, gcd, a_div_gcd, b_div_gcd, 64);
+    })
+}
+
+#[test_case(&[3, 5, 7], 52 ; "chinese_remainder_combine(): 3 5 7")]
+#[test_case(&[3, 5, 7], 0 ; "chinese_remainder_combine(): zero")]
+#[test_case(&[3, 5, 7], 104 ; "chinese_remainder_combine(): max")]
+#[test_case(&[7, 5, 3], 71 ; "chinese_remainder_combine(): reordered moduli")]
+#[test_case(&[11], 6 ; "chinese_remainder_combine(): single modulus")]
+#[test_case(&[0xfffffffb, 0xffffffef, 0xfffffff1, 1 << 20], 0x123456789abcdef0123456789 ; "chinese_remainder_combine(): large moduli")]
+pub fn test_chinese_remainder_combine(moduli: &[u64], x: u128) {
+    let res = base_test().run(|ctx, chip| {
+        let residues = ctx
+            .assign_witnesses(moduli.iter().map(|m| biguint_to_fe(&BigUint::from(x % *m as u128))));
+        let moduli = moduli.iter().map(|m| Fr::from(*m)).collect::<Vec<_>>();
+        *chip.chinese_remainder_combine(ctx, &residues, &moduli).value()
+    });
+    assert_eq!(res, biguint_to_fe(&BigUint::from(x)));
+}
+
+#[test]
+pub fn test_chinese_remainder_combine_not_unique() {
+    // 52 = 1 mod 3, 2 mod 5, 3 mod 7
+    base_test().expect_satisfied(false).run(|ctx, chip| {
+        let residues = ctx.assign_witnesses([1u64, 2, 3].map(Fr::from));
+        let moduli = [3u64, 5, 7].map(Fr::from);
+        let x = chip.chinese_remainder_combine(ctx, &residues, &moduli);
+        // 157 has the same residues, but the result is constrained to be less than 105
+        let claimed = ctx.load_witness(Fr::from(157));
+        ctx.constrain_equal(&x, &claimed);
+    })
+}
+
+#[test]
+fn test_rollback_range_check() {
+    base_test().k(10).lookup_bits(8).run(|ctx, chip| {
+        let a = ctx.load_witness(Fr::from(3));
+        let snapshot = chip.snapshot(ctx);
+        chip.range_check(ctx, a, 16);
+        chip.rollback(ctx, snapshot);
+        // reuses the offsets of the removed cells, with values outside the lookup table
+        chip.gate().add(ctx, a, Constant(Fr::from(1 << 10)));
+        ctx.load_witness(Fr::from(1 << 10));
+        chip.range_check(ctx, a, 8);
+        assert_eq!(chip.lookup_manager()[0].total_rows(), 1);
+    })
+}
+
+#[test_case([2, 10, 6, 4], 0 => Fr::from(2); "cubic_bezier_eval(): t = 0")]
+#[test_case([2, 10, 6, 4], 256 => Fr::from(4); "cubic_bezier_eval(): t = 1")]
+pub fn test_cubic_bezier_eval(control_points: [u64; 4], t: u64) -> Fr {
+    base_test().run(|ctx, chip| {
+        let control_points = ctx.assign_witnesses(control_points.map(Fr::from)).try_into().unwrap();
+        let t = ctx.load_witness(Fr::from(t));
+        *chip.cubic_bezier_eval(ctx, control_points, t, 8).value()
+    })
+}
+
+#[test]
+pub fn test_cubic_bezier_eval_half() {
+    let control_points = [2u64, 10, 6, 4];
+    let [p0, p1, p2, p3] = control_points.map(|p| p as f64);
+    let native = 0.125 * p0 + 0.375 * p1 + 0.375 * p2 + 0.125 * p3;
+    // at t = 1/2 every term has denominator dividing 8
+    let expected = Fr::from((native * 8.0) as u64) * Fr::from(8).invert().unwrap();
+    assert_eq!(test_cubic_bezier_eval(control_points, 128), expected);
+}
+
+#[test_case(Fr::from(257); "cubic_bezier_eval(): t > 1")]
+#[test_case(-Fr::one(); "cubic_bezier_eval(): t < 0")]
+pub fn test_cubic_bezier_eval_t_out_of_range(t: Fr) {
+    base_test().expect_satisfied(false).run(|ctx, chip| {
+        let control_points = ctx.assign_witnesses([2, 10, 6, 4].map(Fr::from)).try_into().unwrap();
+        let t = ctx.load_witness(t);
+        chip.cubic_bezier_eval(ctx, control_points, t, 8);
+    })
+}
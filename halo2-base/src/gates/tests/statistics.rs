@@ -0,0 +1,72 @@
+use crate::gates::flex_gate::threads::{MultiPhaseCoreManager, SinglePhaseCoreManager};
+use crate::gates::{GateChip, GateInstructions};
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use crate::virtual_region::copy_constraints::SharedCopyConstraintManager;
+use crate::virtual_region::lookups::LookupAnyManager;
+use crate::virtual_region::manager::{CircuitStats, VirtualRegionManager};
+use crate::Context;
+use crate::QuantumCell::Constant;
+use test_case::test_case;
+
+fn assign_cells(ctx: &mut Context<Fr>) {
+    let gate = GateChip::default();
+    let a = ctx.load_witness(Fr::from(3));
+    let b = ctx.load_constant(Fr::from(5));
+    // | a | b | 1 | a + b |
+    let c = gate.add(ctx, a, b);
+    // | 0 | c | 5 | c * 5 |
+    gate.mul(ctx, c, Constant(Fr::from(5)));
+}
+
+#[test_case(false => CircuitStats { num_advice_cells: 10, num_selector_on: 2, num_copy_constraints: 7, num_constants: 3 }; "keygen")]
+#[test_case(true => CircuitStats { num_advice_cells: 10, num_selector_on: 0, num_copy_constraints: 0, num_constants: 0 }; "witness gen only")]
+fn test_single_phase_statistics(witness_gen_only: bool) -> CircuitStats {
+    let copy_manager = SharedCopyConstraintManager::default();
+    let mut core = SinglePhaseCoreManager::new(witness_gen_only, copy_manager.clone());
+    assert_eq!(core.statistics(), CircuitStats::default());
+    assign_cells(core.main());
+    let stats = core.statistics();
+
+    // cells of a region in another phase sharing the copy manager are not counted
+    let mut other = SinglePhaseCoreManager::new(witness_gen_only, copy_manager.clone()).in_phase(1);
+    assign_cells(other.main());
+    assert_eq!(core.statistics(), stats);
+    assert_eq!(other.statistics(), stats);
+
+    copy_manager.lock().unwrap().clear();
+    stats
+}
+
+// both phases use the same 3 constants, which are only counted once
+#[test_case(false => CircuitStats { num_advice_cells: 20, num_selector_on: 4, num_copy_constraints: 14, num_constants: 3 }; "keygen")]
+#[test_case(true => CircuitStats { num_advice_cells: 20, num_selector_on: 0, num_copy_constraints: 0, num_constants: 0 }; "witness gen only")]
+fn test_multi_phase_statistics(witness_gen_only: bool) -> CircuitStats {
+    let mut core = MultiPhaseCoreManager::new(witness_gen_only);
+    assert_eq!(core.circuit_stats(), CircuitStats::default());
+    assign_cells(core.main(0));
+    assign_cells(core.main(1));
+    let stats = core.circuit_stats();
+    // the copy manager is shared by all phases, so it sees the same copy constraints and constants
+    let copy_stats = core.copy_manager.statistics();
+    assert_eq!(copy_stats.num_copy_constraints, stats.num_copy_constraints);
+    assert_eq!(copy_stats.num_constants, stats.num_constants);
+
+    core.clear();
+    stats
+}
+
+#[test_case(false => CircuitStats { num_advice_cells: 4, num_selector_on: 0, num_copy_constraints: 4, num_constants: 0 }; "keygen")]
+#[test_case(true => CircuitStats { num_advice_cells: 4, num_selector_on: 0, num_copy_constraints: 0, num_constants: 0 }; "witness gen only")]
+fn test_lookup_statistics(witness_gen_only: bool) -> CircuitStats {
+    let mut core = SinglePhaseCoreManager::new(witness_gen_only, Default::default());
+    let mut lookup_manager =
+        LookupAnyManager::<Fr, 2>::new(witness_gen_only, core.copy_manager.clone());
+    assert_eq!(lookup_manager.statistics(), CircuitStats::default());
+    let ctx = core.main();
+    let [a, b] = [1, 2].map(|x| ctx.load_witness(Fr::from(x)));
+    lookup_manager.add_lookups(ctx.tag(), [[a, b], [b, a]]);
+    let stats = lookup_manager.statistics();
+
+    lookup_manager.clear();
+    stats
+}
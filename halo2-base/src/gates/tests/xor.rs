@@ -0,0 +1,57 @@
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use test_case::test_case;
+
+use super::lookup::LookupCircuit;
+use crate::{
+    gates::xor::{xor_table, XorChip, XorGateChip, XorInstructions, XOR_LOOKUP_WIDTH},
+    safe_types::SafeTypeChip,
+    utils::testing::base_test,
+    Context,
+};
+
+// the XOR table has 2^16 rows
+pub(super) const K: usize = 17;
+
+pub(super) type XorCircuit = LookupCircuit<Fr, XOR_LOOKUP_WIDTH>;
+
+const PAIRS: [(u8, u8); 4] = [(0x00, 0x00), (0xf0, 0x3c), (0xff, 0x01), (0x5a, 0xa5)];
+
+// checks `chip` against native XOR on `PAIRS`
+fn check_xor_bytes(ctx: &mut Context<Fr>, chip: &impl XorInstructions<Fr>) {
+    for (a, b) in PAIRS {
+        let [a_byte, b_byte] =
+            [a, b].map(|x| SafeTypeChip::unsafe_to_byte(ctx.load_witness(Fr::from(x as u64))));
+        let out = chip.xor_byte(ctx, a_byte, b_byte);
+        assert_eq!(*out.as_ref().value(), Fr::from((a ^ b) as u64));
+    }
+}
+
+#[test]
+fn test_xor_table() {
+    let table = xor_table::<Fr>();
+    assert_eq!(table.len(), 1 << 16);
+    assert_eq!(table[(0xf0 << 8) + 0x3c], [0xf0, 0x3c, 0xcc].map(Fr::from));
+}
+
+#[test]
+fn test_xor_lookup() {
+    let mut circuit = XorCircuit::new(xor_table());
+    let chip = XorChip::new(circuit.lookup_manager.clone());
+    check_xor_bytes(circuit.cpu.main(), &chip);
+    circuit.assert_satisfied(K, true);
+}
+
+#[test_case(0xf0, 0x3c, 0xcd ; "wrong output")]
+#[test_case(0x100, 0x00, 0x100 ; "input not a byte")]
+fn test_xor_lookup_invalid(a: u64, b: u64, out: u64) {
+    let mut circuit = XorCircuit::new(xor_table());
+    let ctx = circuit.cpu.main();
+    let [a, b, out] = [a, b, out].map(|v| ctx.load_witness(Fr::from(v)));
+    circuit.lookup_manager.add_lookup(ctx.tag(), [a, b, out]);
+    circuit.assert_satisfied(K, false);
+}
+
+#[test]
+fn test_xor_gate() {
+    base_test().run_gate(|ctx, gate| check_xor_bytes(ctx, &XorGateChip::new(gate)));
+}
@@ -0,0 +1,96 @@
+use crate::{
+    gates::{
+        flex_gate::{GateChip, GateInstructions},
+        lookup::bitwise::BitwiseChip,
+    },
+    safe_types::{SafeByte, SafeTypeChip},
+    utils::ScalarField,
+    virtual_region::lookups::LookupAnyManager,
+    Context,
+    QuantumCell::Constant,
+};
+
+/// Number of columns of the XOR lookup: the two input bytes and the output byte.
+pub const XOR_LOOKUP_WIDTH: usize = 3;
+
+/// Returns the rows `(a, b, a ^ b)` of the XOR lookup table, for all bytes `a, b`.
+///
+/// The table has `2^16` rows, so the circuit needs `k >= 17`. It contains the all-zero row, which is a valid XOR.
+/// [bitwise_table](super::lookup::bitwise::bitwise_table) contains the same XORs, tagged by their operation.
+pub fn xor_table<F: ScalarField>() -> Vec<[F; XOR_LOOKUP_WIDTH]> {
+    (0..=255u64).flat_map(|a| (0..=255u64).map(move |b| [a, b, a ^ b].map(F::from))).collect()
+}
+
+/// Trait for XOR of bytes, implemented both with and without a lookup table.
+pub trait XorInstructions<F: ScalarField> {
+    /// Constrains and returns `a ^ b`.
+    ///
+    /// Implementations also constrain `a` and `b` to be bytes, which [KeccakChip](super::keccak::KeccakChip) relies on.
+    fn xor_byte(&self, ctx: &mut Context<F>, a: SafeByte<F>, b: SafeByte<F>) -> SafeByte<F>;
+}
+
+/// Chip for XOR of bytes, using a lookup into [xor_table].
+///
+/// The lookup should be configured with a [LookupArgument](super::lookup::LookupArgument) of
+/// [XOR_LOOKUP_WIDTH] table columns, and `lookup_manager` assigned to its input columns.
+///
+/// [BitwiseChip] also implements [XorInstructions], since its table already contains every XOR of two bytes.
+/// Circuits that already configure a [BitwiseChip] should use it instead, rather than adding a second table of `2^16` rows.
+#[derive(Clone, Debug)]
+pub struct XorChip<F: ScalarField> {
+    /// Manager for the cells to look up in the XOR table.
+    pub lookup_manager: LookupAnyManager<F, XOR_LOOKUP_WIDTH>,
+}
+
+impl<F: ScalarField> XorChip<F> {
+    /// Creates a new [XorChip] that adds lookups to `lookup_manager`.
+    pub fn new(lookup_manager: LookupAnyManager<F, XOR_LOOKUP_WIDTH>) -> Self {
+        Self { lookup_manager }
+    }
+}
+
+impl<F: ScalarField> XorInstructions<F> for XorChip<F> {
+    /// Constrains and returns `a ^ b` with a single lookup.
+    fn xor_byte(&self, ctx: &mut Context<F>, a: SafeByte<F>, b: SafeByte<F>) -> SafeByte<F> {
+        let (a, b) = (*a.as_ref(), *b.as_ref());
+        let out = a.value().get_lower_32() ^ b.value().get_lower_32();
+        let out = ctx.load_witness(F::from(out as u64));
+        self.lookup_manager.add_lookup(ctx.tag(), [a, b, out]);
+        // the table only contains bytes
+        SafeTypeChip::unsafe_to_byte(out)
+    }
+}
+
+impl<F: ScalarField> XorInstructions<F> for BitwiseChip<F> {
+    fn xor_byte(&self, ctx: &mut Context<F>, a: SafeByte<F>, b: SafeByte<F>) -> SafeByte<F> {
+        BitwiseChip::xor_byte(self, ctx, a, b)
+    }
+}
+
+/// Chip for XOR of bytes without lookups, by decomposing both bytes into bits.
+///
+/// Much more expensive than [XorChip], but needs no extra columns, e.g. to test circuits using [XorInstructions].
+#[derive(Clone, Debug)]
+pub struct XorGateChip<'a, F: ScalarField> {
+    gate: &'a GateChip<F>,
+}
+
+impl<'a, F: ScalarField> XorGateChip<'a, F> {
+    /// Creates a new [XorGateChip] using `gate`.
+    pub fn new(gate: &'a GateChip<F>) -> Self {
+        Self { gate }
+    }
+}
+
+impl<'a, F: ScalarField> XorInstructions<F> for XorGateChip<'a, F> {
+    fn xor_byte(&self, ctx: &mut Context<F>, a: SafeByte<F>, b: SafeByte<F>) -> SafeByte<F> {
+        let gate = self.gate;
+        let a_bits = gate.num_to_bits(ctx, *a.as_ref(), 8);
+        let b_bits = gate.num_to_bits(ctx, *b.as_ref(), 8);
+        let out_bits =
+            a_bits.into_iter().zip(b_bits).map(|(a, b)| gate.xor(ctx, a, b)).collect::<Vec<_>>();
+        let out = gate.inner_product(ctx, out_bits, (0..8).map(|i| Constant(gate.pow_of_two()[i])));
+        // recomposed from 8 bits
+        SafeTypeChip::unsafe_to_byte(out)
+    }
+}
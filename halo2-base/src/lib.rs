@@ -10,6 +10,7 @@
 #![warn(missing_docs)]
 
 use std::any::TypeId;
+use std::hash::{Hash, Hasher};
 
 use getset::CopyGetters;
 use itertools::Itertools;
@@ -45,6 +46,8 @@ use halo2_proofs::plonk::Assigned;
 use utils::ScalarField;
 use virtual_region::copy_constraints::SharedCopyConstraintManager;
 
+/// Module for non-native arithmetic on big integers with a fixed number of limbs.
+pub mod bigint;
 /// Module that contains the main API for creating and working with circuits.
 /// `gates` is misleading because we currently only use one custom gate throughout.
 pub mod gates;
@@ -102,15 +105,27 @@ impl<F: ScalarField> QuantumCell<F> {
             Self::Constant(a) => a,
         }
     }
+
+    /// Returns the constant [QuantumCell] `Constant(F::ONE)` if `b` is true, and `Constant(F::ZERO)` otherwise.
+    pub fn from_bool(b: bool) -> Self {
+        Self::Constant(F::from(b))
+    }
 }
 
 /// Unique tag for a context across all virtual regions
+///
+/// With the `serde` feature, it can be serialized with `#[serde(with = "halo2_base::utils::serialization::context_tag")]`,
+/// once its virtual region is registered with `halo2_base::utils::serialization::register_region`.
 pub type ContextTag = (TypeId, usize);
 
 /// Pointer to the position of a cell at `offset` in an advice column within a [Context] of `context_id`.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContextCell {
     /// The [TypeId] of the virtual region that this cell belongs to.
+    ///
+    /// With the `serde` feature, it is serialized as the name the region is registered with.
+    #[cfg_attr(feature = "serde", serde(with = "utils::serialization::region_type_id"))]
     pub type_id: TypeId,
     /// Identifier of the [Context] that this cell belongs to.
     pub context_id: usize,
@@ -129,8 +144,14 @@ impl ContextCell {
 ///
 /// Note: Performs a copy of the value, should only be used when you are about to assign the value again elsewhere.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "F: ScalarField")
+)]
 pub struct AssignedValue<F: crate::ff::Field> {
     /// Value of the cell.
+    #[cfg_attr(feature = "serde", serde(with = "utils::serialization::assigned"))]
     pub value: Assigned<F>, // we don't use reference to avoid issues with lifetimes (you can't safely borrow from vector and push to it at the same time).
     // only needed during vkey, pkey gen to fetch the actual cell from the relevant context
     /// [ContextCell] pointer to the cell the value is assigned to within an advice column of a [Context].
@@ -154,10 +175,17 @@ impl<F: ScalarField> AssignedValue<F> {
         }
     }
 
+    /// Returns true if the underlying value of this AssignedValue<F> is zero.
+    ///
+    /// Panics if the AssignedValue<F> is of type WitnessFraction, as [AssignedValue::value] does.
+    pub fn is_zero_cell(&self) -> bool {
+        *self.value() == F::ZERO
+    }
+
     /// Debug helper function for writing negative tests. This will change the **witness** value in `ctx` corresponding to `self.offset`.
     /// This assumes that `ctx` is the context that `self` lies in.
     pub fn debug_prank(&self, ctx: &mut Context<F>, prank_value: F) {
-        ctx.advice[self.cell.unwrap().offset] = Assigned::Trivial(prank_value);
+        ctx.debug_prank(self.cell.unwrap().offset, prank_value);
     }
 }
 
@@ -167,10 +195,50 @@ impl<F: ScalarField> AsRef<AssignedValue<F>> for AssignedValue<F> {
     }
 }
 
+/// Two [AssignedValue]s are equal if they point to the same [ContextCell], regardless of their values.
+///
+/// Without cells (in witness generation mode), they are equal if their values are equal.
+/// An [AssignedValue] with a cell never equals one without.
+impl<F: ScalarField> PartialEq for AssignedValue<F> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.cell, other.cell) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => self.value.evaluate() == other.value.evaluate(),
+            _ => false,
+        }
+    }
+}
+
+impl<F: ScalarField> Eq for AssignedValue<F> {}
+
+impl<F: ScalarField> Hash for AssignedValue<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self.cell {
+            Some(cell) => cell.hash(state),
+            None => self.value.evaluate().hash(state),
+        }
+    }
+}
+
+/// Position in the assignment of a [Context], returned by [Context::snapshot] to later [Context::rollback] to it.
+#[derive(Clone, Copy, Debug)]
+pub struct ContextSnapshot<F: ScalarField> {
+    advice_len: usize,
+    selector_len: usize,
+    advice_equalities_len: usize,
+    constant_equalities_len: usize,
+    zero_cell: Option<AssignedValue<F>>,
+}
+
 /// Represents a single thread of an execution trace.
 /// * We keep the naming [Context] for historical reasons.
 ///
 /// [Context] is CPU thread-local.
+///
+/// No wrapper is needed to move contexts between threads: every field of [Context] is `Send + Sync` because
+/// [`Field`](crate::ff::Field) already requires `F: Send + Sync` and the copy manager is an `Arc<Mutex<_>>`.
+/// Contexts can therefore be filled in parallel, e.g. with rayon, as long as they are appended to their virtual
+/// region in a fixed order afterwards (see [parallelize_core](crate::gates::flex_gate::threads::parallelize_core)).
 #[derive(Clone, Debug, CopyGetters)]
 pub struct Context<F: ScalarField> {
     /// Flag to determine whether only witness generation or proving and verification key generation is being performed.
@@ -187,6 +255,10 @@ pub struct Context<F: ScalarField> {
     context_id: usize,
 
     /// Single column of advice cells.
+    #[deprecated(
+        since = "0.4.1",
+        note = "will become private; use `advice_len`, `get` or `debug_prank` instead"
+    )]
     pub advice: Vec<Assigned<F>>,
 
     /// Slight optimization: since zero is so commonly used, keep a reference to the zero cell.
@@ -197,12 +269,15 @@ pub struct Context<F: ScalarField> {
     // If `witness_gen_only == false`:
     /// [Vec] representing the selector column of this [Context] accompanying each `advice` column
     /// * Assumed to have the same length as `advice`
+    #[deprecated(since = "0.4.1", note = "will become private; use `selector_len` instead")]
     pub selector: Vec<bool>,
 
     /// Global shared thread-safe manager for all copy (equality) constraints between virtual advice, constants, and raw external Halo2 cells.
     pub copy_manager: SharedCopyConstraintManager<F>,
 }
 
+// The deprecated `advice` and `selector` fields are only accessed directly within this impl.
+#[allow(deprecated)]
 impl<F: ScalarField> Context<F> {
     /// Creates a new [Context] with the given `context_id` and witness generation enabled/disabled by the `witness_gen_only` flag.
     /// * `witness_gen_only`: flag to determine whether public key generation or only witness generation is being performed.
@@ -214,6 +289,10 @@ impl<F: ScalarField> Context<F> {
         context_id: usize,
         copy_manager: SharedCopyConstraintManager<F>,
     ) -> Self {
+        #[cfg(debug_assertions)]
+        if !witness_gen_only {
+            copy_manager.lock().unwrap().context_phases.insert((type_id, context_id), phase);
+        }
         Self {
             witness_gen_only,
             phase,
@@ -231,11 +310,134 @@ impl<F: ScalarField> Context<F> {
         self.context_id
     }
 
+    /// Changes the context id to `context_id`, e.g. when the [Context] is moved into another virtual region.
+    ///
+    /// Cells previously returned by this [Context] keep the old id; only the cached zero cell is updated.
+    pub(crate) fn renumber(&mut self, context_id: usize) {
+        self.context_id = context_id;
+        if let Some(cell) = self.zero_cell.as_mut().and_then(|zero| zero.cell.as_mut()) {
+            cell.context_id = context_id;
+        }
+    }
+
     /// A unique tag that should identify this context across all virtual regions and phases.
     pub fn tag(&self) -> ContextTag {
         (self.type_id, self.context_id)
     }
 
+    /// Returns the number of cells assigned to the advice column of this [Context].
+    pub fn advice_len(&self) -> usize {
+        self.advice.len()
+    }
+
+    /// Returns the length of the selector column of this [Context].
+    ///
+    /// This may be less than [Context::advice_len], since the selector column is only extended when a gate is enabled.
+    pub fn selector_len(&self) -> usize {
+        self.selector.len()
+    }
+
+    /// Returns the number of gates enabled in this [Context], i.e., the number of `true` entries of the selector column.
+    ///
+    /// Unlike [Context::advice_len], this counts only the cells where a gate starts. Returns 0 if `witness_gen_only` is true,
+    /// since selectors are not tracked then.
+    pub fn num_active_gates(&self) -> usize {
+        if self.witness_gen_only {
+            return 0;
+        }
+        self.selector.iter().filter(|&&s| s).count()
+    }
+
+    /// Reserves capacity for at least `additional` more cells in the advice column, and in the selector column
+    /// unless `witness_gen_only` is true (when it is never written to).
+    ///
+    /// Avoids reallocations during assignment when the number of cells can be estimated up-front.
+    pub fn reserve(&mut self, additional: usize) {
+        self.advice.reserve(additional);
+        if !self.witness_gen_only {
+            self.selector.reserve(additional);
+        }
+    }
+
+    /// Returns the advice column of this [Context].
+    pub(crate) fn advice(&self) -> &[Assigned<F>] {
+        &self.advice
+    }
+
+    /// Returns the selector column of this [Context].
+    pub(crate) fn selector(&self) -> &[bool] {
+        &self.selector
+    }
+
+    /// Replaces the advice and selector columns of this [Context], returning the old ones.
+    pub(crate) fn replace_columns(
+        &mut self,
+        advice: Vec<Assigned<F>>,
+        selector: Vec<bool>,
+    ) -> (Vec<Assigned<F>>, Vec<bool>) {
+        (
+            std::mem::replace(&mut self.advice, advice),
+            std::mem::replace(&mut self.selector, selector),
+        )
+    }
+
+    /// Debug helper function for writing negative tests. This will change the **witness** value of the cell at `offset` in the advice column.
+    ///
+    /// Unlike [AssignedValue::debug_prank], this also works when `witness_gen_only` is true.
+    pub fn debug_prank(&mut self, offset: usize, prank_value: F) {
+        self.advice[offset] = Assigned::Trivial(prank_value);
+    }
+
+    /// Returns a [ContextSnapshot] of the current state of this [Context], to undo any assignments made after it with [Context::rollback].
+    pub fn snapshot(&self) -> ContextSnapshot<F> {
+        let (advice_equalities_len, constant_equalities_len) = if self.witness_gen_only {
+            (0, 0)
+        } else {
+            let copy_manager = self.copy_manager.lock().unwrap();
+            (copy_manager.advice_equalities.len(), copy_manager.constant_equalities.len())
+        };
+        ContextSnapshot {
+            advice_len: self.advice.len(),
+            selector_len: self.selector.len(),
+            advice_equalities_len,
+            constant_equalities_len,
+            zero_cell: self.zero_cell,
+        }
+    }
+
+    /// Removes all cells assigned to this [Context] after `snapshot` was taken, together with the copy constraints on them.
+    ///
+    /// Copy constraints added by other contexts since `snapshot` are kept, unless they involve a removed cell.
+    /// Any [AssignedValue] of a removed cell must no longer be used.
+    ///
+    /// Lookups are not stored in the [Context], so they are not removed: if cells may have been range checked
+    /// since `snapshot`, use [RangeChip::rollback](crate::gates::RangeChip::rollback) instead.
+    /// * `snapshot`: a [ContextSnapshot] previously returned by [Context::snapshot] on this [Context]
+    pub fn rollback(&mut self, snapshot: ContextSnapshot<F>) {
+        assert!(
+            snapshot.advice_len <= self.advice.len(),
+            "snapshot was not taken from this context or it was already rolled back past it"
+        );
+        if !self.witness_gen_only {
+            let (type_id, context_id) = self.tag();
+            let removed = |cell: &ContextCell| {
+                cell.type_id == type_id
+                    && cell.context_id == context_id
+                    && cell.offset >= snapshot.advice_len
+            };
+            let mut copy_manager = self.copy_manager.lock().unwrap();
+            let advice_equalities = &mut copy_manager.advice_equalities;
+            let added = advice_equalities.split_off(snapshot.advice_equalities_len);
+            advice_equalities.extend(added.into_iter().filter(|(a, b)| !removed(a) && !removed(b)));
+            let constant_equalities = &mut copy_manager.constant_equalities;
+            let added = constant_equalities.split_off(snapshot.constant_equalities_len);
+            constant_equalities.extend(added.into_iter().filter(|(_, cell)| !removed(cell)));
+        }
+        self.advice.truncate(snapshot.advice_len);
+        self.selector.truncate(snapshot.selector_len);
+        self.zero_cell = snapshot.zero_cell;
+    }
+
     fn latest_cell(&self) -> ContextCell {
         ContextCell::new(self.type_id, self.context_id, self.advice.len() - 1)
     }
@@ -250,11 +452,20 @@ impl<F: ScalarField> Context<F> {
                 // If witness generation is not performed, enforce equality constraints between the existing cell and the new cell
                 if !self.witness_gen_only {
                     let new_cell = self.latest_cell();
-                    self.copy_manager
-                        .lock()
-                        .unwrap()
-                        .advice_equalities
-                        .push((new_cell, acell.cell.unwrap()));
+                    let cell = acell.cell.unwrap();
+                    let mut copy_manager = self.copy_manager.lock().unwrap();
+                    // Catch cells from a later challenge phase being used in an earlier phase
+                    #[cfg(debug_assertions)]
+                    if let Some(&phase) =
+                        copy_manager.context_phases.get(&(cell.type_id, cell.context_id))
+                    {
+                        assert!(
+                            phase <= self.phase,
+                            "Cell from phase {phase} cannot be used in a context of phase {}",
+                            self.phase
+                        );
+                    }
+                    copy_manager.advice_equalities.push((new_cell, cell));
                 }
             }
             QuantumCell::Witness(val) => {
@@ -316,6 +527,15 @@ impl<F: ScalarField> Context<F> {
         }
     }
 
+    /// Creates an equality constraint between an `advice` cell and the constant `c`, without assigning a new cell.
+    /// * `a`: the `advice` cell to be constrained equal to `c`
+    /// * `c`: the constant value
+    pub fn constrain_constant(&mut self, a: &AssignedValue<F>, c: F) {
+        if !self.witness_gen_only {
+            self.copy_manager.lock().unwrap().constant_equalities.push((c, a.cell.unwrap()));
+        }
+    }
+
     /// Pushes multiple advice cells to the `advice` column of [Context] and enables them by enabling the corresponding selector specified in `gate_offset`.
     ///
     /// * `inputs`: Iterator that specifies the cells to be assigned
@@ -425,20 +645,35 @@ impl<F: ScalarField> Context<F> {
         &mut self,
         witnesses: impl IntoIterator<Item = F>,
     ) -> Vec<AssignedValue<F>> {
+        let mut assigned = Vec::new();
+        self.assign_witnesses_into(witnesses, &mut assigned);
+        assigned
+    }
+
+    /// Assigns a region of witness cells in an iterator and appends the assigned cells to `out`.
+    ///
+    /// Same as [`Self::assign_witnesses`], but reuses the allocation of a caller-supplied buffer.
+    /// * `witnesses`: Iterator that specifies the cells to be assigned
+    /// * `out`: [Vec] to append the assigned cells to
+    pub fn assign_witnesses_into(
+        &mut self,
+        witnesses: impl IntoIterator<Item = F>,
+        out: &mut Vec<AssignedValue<F>>,
+    ) {
         let row_offset = self.advice.len();
         self.assign_region(witnesses.into_iter().map(QuantumCell::Witness), []);
-        self.advice[row_offset..]
-            .iter()
-            .enumerate()
-            .map(|(i, v)| {
-                let cell = (!self.witness_gen_only).then_some(ContextCell::new(
-                    self.type_id,
-                    self.context_id,
-                    row_offset + i,
-                ));
-                AssignedValue { value: *v, cell }
-            })
-            .collect()
+        out.extend((row_offset..self.advice.len()).map(|offset| self.get(offset as isize)));
+    }
+
+    /// Assigns a fixed number of witness cells and returns an array of assigned cells, without heap allocation.
+    /// * `witnesses`: array of the witness values to be assigned
+    pub fn assign_witnesses_exact<const N: usize>(
+        &mut self,
+        witnesses: [F; N],
+    ) -> [AssignedValue<F>; N] {
+        let row_offset = self.advice.len();
+        self.assign_region(witnesses.map(QuantumCell::Witness), []);
+        std::array::from_fn(|i| self.get((row_offset + i) as isize))
     }
 
     /// Assigns a witness value and returns the corresponding assigned cell.
@@ -451,6 +686,25 @@ impl<F: ScalarField> Context<F> {
         self.last().unwrap()
     }
 
+    /// Assigns `witnesses` to new cells, without constraints, and returns the assigned cells.
+    ///
+    /// Unlike [Context::load_witness] and [Context::assign_witnesses], the same code runs for every witness whether or
+    /// not `witness_gen_only` is set: the selector column is always extended, even though it is unused during witness
+    /// generation, and each value is passed through [std::hint::black_box] so the compiler cannot branch on it.
+    /// This only covers the assignment itself; it does not make field arithmetic or the constraints built on top of
+    /// the returned cells constant time.
+    /// * `witnesses`: the witness values to be assigned
+    pub fn load_witnesses_constant_time(&mut self, witnesses: &[F]) -> Vec<AssignedValue<F>> {
+        let row_offset = self.advice.len();
+        self.advice.reserve(witnesses.len());
+        self.selector.reserve(witnesses.len());
+        for witness in witnesses {
+            self.advice.push(Assigned::Trivial(std::hint::black_box(*witness)));
+        }
+        self.selector.resize(self.advice.len(), false);
+        (row_offset..self.advice.len()).map(|offset| self.get(offset as isize)).collect()
+    }
+
     /// Assigns a constant value and returns the corresponding assigned cell.
     /// * `c`: the constant value to be assigned
     pub fn load_constant(&mut self, c: F) -> AssignedValue<F> {
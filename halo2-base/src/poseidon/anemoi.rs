@@ -0,0 +1,251 @@
+use std::array;
+
+#[cfg(feature = "halo2-axiom")]
+use crate::halo2_proofs::halo2curves::bls12_381;
+use crate::{
+    gates::GateInstructions,
+    halo2_proofs::halo2curves::bn256,
+    utils::{modulus, BigPrimeField, ScalarField},
+    AssignedValue, Context,
+    QuantumCell::Constant,
+};
+
+use getset::Getters;
+use num_traits::Zero;
+
+/// First 100 decimal digits of pi after the leading 3, used to derive round constants.
+const PI_0: &str = "1415926535897932384626433832795028841971693993751058209749445923078164062862089986280348253421170679";
+/// Next 100 decimal digits of pi, used to derive round constants.
+const PI_1: &str = "8214808651328230664709384460955058223172535940812848111745028410270193852110555964462294895493038196";
+
+/// Parameters of the Anemoi permutation on a state of `2 * L` field elements `(x, y)`, with `x, y` of length `L`.
+///
+/// Derived from the field as in the Anemoi paper: `alpha` is the smallest of 3, 5, 7, 11 coprime to `p - 1`,
+/// `beta` is a multiplicative generator `g` of the field, `delta = g^{-1}`, and the round constants are
+/// `c_{r,i} = g * pi_0^{2r} + (pi_0^r + pi_1^i)^alpha` and `d_{r,i} = g * pi_1^{2i} + (pi_0^r + pi_1^i)^alpha + g^{-1}`.
+/// The number of rounds targets 128 bits of security. Only `L = 1` and `L = 2` are supported.
+#[derive(Clone, Debug, Getters)]
+pub struct AnemoiSpec<F: ScalarField, const L: usize> {
+    /// Exponent of the Flystel S-box.
+    #[getset(get = "pub")]
+    alpha: u64,
+    /// Little-endian `u64` digits of `alpha^{-1} mod (p - 1)`.
+    #[getset(get = "pub")]
+    alpha_inv: Vec<u64>,
+    /// Multiplier of the quadratic functions of the Flystel S-box.
+    #[getset(get = "pub")]
+    beta: F,
+    /// Constant of the second quadratic function of the Flystel S-box.
+    #[getset(get = "pub")]
+    delta: F,
+    /// Round constants `(c_r, d_r)` added to `(x, y)` at the start of round `r`.
+    #[getset(get = "pub")]
+    round_constants: Vec<([F; L], [F; L])>,
+    /// MDS matrix of the linear layer.
+    #[getset(get = "pub")]
+    mds: [[F; L]; L],
+}
+
+impl<F: BigPrimeField, const L: usize> AnemoiSpec<F, L> {
+    /// Derives the Anemoi parameters for the field `F` with generator `g`.
+    ///
+    /// The generator is not necessarily [PrimeField::MULTIPLICATIVE_GENERATOR](crate::ff::PrimeField::MULTIPLICATIVE_GENERATOR):
+    /// to match the reference implementation, use the generator it fixes for the field, e.g. [AnemoiSpec::bn254].
+    pub fn new(g: F) -> Self {
+        assert!(!g.is_zero_vartime(), "generator must be nonzero");
+        let num_rounds = match L {
+            1 => 21,
+            2 => 14,
+            _ => panic!("Anemoi is only supported for L = 1 or L = 2"),
+        };
+        let p_minus_one = modulus::<F>() - 1u64;
+        let (alpha, alpha_inv) = [3u64, 5, 7, 11]
+            .into_iter()
+            .find_map(|alpha| {
+                // alpha^{-1} = (k * (p - 1) + 1) / alpha for some 0 < k < alpha if gcd(alpha, p - 1) = 1
+                (1..alpha)
+                    .map(|k| &p_minus_one * k + 1u64)
+                    .find(|n| (n % alpha).is_zero())
+                    .map(|n| (alpha, (n / alpha).to_u64_digits()))
+            })
+            .expect("no supported Anemoi exponent is coprime to p - 1");
+
+        let delta = g.invert().unwrap();
+        let [pi_0, pi_1] = [PI_0, PI_1].map(|pi| F::from_str_vartime(pi).unwrap());
+        let round_constants = (0..num_rounds)
+            .map(|r| {
+                let pi_0_r = pi_0.pow_vartime([r]);
+                let mut c = [F::ZERO; L];
+                let mut d = [F::ZERO; L];
+                for i in 0..L {
+                    let pi_1_i = pi_1.pow_vartime([i as u64]);
+                    let sum_pow = (pi_0_r + pi_1_i).pow_vartime([alpha]);
+                    c[i] = g * pi_0_r.square() + sum_pow;
+                    d[i] = g * pi_1_i.square() + sum_pow + delta;
+                }
+                (c, d)
+            })
+            .collect();
+
+        let mds_rows = match L {
+            1 => vec![vec![F::ONE]],
+            _ => vec![vec![F::ONE, g], vec![g, g.square() + F::ONE]],
+        };
+        let mds = array::from_fn(|i| array::from_fn(|j| mds_rows[i][j]));
+
+        Self { alpha, alpha_inv, beta: g, delta, round_constants, mds }
+    }
+}
+
+impl<const L: usize> AnemoiSpec<bn256::Fr, L> {
+    /// Anemoi parameters for the BN254 scalar field with the generator `g = 5` of the reference implementation,
+    /// which differs from the halo2curves generator 7.
+    pub fn bn254() -> Self {
+        Self::new(bn256::Fr::from(5))
+    }
+}
+
+#[cfg(feature = "halo2-axiom")]
+impl<const L: usize> AnemoiSpec<bls12_381::Fr, L> {
+    /// Anemoi parameters for the BLS12-381 scalar field with the generator `g = 7` of the reference implementation.
+    pub fn bls12_381() -> Self {
+        Self::new(bls12_381::Fr::from(7))
+    }
+}
+
+/// Stateless Anemoi hasher.
+#[derive(Clone, Debug)]
+pub struct AnemoiHasher<F: ScalarField, const L: usize> {
+    spec: AnemoiSpec<F, L>,
+}
+
+impl<F: ScalarField, const L: usize> AnemoiHasher<F, L> {
+    /// Create a new AnemoiHasher.
+    pub fn new(spec: AnemoiSpec<F, L>) -> Self {
+        Self { spec }
+    }
+
+    /// Returns the Anemoi specification of this hasher.
+    pub fn spec(&self) -> &AnemoiSpec<F, L> {
+        &self.spec
+    }
+
+    /// Constrains and returns the Anemoi permutation of the state `(x, y)`.
+    pub fn permutation(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        state: ([AssignedValue<F>; L], [AssignedValue<F>; L]),
+    ) -> ([AssignedValue<F>; L], [AssignedValue<F>; L]) {
+        let (mut x, mut y) = state;
+        for (c, d) in &self.spec.round_constants {
+            x = array::from_fn(|i| gate.add(ctx, x[i], Constant(c[i])));
+            y = array::from_fn(|i| gate.add(ctx, y[i], Constant(d[i])));
+            (x, y) = self.linear_layer(ctx, gate, x, y);
+            for i in 0..L {
+                (x[i], y[i]) = self.flystel(ctx, gate, x[i], y[i]);
+            }
+        }
+        self.linear_layer(ctx, gate, x, y)
+    }
+
+    /// Constrains and returns the Jive compression of `2 * L` elements `(x, y)` into `L` elements,
+    /// `x_i + y_i + u_i + v_i` where `(u, v)` is the permutation of `(x, y)`.
+    pub fn compress(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        x: [AssignedValue<F>; L],
+        y: [AssignedValue<F>; L],
+    ) -> [AssignedValue<F>; L] {
+        let (u, v) = self.permutation(ctx, gate, (x, y));
+        array::from_fn(|i| gate.sum(ctx, [x[i], y[i], u[i], v[i]]))
+    }
+
+    /// Constrains and returns the sponge hash of a witness array, with rate `2 * L - 1` and capacity 1.
+    ///
+    /// If the length of `inputs` is a non-zero multiple of the rate, 1 is added to the capacity element before absorbing.
+    /// Otherwise `inputs` is padded with a single 1 followed by zeros.
+    /// * inputs: An array of [AssignedValue].
+    pub fn hash_fix_len_array(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        inputs: &[AssignedValue<F>],
+    ) -> AssignedValue<F> {
+        let rate = 2 * L - 1;
+        let zero = ctx.load_zero();
+        let mut x = [zero; L];
+        let mut y = [zero; L];
+        let mut inputs = inputs.to_vec();
+        if inputs.is_empty() || inputs.len() % rate != 0 {
+            inputs.push(ctx.load_constant(F::ONE));
+            inputs.resize((inputs.len() + rate - 1) / rate * rate, zero);
+        } else {
+            y[L - 1] = ctx.load_constant(F::ONE);
+        }
+        for chunk in inputs.chunks(rate) {
+            for (i, input) in chunk.iter().enumerate() {
+                let s = if i < L { &mut x[i] } else { &mut y[i - L] };
+                *s = gate.add(ctx, *s, *input);
+            }
+            (x, y) = self.permutation(ctx, gate, (x, y));
+        }
+        x[0]
+    }
+
+    /// Constrains and returns the linear layer `x <- M x, y <- M rot(y)` followed by the pseudo-Hadamard transform
+    /// `y <- y + x, x <- x + y`, where `rot` rotates left by one.
+    fn linear_layer(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        x: [AssignedValue<F>; L],
+        y: [AssignedValue<F>; L],
+    ) -> ([AssignedValue<F>; L], [AssignedValue<F>; L]) {
+        let (x, y) = if L == 1 {
+            (x, y)
+        } else {
+            let mds = &self.spec.mds;
+            let x =
+                array::from_fn(|i| gate.inner_product(ctx, x, mds[i].iter().map(|m| Constant(*m))));
+            let y = array::from_fn(|i| {
+                let rot_y = (0..L).map(|j| y[(j + 1) % L]);
+                gate.inner_product(ctx, rot_y, mds[i].iter().map(|m| Constant(*m)))
+            });
+            (x, y)
+        };
+        let y: [_; L] = array::from_fn(|i| gate.add(ctx, y[i], x[i]));
+        let x = array::from_fn(|i| gate.add(ctx, x[i], y[i]));
+        (x, y)
+    }
+
+    /// Constrains and returns the open Flystel S-box:
+    /// `x <- x - beta * y^2`, `y <- y - x^{1/alpha}`, `x <- x + beta * y^2 + delta`.
+    ///
+    /// The `alpha`-th root is witnessed and its `alpha`-th power is constrained to equal `x`.
+    fn flystel(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        x: AssignedValue<F>,
+        y: AssignedValue<F>,
+    ) -> (AssignedValue<F>, AssignedValue<F>) {
+        let beta = Constant(self.spec.beta);
+        let y_sq = gate.mul(ctx, y, y);
+        let x = gate.sub_mul(ctx, x, beta, y_sq);
+
+        let root = ctx.load_witness(x.value().pow_vartime(&self.spec.alpha_inv));
+        let mut root_pow = root;
+        for _ in 1..self.spec.alpha {
+            root_pow = gate.mul(ctx, root_pow, root);
+        }
+        ctx.constrain_equal(&root_pow, &x);
+        let y = gate.sub(ctx, y, root);
+
+        let y_sq = gate.mul(ctx, y, y);
+        let x = gate.mul_add(ctx, beta, y_sq, x);
+        let x = gate.add(ctx, x, Constant(self.spec.delta));
+        (x, y)
+    }
+}
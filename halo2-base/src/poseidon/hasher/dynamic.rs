@@ -0,0 +1,185 @@
+use crate::{
+    ff::{FromUniformBytes, PrimeField},
+    gates::GateInstructions,
+    poseidon::hasher::{
+        spec::OptimizedPoseidonSpec,
+        state::{absorb_with_pre_constants, apply_mds, apply_sparse_mds, sbox_full, sbox_part},
+        PoseidonHasher,
+    },
+    utils::ScalarField,
+    AssignedValue, Context,
+};
+
+use getset::{CopyGetters, Getters};
+
+/// [OptimizedPoseidonSpec] with the state width stored at runtime instead of as a const generic.
+#[derive(Debug, Clone, Getters, CopyGetters)]
+pub struct DynPoseidonSpec<F: PrimeField> {
+    /// Width of the state
+    #[getset(get_copy = "pub")]
+    t: usize,
+    /// Number of full rounds
+    #[getset(get_copy = "pub")]
+    r_f: usize,
+    /// MDS matrix
+    #[getset(get = "pub")]
+    mds: Vec<Vec<F>>,
+    /// Transition matrix
+    #[getset(get = "pub")]
+    pre_sparse_mds: Vec<Vec<F>>,
+    /// Sparse matrices, as pairs of `(row, col_hat)`
+    #[getset(get = "pub")]
+    sparse_matrices: Vec<(Vec<F>, Vec<F>)>,
+    /// Round constants of the first half of the full rounds
+    #[getset(get = "pub")]
+    constants_start: Vec<Vec<F>>,
+    /// Round constants of the partial rounds
+    #[getset(get = "pub")]
+    constants_partial: Vec<F>,
+    /// Round constants of the second half of the full rounds
+    #[getset(get = "pub")]
+    constants_end: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> DynPoseidonSpec<F> {
+    /// Generate new spec for a rate only known at runtime, with capacity 1.
+    ///
+    /// Supported rates are 2 to 8. For other rates, construct an [OptimizedPoseidonSpec] and convert it.
+    pub fn new<const R_F: usize, const R_P: usize, const SECURE_MDS: usize>(rate: usize) -> Self
+    where
+        F: FromUniformBytes<64> + Ord,
+    {
+        macro_rules! dispatch {
+            ($($rate:literal),*) => {
+                match rate {
+                    $($rate => OptimizedPoseidonSpec::<F, { $rate + 1 }, $rate>::new::<R_F, R_P, SECURE_MDS>().into(),)*
+                    _ => panic!("unsupported Poseidon rate {rate}"),
+                }
+            };
+        }
+        dispatch!(2, 3, 4, 5, 6, 7, 8)
+    }
+}
+
+impl<F: PrimeField, const T: usize, const RATE: usize> From<OptimizedPoseidonSpec<F, T, RATE>>
+    for DynPoseidonSpec<F>
+{
+    fn from(spec: OptimizedPoseidonSpec<F, T, RATE>) -> Self {
+        let to_vecs = |m: [[F; T]; T]| m.map(Vec::from).to_vec();
+        let mds_matrices = spec.mds_matrices;
+        Self {
+            t: T,
+            r_f: spec.r_f,
+            mds: to_vecs(mds_matrices.mds.0),
+            pre_sparse_mds: to_vecs(mds_matrices.pre_sparse_mds.0),
+            sparse_matrices: mds_matrices
+                .sparse_matrices
+                .into_iter()
+                .map(|m| (m.row.to_vec(), m.col_hat.to_vec()))
+                .collect(),
+            constants_start: spec.constants.start.into_iter().map(Vec::from).collect(),
+            constants_partial: spec.constants.partial,
+            constants_end: spec.constants.end.into_iter().map(Vec::from).collect(),
+        }
+    }
+}
+
+/// Stateless Poseidon hasher whose rate and capacity are chosen at runtime.
+///
+/// Produces the same hashes as [PoseidonHasher] with the same spec, so code using it can migrate incrementally.
+#[derive(Clone, Debug, Getters, CopyGetters)]
+pub struct DynPoseidonChip<F: ScalarField> {
+    /// Number of elements absorbed per permutation
+    #[getset(get_copy = "pub")]
+    rate: usize,
+    /// Number of state elements not touched by inputs
+    #[getset(get_copy = "pub")]
+    capacity: usize,
+    /// Spec of the permutation
+    #[getset(get = "pub")]
+    spec: DynPoseidonSpec<F>,
+}
+
+impl<F: ScalarField> DynPoseidonChip<F> {
+    /// Create a new [DynPoseidonChip].
+    ///
+    /// Panics if `rate` is 0, if `capacity` is not 1 (the only capacity the optimized permutation supports),
+    /// or if `spec` does not have a state of width `rate + capacity`.
+    pub fn new(rate: usize, capacity: usize, spec: DynPoseidonSpec<F>) -> Self {
+        assert!(rate > 0, "Poseidon rate must be positive");
+        assert_eq!(capacity, 1, "Poseidon capacity must be 1");
+        assert_eq!(spec.t, rate + capacity, "Poseidon spec width does not match rate and capacity");
+        Self { rate, capacity, spec }
+    }
+
+    /// Constrains and returns hash of a witness array.
+    ///
+    /// * inputs: An array of [AssignedValue].
+    /// Return hash of `inputs`.
+    pub fn hash_fix_len_array(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        inputs: &[AssignedValue<F>],
+    ) -> AssignedValue<F> {
+        let mut state = self.init_state(ctx);
+        for chunk in inputs.chunks(self.rate) {
+            self.permutation(ctx, gate, &mut state, chunk);
+        }
+        if inputs.len() % self.rate == 0 {
+            self.permutation(ctx, gate, &mut state, &[]);
+        }
+        state[1]
+    }
+
+    fn init_state(&self, ctx: &mut Context<F>) -> Vec<AssignedValue<F>> {
+        // same capacity value as `PoseidonState::default`
+        let mut state = vec![F::ZERO; self.spec.t];
+        state[0] = F::from_u128(1u128 << 64);
+        state.into_iter().map(|f| ctx.load_constant(f)).collect()
+    }
+
+    /// Same as `PoseidonState::permutation` for fixed length inputs, with the width of the state known at runtime.
+    fn permutation(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        state: &mut [AssignedValue<F>],
+        inputs: &[AssignedValue<F>],
+    ) {
+        let spec = &self.spec;
+        let r_f = spec.r_f / 2;
+
+        // First half of the full rounds
+        let constants = &spec.constants_start;
+        absorb_with_pre_constants(ctx, gate, state, inputs, &constants[0]);
+        for constants in constants.iter().skip(1).take(r_f - 1) {
+            sbox_full(ctx, gate, state, constants);
+            apply_mds(ctx, gate, state, &spec.mds);
+        }
+        sbox_full(ctx, gate, state, constants.last().unwrap());
+        apply_mds(ctx, gate, state, &spec.pre_sparse_mds);
+
+        // Partial rounds
+        for (constant, (row, col_hat)) in spec.constants_partial.iter().zip(&spec.sparse_matrices) {
+            sbox_part(ctx, gate, state, constant);
+            apply_sparse_mds(ctx, gate, state, row, col_hat);
+        }
+
+        // Second half of the full rounds
+        for constants in &spec.constants_end {
+            sbox_full(ctx, gate, state, constants);
+            apply_mds(ctx, gate, state, &spec.mds);
+        }
+        sbox_full(ctx, gate, state, &vec![F::ZERO; spec.t]);
+        apply_mds(ctx, gate, state, &spec.mds);
+    }
+}
+
+impl<F: ScalarField, const T: usize, const RATE: usize> From<PoseidonHasher<F, T, RATE>>
+    for DynPoseidonChip<F>
+{
+    fn from(hasher: PoseidonHasher<F, T, RATE>) -> Self {
+        Self::new(RATE, T - RATE, hasher.spec.into())
+    }
+}
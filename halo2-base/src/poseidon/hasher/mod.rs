@@ -15,6 +15,8 @@ use std::{cell::OnceCell, mem};
 #[cfg(test)]
 mod tests;
 
+/// Module for Poseidon hasher with the rate chosen at runtime.
+pub mod dynamic;
 /// Module for maximum distance separable matrix operations.
 pub mod mds;
 /// Module for poseidon specification.
@@ -319,6 +321,24 @@ impl<F: ScalarField, const T: usize, const RATE: usize> PoseidonSponge<F, T, RAT
         self.absorbing.extend_from_slice(elements);
     }
 
+    /// Absorbs `elements`, permuting the state with every full chunk of `RATE` buffered elements right away.
+    ///
+    /// Equivalent to [PoseidonSponge::update], but only less than `RATE` elements are kept in the buffer between
+    /// calls, so large messages can be absorbed incrementally.
+    pub fn absorb(
+        &mut self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+        elements: &[AssignedValue<F>],
+    ) {
+        self.absorbing.extend_from_slice(elements);
+        let num_full = self.absorbing.len() / RATE * RATE;
+        for chunk in self.absorbing[..num_full].chunks(RATE) {
+            self.state.permutation(ctx, gate, chunk, None, &self.spec);
+        }
+        self.absorbing.drain(..num_full);
+    }
+
     /// Consume buffer and perform permutation, then output second element of
     /// state.
     pub fn squeeze(
@@ -1,10 +1,8 @@
-use std::iter;
-
 use itertools::Itertools;
 
 use crate::{
     gates::GateInstructions,
-    poseidon::hasher::{mds::SparseMDSMatrix, spec::OptimizedPoseidonSpec},
+    poseidon::hasher::spec::OptimizedPoseidonSpec,
     safe_types::SafeBool,
     utils::ScalarField,
     AssignedValue, Context,
@@ -55,30 +53,30 @@ impl<F: ScalarField, const T: usize, const RATE: usize> PoseidonState<F, T, RATE
                 );
             self.absorb_var_len_with_pre_constants(ctx, gate, padded_inputs, len, &constants[0]);
         } else {
-            self.absorb_with_pre_constants(ctx, gate, inputs, &constants[0]);
+            absorb_with_pre_constants(ctx, gate, &mut self.s, inputs, &constants[0]);
         }
         for constants in constants.iter().skip(1).take(r_f - 1) {
-            self.sbox_full(ctx, gate, constants);
-            self.apply_mds(ctx, gate, mds);
+            sbox_full(ctx, gate, &mut self.s, constants);
+            apply_mds(ctx, gate, &mut self.s, mds);
         }
-        self.sbox_full(ctx, gate, constants.last().unwrap());
-        self.apply_mds(ctx, gate, pre_sparse_mds);
+        sbox_full(ctx, gate, &mut self.s, constants.last().unwrap());
+        apply_mds(ctx, gate, &mut self.s, pre_sparse_mds);
 
         // Partial rounds
         let constants = &spec.constants.partial;
         for (constant, sparse_mds) in constants.iter().zip(sparse_matrices.iter()) {
-            self.sbox_part(ctx, gate, constant);
-            self.apply_sparse_mds(ctx, gate, sparse_mds);
+            sbox_part(ctx, gate, &mut self.s, constant);
+            apply_sparse_mds(ctx, gate, &mut self.s, &sparse_mds.row, &sparse_mds.col_hat);
         }
 
         // Second half of the full rounds
         let constants = &spec.constants.end;
         for constants in constants.iter() {
-            self.sbox_full(ctx, gate, constants);
-            self.apply_mds(ctx, gate, mds);
+            sbox_full(ctx, gate, &mut self.s, constants);
+            apply_mds(ctx, gate, &mut self.s, mds);
         }
-        self.sbox_full(ctx, gate, &[F::ZERO; T]);
-        self.apply_mds(ctx, gate, mds);
+        sbox_full(ctx, gate, &mut self.s, &[F::ZERO; T]);
+        apply_mds(ctx, gate, &mut self.s, mds);
     }
 
     /// Constrains and set self to a specific state if `selector` is true.
@@ -94,72 +92,6 @@ impl<F: ScalarField, const T: usize, const RATE: usize> PoseidonState<F, T, RATE
         }
     }
 
-    fn x_power5_with_constant(
-        ctx: &mut Context<F>,
-        gate: &impl GateInstructions<F>,
-        x: AssignedValue<F>,
-        constant: &F,
-    ) -> AssignedValue<F> {
-        let x2 = gate.mul(ctx, x, x);
-        let x4 = gate.mul(ctx, x2, x2);
-        gate.mul_add(ctx, x, x4, Constant(*constant))
-    }
-
-    fn sbox_full(
-        &mut self,
-        ctx: &mut Context<F>,
-        gate: &impl GateInstructions<F>,
-        constants: &[F; T],
-    ) {
-        for (x, constant) in self.s.iter_mut().zip(constants.iter()) {
-            *x = Self::x_power5_with_constant(ctx, gate, *x, constant);
-        }
-    }
-
-    fn sbox_part(&mut self, ctx: &mut Context<F>, gate: &impl GateInstructions<F>, constant: &F) {
-        let x = &mut self.s[0];
-        *x = Self::x_power5_with_constant(ctx, gate, *x, constant);
-    }
-
-    fn absorb_with_pre_constants(
-        &mut self,
-        ctx: &mut Context<F>,
-        gate: &impl GateInstructions<F>,
-        inputs: &[AssignedValue<F>],
-        pre_constants: &[F; T],
-    ) {
-        assert!(inputs.len() < T);
-
-        // Explanation of what's going on: before each round of the poseidon permutation,
-        // two things have to be added to the state: inputs (the absorbed elements) and
-        // preconstants. Imagine the state as a list of T elements, the first of which is
-        // the capacity:  |--cap--|--el1--|--el2--|--elR--|
-        // - A preconstant is added to each of all T elements (which is different for each)
-        // - The inputs are added to all elements starting from el1 (so, not to the capacity),
-        //   to as many elements as inputs are available.
-        // - To the first element for which no input is left (if any), an extra 1 is added.
-
-        // adding preconstant to the distinguished capacity element (only one)
-        self.s[0] = gate.add(ctx, self.s[0], Constant(pre_constants[0]));
-
-        // adding pre-constants and inputs to the elements for which both are available
-        for ((x, constant), input) in
-            self.s.iter_mut().zip(pre_constants.iter()).skip(1).zip(inputs.iter())
-        {
-            *x = gate.sum(ctx, [Existing(*x), Existing(*input), Constant(*constant)]);
-        }
-
-        let offset = inputs.len() + 1;
-        // adding only pre-constants when no input is left
-        for (i, (x, constant)) in
-            self.s.iter_mut().zip(pre_constants.iter()).skip(offset).enumerate()
-        {
-            *x = gate.add(ctx, *x, Constant(if i == 0 { F::ONE + constant } else { *constant }));
-            // the if idx == 0 { F::one() } else { F::zero() } is to pad the input with a single 1 and then 0s
-            // this is the padding suggested in pg 31 of https://eprint.iacr.org/2019/458.pdf and in Section 4.2 (Variable-Input-Length Hashing. The padding consists of one field element being 1, and the remaining elements being 0.)
-        }
-    }
-
     /// Absorb inputs with a variable length.
     ///
     /// `inputs` is right padded.
@@ -210,42 +142,111 @@ impl<F: ScalarField, const T: usize, const RATE: usize> PoseidonState<F, T, RATE
         let empty_extra_one = gate.not(ctx, inputs_mask[0]);
         self.s[1] = gate.add(ctx, self.s[1], empty_extra_one);
     }
+}
 
-    fn apply_mds(
-        &mut self,
-        ctx: &mut Context<F>,
-        gate: &impl GateInstructions<F>,
-        mds: &[[F; T]; T],
-    ) {
-        let res = mds
-            .iter()
-            .map(|row| {
-                gate.inner_product(ctx, self.s.iter().copied(), row.iter().map(|c| Constant(*c)))
-            })
-            .collect::<Vec<_>>();
+// The round helpers below work on a state of any width, so that they are shared by [PoseidonState] and
+// [DynPoseidonChip](super::dynamic::DynPoseidonChip).
+
+fn x_power5_with_constant<F: ScalarField>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    x: AssignedValue<F>,
+    constant: &F,
+) -> AssignedValue<F> {
+    let x2 = gate.mul(ctx, x, x);
+    let x4 = gate.mul(ctx, x2, x2);
+    gate.mul_add(ctx, x, x4, Constant(*constant))
+}
 
-        self.s = res.try_into().unwrap();
+pub(crate) fn sbox_full<F: ScalarField>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    state: &mut [AssignedValue<F>],
+    constants: &[F],
+) {
+    for (x, constant) in state.iter_mut().zip(constants.iter()) {
+        *x = x_power5_with_constant(ctx, gate, *x, constant);
     }
+}
 
-    fn apply_sparse_mds(
-        &mut self,
-        ctx: &mut Context<F>,
-        gate: &impl GateInstructions<F>,
-        mds: &SparseMDSMatrix<F, T, RATE>,
-    ) {
-        self.s = iter::once(gate.inner_product(
-            ctx,
-            self.s.iter().copied(),
-            mds.row.iter().map(|c| Constant(*c)),
-        ))
-        .chain(
-            mds.col_hat
-                .iter()
-                .zip(self.s.iter().skip(1))
-                .map(|(coeff, state)| gate.mul_add(ctx, self.s[0], Constant(*coeff), *state)),
-        )
-        .collect::<Vec<_>>()
-        .try_into()
-        .unwrap();
+pub(crate) fn sbox_part<F: ScalarField>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    state: &mut [AssignedValue<F>],
+    constant: &F,
+) {
+    let x = &mut state[0];
+    *x = x_power5_with_constant(ctx, gate, *x, constant);
+}
+
+pub(crate) fn absorb_with_pre_constants<F: ScalarField>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    state: &mut [AssignedValue<F>],
+    inputs: &[AssignedValue<F>],
+    pre_constants: &[F],
+) {
+    assert!(inputs.len() < state.len());
+
+    // Explanation of what's going on: before each round of the poseidon permutation,
+    // two things have to be added to the state: inputs (the absorbed elements) and
+    // preconstants. Imagine the state as a list of T elements, the first of which is
+    // the capacity:  |--cap--|--el1--|--el2--|--elR--|
+    // - A preconstant is added to each of all T elements (which is different for each)
+    // - The inputs are added to all elements starting from el1 (so, not to the capacity),
+    //   to as many elements as inputs are available.
+    // - To the first element for which no input is left (if any), an extra 1 is added.
+
+    // adding preconstant to the distinguished capacity element (only one)
+    state[0] = gate.add(ctx, state[0], Constant(pre_constants[0]));
+
+    // adding pre-constants and inputs to the elements for which both are available
+    for ((x, constant), input) in
+        state.iter_mut().zip(pre_constants.iter()).skip(1).zip(inputs.iter())
+    {
+        *x = gate.sum(ctx, [Existing(*x), Existing(*input), Constant(*constant)]);
+    }
+
+    let offset = inputs.len() + 1;
+    // adding only pre-constants when no input is left
+    for (i, (x, constant)) in state.iter_mut().zip(pre_constants.iter()).skip(offset).enumerate() {
+        *x = gate.add(ctx, *x, Constant(if i == 0 { F::ONE + constant } else { *constant }));
+        // the if idx == 0 { F::one() } else { F::zero() } is to pad the input with a single 1 and then 0s
+        // this is the padding suggested in pg 31 of https://eprint.iacr.org/2019/458.pdf and in Section 4.2 (Variable-Input-Length Hashing. The padding consists of one field element being 1, and the remaining elements being 0.)
+    }
+}
+
+pub(crate) fn apply_mds<F: ScalarField>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    state: &mut [AssignedValue<F>],
+    mds: &[impl AsRef<[F]>],
+) {
+    let res = mds
+        .iter()
+        .map(|row| {
+            gate.inner_product(
+                ctx,
+                state.iter().copied(),
+                row.as_ref().iter().map(|c| Constant(*c)),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    state.copy_from_slice(&res);
+}
+
+/// Multiplies the state by the sparse MDS matrix with first row `row` and first column `col_hat` below the diagonal.
+pub(crate) fn apply_sparse_mds<F: ScalarField>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    state: &mut [AssignedValue<F>],
+    row: &[F],
+    col_hat: &[F],
+) {
+    let s0 = state[0];
+    state[0] = gate.inner_product(ctx, state.iter().copied(), row.iter().map(|c| Constant(*c)));
+    for (x, coeff) in state.iter_mut().skip(1).zip(col_hat.iter()) {
+        *x = gate.mul_add(ctx, s0, Constant(*coeff), *x);
     }
 }
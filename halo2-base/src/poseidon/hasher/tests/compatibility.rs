@@ -22,6 +22,8 @@ fn sponge_compatiblity_verification<
     mut absorptions: Vec<Vec<F>>,
     // list of amounts of elements of F that should be squeezed every time
     mut squeezings: Vec<usize>,
+    // whether to absorb with `absorb` instead of `update`
+    eager: bool,
 ) {
     let mut pool = SinglePhaseCoreManager::new(true, Default::default());
     let gate = GateChip::default();
@@ -41,7 +43,12 @@ fn sponge_compatiblity_verification<
     for (absorption, squeezing) in zip(absorptions, squeezings) {
         // absorb (if any elements were provided)
         native_sponge.update(&absorption);
-        circuit_sponge.update(&ctx.assign_witnesses(absorption));
+        let absorption = ctx.assign_witnesses(absorption);
+        if eager {
+            circuit_sponge.absorb(ctx, &gate, &absorption);
+        } else {
+            circuit_sponge.update(&absorption);
+        }
 
         // squeeze (if any elements were requested)
         for _ in 0..squeezing {
@@ -89,7 +96,7 @@ fn test_sponge_compatibility_squeezing_only() {
     let absorptions = Vec::new();
     let squeezings = random_list_usize(10, 7);
 
-    sponge_compatiblity_verification::<Fr, 3, 2, 8, 57>(absorptions, squeezings);
+    sponge_compatiblity_verification::<Fr, 3, 2, 8, 57>(absorptions, squeezings, false);
 }
 
 #[test]
@@ -97,7 +104,7 @@ fn test_sponge_compatibility_absorbing_only() {
     let absorptions = random_nested_list_f(8, 5);
     let squeezings = Vec::new();
 
-    sponge_compatiblity_verification::<Fr, 3, 2, 8, 57>(absorptions, squeezings);
+    sponge_compatiblity_verification::<Fr, 3, 2, 8, 57>(absorptions, squeezings, false);
 }
 
 #[test]
@@ -105,7 +112,7 @@ fn test_sponge_compatibility_interleaved() {
     let absorptions = random_nested_list_f(10, 5);
     let squeezings = random_list_usize(7, 10);
 
-    sponge_compatiblity_verification::<Fr, 3, 2, 8, 57>(absorptions, squeezings);
+    sponge_compatiblity_verification::<Fr, 3, 2, 8, 57>(absorptions, squeezings, false);
 }
 
 #[test]
@@ -113,5 +120,21 @@ fn test_sponge_compatibility_other_params() {
     let absorptions = random_nested_list_f(10, 10);
     let squeezings = random_list_usize(10, 10);
 
-    sponge_compatiblity_verification::<Fr, 5, 4, 8, 120>(absorptions, squeezings);
+    sponge_compatiblity_verification::<Fr, 5, 4, 8, 120>(absorptions, squeezings, false);
+}
+
+#[test]
+fn test_sponge_compatibility_absorb_interleaved() {
+    let absorptions = random_nested_list_f(10, 5);
+    let squeezings = random_list_usize(7, 10);
+
+    sponge_compatiblity_verification::<Fr, 3, 2, 8, 57>(absorptions, squeezings, true);
+}
+
+#[test]
+fn test_sponge_compatibility_absorb_other_params() {
+    let absorptions = random_nested_list_f(10, 10);
+    let squeezings = random_list_usize(10, 10);
+
+    sponge_compatiblity_verification::<Fr, 5, 4, 8, 120>(absorptions, squeezings, true);
 }
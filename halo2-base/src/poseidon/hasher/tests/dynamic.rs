@@ -0,0 +1,64 @@
+use crate::{
+    gates::RangeInstructions,
+    halo2_proofs::halo2curves::bn256::Fr,
+    poseidon::hasher::{
+        dynamic::{DynPoseidonChip, DynPoseidonSpec},
+        spec::OptimizedPoseidonSpec,
+        PoseidonHasher,
+    },
+    utils::testing::base_test,
+};
+use pse_poseidon::Poseidon;
+use test_case::test_case;
+
+fn dyn_hasher_compatibility_verification<const T: usize, const RATE: usize>(len: usize) {
+    base_test().k(14).run(|ctx, range| {
+        let values = (0..len as u64).map(|i| Fr::from(i * i + 7)).collect::<Vec<_>>();
+        let inputs = ctx.assign_witnesses(values.clone());
+
+        let mut native_sponge = Poseidon::<Fr, T, RATE>::new(8, 57);
+        native_sponge.update(&values);
+        let native_result = native_sponge.squeeze();
+
+        let chip = DynPoseidonChip::new(RATE, 1, DynPoseidonSpec::new::<8, 57, 0>(RATE));
+        let dyn_result = chip.hash_fix_len_array(ctx, range.gate(), &inputs);
+        assert_eq!(*dyn_result.value(), native_result);
+
+        let spec = OptimizedPoseidonSpec::<Fr, T, RATE>::new::<8, 57, 0>();
+        let mut hasher = PoseidonHasher::<Fr, T, RATE>::new(spec);
+        hasher.initialize_consts(ctx, range.gate());
+        let hasher_result = hasher.hash_fix_len_array(ctx, range.gate(), &inputs);
+        let converted = DynPoseidonChip::from(hasher);
+        assert_eq!((converted.rate(), converted.capacity()), (RATE, 1));
+        let converted_result = converted.hash_fix_len_array(ctx, range.gate(), &inputs);
+        assert_eq!(*converted_result.value(), *hasher_result.value());
+    });
+}
+
+#[test_case(0; "empty")]
+#[test_case(4; "multiple of rate")]
+#[test_case(7; "not multiple of rate")]
+fn test_dyn_poseidon_rate_2(len: usize) {
+    dyn_hasher_compatibility_verification::<3, 2>(len);
+}
+
+#[test_case(0; "empty")]
+#[test_case(8; "multiple of rate")]
+#[test_case(11; "not multiple of rate")]
+fn test_dyn_poseidon_rate_4(len: usize) {
+    dyn_hasher_compatibility_verification::<5, 4>(len);
+}
+
+#[test]
+#[should_panic(expected = "Poseidon capacity must be 1")]
+fn test_dyn_poseidon_bad_capacity() {
+    let spec = DynPoseidonSpec::<Fr>::new::<8, 57, 0>(2);
+    DynPoseidonChip::new(1, 2, spec);
+}
+
+#[test]
+#[should_panic(expected = "Poseidon spec width does not match rate and capacity")]
+fn test_dyn_poseidon_bad_rate() {
+    let spec = DynPoseidonSpec::<Fr>::new::<8, 57, 0>(2);
+    DynPoseidonChip::new(4, 1, spec);
+}
@@ -4,6 +4,7 @@ use crate::halo2_proofs::halo2curves::{bn256::Fr, ff::PrimeField};
 use itertools::Itertools;
 
 mod compatibility;
+mod dynamic;
 mod hasher;
 mod state;
 
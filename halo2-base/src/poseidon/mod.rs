@@ -1,15 +1,26 @@
 use crate::{
-    gates::{RangeChip, RangeInstructions},
+    gates::{GateInstructions, RangeChip, RangeInstructions},
     poseidon::hasher::{spec::OptimizedPoseidonSpec, PoseidonHasher},
     safe_types::{FixLenBytes, VarLenBytes, VarLenBytesVec},
     utils::{BigPrimeField, ScalarField},
     AssignedValue, Context,
+    QuantumCell::Constant,
 };
 
 use itertools::Itertools;
+use std::iter;
 
+/// Module for Anemoi hasher
+pub mod anemoi;
 /// Module for Poseidon hasher
 pub mod hasher;
+/// Module for the Poseidon2 permutation
+pub mod poseidon2;
+/// Module for Poseidon-based Fiat-Shamir transcripts
+pub mod transcript;
+
+#[cfg(test)]
+mod tests;
 
 /// Chip for Poseidon hash.
 pub struct PoseidonChip<'a, F: ScalarField, const T: usize, const RATE: usize> {
@@ -28,6 +39,127 @@ impl<'a, F: ScalarField, const T: usize, const RATE: usize> PoseidonChip<'a, F,
         hasher.initialize_consts(ctx, range_chip.gate());
         Self { range_chip, hasher }
     }
+
+    /// Returns the [PoseidonHasher] of this chip, whose constants are already loaded.
+    pub fn hasher(&self) -> &PoseidonHasher<F, T, RATE> {
+        &self.hasher
+    }
+
+    /// Returns the [RangeChip] of this chip.
+    pub fn range_chip(&self) -> &'a RangeChip<F> {
+        self.range_chip
+    }
+
+    /// Constrains `sorted` to be strictly increasing and returns the Poseidon hash of it as a commitment.
+    ///
+    /// Each element of `sorted` is range checked to `num_bits` bits.
+    /// * sorted: list of [AssignedValue]. Length should be determined at compile time.
+    /// * num_bits: number of bits of each element. Must be less than `F::NUM_BITS - 1`.
+    pub fn commit_sorted_list(
+        &self,
+        ctx: &mut Context<F>,
+        sorted: &[AssignedValue<F>],
+        num_bits: usize,
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        for a in sorted {
+            self.range_chip.range_check(ctx, *a, num_bits);
+        }
+        for (a, b) in sorted.iter().tuple_windows() {
+            self.range_chip.check_less_than(ctx, *a, *b, num_bits);
+        }
+        self.hasher.hash_fix_len_array(ctx, self.range_chip.gate(), sorted)
+    }
+
+    /// Returns 1 if `elem` is an element of `sorted`, and 0 otherwise.
+    ///
+    /// Constrains the Poseidon hash of `sorted` to equal `commitment`.
+    /// Assumes `commitment` was produced by [`Self::commit_sorted_list`], so the elements of `sorted` are distinct.
+    /// * elem: [AssignedValue] to look up
+    /// * sorted: list of [AssignedValue] that was committed to
+    /// * commitment: commitment to `sorted`
+    pub fn inclusion_proof(
+        &self,
+        ctx: &mut Context<F>,
+        elem: AssignedValue<F>,
+        sorted: &[AssignedValue<F>],
+        commitment: AssignedValue<F>,
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        let gate = self.range_chip.gate();
+        let hash = self.hasher.hash_fix_len_array(ctx, gate, sorted);
+        ctx.constrain_equal(&hash, &commitment);
+        // elements are distinct, so at most one indicator is 1
+        let indicators = sorted.iter().map(|a| gate.is_equal(ctx, elem, *a)).collect_vec();
+        gate.sum(ctx, indicators)
+    }
+
+    /// Returns the Poseidon MAC of `message` under `key`, computed as the hash of `key || tag || message`.
+    ///
+    /// `tag` separates MACs computed with the same key for different purposes.
+    /// The lengths of `key` and `message` should be determined at compile time.
+    /// * key: secret key
+    /// * message: message to authenticate
+    /// * tag: domain separation tag
+    pub fn poseidon_mac(
+        &self,
+        ctx: &mut Context<F>,
+        key: &[AssignedValue<F>],
+        message: &[AssignedValue<F>],
+        tag: u64,
+    ) -> AssignedValue<F> {
+        let tag = ctx.load_constant(F::from(tag));
+        let inputs = key.iter().copied().chain([tag]).chain(message.iter().copied()).collect_vec();
+        self.hasher.hash_fix_len_array(ctx, self.range_chip.gate(), &inputs)
+    }
+
+    /// Returns 1 if `claimed_mac` is the Poseidon MAC of `message` under `key` and `tag`, and 0 otherwise.
+    ///
+    /// See [`Self::poseidon_mac`].
+    pub fn verify_poseidon_mac(
+        &self,
+        ctx: &mut Context<F>,
+        key: &[AssignedValue<F>],
+        message: &[AssignedValue<F>],
+        tag: u64,
+        claimed_mac: AssignedValue<F>,
+    ) -> AssignedValue<F> {
+        let mac = self.poseidon_mac(ctx, key, message, tag);
+        self.range_chip.gate().is_equal(ctx, mac, claimed_mac)
+    }
+
+    /// Returns the Poseidon hash of the byte string `bytes` as a single field element.
+    ///
+    /// Each byte is range checked to 8 bits. The bytes are packed little-endian into chunks of `F::CAPACITY / 8`
+    /// bytes, one field element per chunk, and the hash is taken over `len(bytes) || chunks`, so byte strings
+    /// differing only in trailing zeros hash differently.
+    /// The length of `bytes` should be determined at compile time.
+    pub fn hash_to_field(
+        &self,
+        ctx: &mut Context<F>,
+        bytes: &[AssignedValue<F>],
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        let gate = self.range_chip.gate();
+        for byte in bytes {
+            self.range_chip.range_check(ctx, *byte, 8);
+        }
+        let bytes_per_elem = F::CAPACITY as usize / 8;
+        let len = ctx.load_constant(F::from(bytes.len() as u64));
+        let byte_base = (0..bytes_per_elem).map(|i| Constant(gate.pow_of_two()[i * 8]));
+        let inputs = iter::once(len)
+            .chain(bytes.chunks(bytes_per_elem).map(|chunk| {
+                gate.inner_product(ctx, chunk.iter().copied(), byte_base.clone().take(chunk.len()))
+            }))
+            .collect_vec();
+        self.hasher.hash_fix_len_array(ctx, gate, &inputs)
+    }
 }
 
 /// Trait for Poseidon instructions
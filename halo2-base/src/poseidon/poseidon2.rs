@@ -0,0 +1,403 @@
+use std::collections::VecDeque;
+
+use crate::{
+    ff::PrimeField,
+    gates::{GateInstructions, RangeChip, RangeInstructions},
+    poseidon::PoseidonInstructions,
+    safe_types::{FixLenBytes, VarLenBytes, VarLenBytesVec},
+    utils::{biguint_to_fe, modulus, BigPrimeField, ScalarField},
+    AssignedValue, Context,
+    QuantumCell::Constant,
+};
+
+use getset::Getters;
+use itertools::Itertools;
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+/// The 4x4 MDS matrix `M4` from which the external matrix is built when `T` is a multiple of 4.
+const M4: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+/// Parameters of the Poseidon2 permutation on a state of `T` field elements, with the S-box `x^5`.
+///
+/// Following https://eprint.iacr.org/2023/323.pdf, the external rounds use the matrix `circ(2, 1, ..., 1)` for
+/// `T = 2, 3` and `circ(2 * M4, M4, ..., M4)` when `T` is a multiple of 4, and the partial rounds use
+/// `J + diag(internal_diag)`, where `J` is the all-ones matrix. The round constants are sampled with the Grain LFSR
+/// of the original Poseidon, so they match the reference implementation for the same parameters.
+#[derive(Clone, Debug, Getters)]
+pub struct Poseidon2Spec<F: ScalarField, const T: usize> {
+    /// Number of full rounds, half of them before and half after the partial rounds.
+    #[getset(get = "pub")]
+    r_f: usize,
+    /// Number of partial rounds.
+    #[getset(get = "pub")]
+    r_p: usize,
+    /// Round constants added to the whole state in each full round.
+    #[getset(get = "pub")]
+    external_constants: Vec<[F; T]>,
+    /// Round constants added to the first element in each partial round.
+    #[getset(get = "pub")]
+    internal_constants: Vec<F>,
+    /// Diagonal of the internal matrix minus the identity.
+    #[getset(get = "pub")]
+    internal_diag: [F; T],
+}
+
+impl<F: BigPrimeField, const T: usize> Poseidon2Spec<F, T> {
+    /// Creates a new [Poseidon2Spec] with `r_f` full rounds, `r_p` partial rounds and internal matrix
+    /// `J + diag(internal_diag)`.
+    ///
+    /// Assumes `x^5` is a permutation of `F` and `internal_diag` gives an MDS internal matrix.
+    pub fn new(r_f: usize, r_p: usize, internal_diag: [F; T]) -> Self {
+        assert!(T == 2 || T == 3 || T % 4 == 0, "T must be 2, 3 or a multiple of 4");
+        assert!(r_f % 2 == 0, "the number of full rounds must be even");
+        let mut grain = Grain::new(F::NUM_BITS as usize, T, r_f, r_p);
+        let full_round_constants =
+            |grain: &mut Grain| -> [F; T] { core::array::from_fn(|_| grain.next_field_element()) };
+        let mut external_constants =
+            (0..r_f / 2).map(|_| full_round_constants(&mut grain)).collect_vec();
+        let internal_constants = (0..r_p).map(|_| grain.next_field_element()).collect();
+        external_constants.extend((0..r_f / 2).map(|_| full_round_constants(&mut grain)));
+        Self { r_f, r_p, external_constants, internal_constants, internal_diag }
+    }
+
+    /// Reference parameters for `T = 3` over a 254-bit field such as the BN254 scalar field:
+    /// 8 full rounds, 56 partial rounds and internal matrix `J + diag(1, 1, 2)`.
+    pub fn t3_bn254() -> Self {
+        assert_eq!(T, 3);
+        let mut diag = [F::ONE; T];
+        diag[2] = F::from(2);
+        Self::new(8, 56, diag)
+    }
+}
+
+impl<F: ScalarField, const T: usize> Poseidon2Spec<F, T> {
+    /// Returns the external matrix, only used when `T` is a multiple of 4.
+    fn external_matrix(&self) -> [[F; T]; T] {
+        core::array::from_fn(|i| {
+            core::array::from_fn(|j| {
+                let m = F::from(M4[i % 4][j % 4]);
+                if i / 4 == j / 4 {
+                    m + m
+                } else {
+                    m
+                }
+            })
+        })
+    }
+}
+
+/// Grain LFSR used to sample the round constants, as specified in Appendix F of https://eprint.iacr.org/2019/458.pdf.
+struct Grain {
+    state: VecDeque<bool>,
+}
+
+impl Grain {
+    fn new(num_bits: usize, t: usize, r_f: usize, r_p: usize) -> Self {
+        let mut state = VecDeque::with_capacity(80);
+        let mut push = |value: usize, len: usize| {
+            state.extend((0..len).rev().map(|i| (value >> i) & 1 == 1));
+        };
+        // prime field, `x^alpha` S-box, parameters, and 30 set bits
+        push(1, 2);
+        push(0, 4);
+        push(num_bits, 12);
+        push(t, 12);
+        push(r_f, 10);
+        push(r_p, 10);
+        push((1 << 30) - 1, 30);
+        let mut grain = Self { state };
+        for _ in 0..160 {
+            grain.update();
+        }
+        grain
+    }
+
+    fn update(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.pop_front();
+        self.state.push_back(bit);
+        bit
+    }
+
+    /// Returns the second bit of the next pair whose first bit is set.
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.update();
+            let bit = self.update();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// Samples big-endian integers of `F::NUM_BITS` bits until one is less than the modulus.
+    fn next_field_element<F: BigPrimeField>(&mut self) -> F {
+        let modulus = modulus::<F>();
+        loop {
+            let value = (0..F::NUM_BITS)
+                .fold(BigUint::zero(), |acc, _| (acc << 1usize) + u32::from(self.next_bit()));
+            if value < modulus {
+                return biguint_to_fe(&value);
+            }
+        }
+    }
+}
+
+/// Chip for Poseidon2 hashing, with the same sponge as [PoseidonChip](super::PoseidonChip).
+///
+/// The state starts as `(2^64, 0, ..., 0)`, each chunk of `RATE` inputs is added to `state[1..]` and padded with a
+/// single 1, and the hash is `state[1]`, so both chips can be used interchangeably through [PoseidonInstructions].
+#[derive(Clone, Debug)]
+pub struct Poseidon2Chip<'a, F: ScalarField, const T: usize, const RATE: usize> {
+    range_chip: &'a RangeChip<F>,
+    spec: Poseidon2Spec<F, T>,
+}
+
+impl<'a, F: ScalarField, const T: usize, const RATE: usize> Poseidon2Chip<'a, F, T, RATE> {
+    /// Create a new Poseidon2Chip.
+    pub fn new(spec: Poseidon2Spec<F, T>, range_chip: &'a RangeChip<F>) -> Self {
+        assert!(RATE < T, "RATE must be less than T");
+        Self { range_chip, spec }
+    }
+
+    /// Returns the [Poseidon2Spec] of this chip.
+    pub fn spec(&self) -> &Poseidon2Spec<F, T> {
+        &self.spec
+    }
+
+    /// Constrains and returns the Poseidon2 permutation of `state`.
+    pub fn permutation(
+        &self,
+        ctx: &mut Context<F>,
+        mut state: [AssignedValue<F>; T],
+    ) -> [AssignedValue<F>; T] {
+        let (first, last) = self.spec.external_constants.split_at(self.spec.r_f / 2);
+        self.external_linear_layer(ctx, &mut state);
+        for constants in first {
+            self.full_round(ctx, &mut state, constants);
+        }
+        let gate = self.range_chip.gate();
+        for constant in &self.spec.internal_constants {
+            state[0] = sbox_with_constant(ctx, gate, state[0], constant);
+            let sum = gate.sum(ctx, state);
+            for (x, d) in state.iter_mut().zip(self.spec.internal_diag.iter()) {
+                *x = gate.mul_add(ctx, *x, Constant(*d), sum);
+            }
+        }
+        for constants in last {
+            self.full_round(ctx, &mut state, constants);
+        }
+        state
+    }
+
+    /// Constrains and returns hash of a witness array.
+    ///
+    /// * inputs: An array of [AssignedValue].
+    /// Return hash of `inputs`.
+    pub fn hash_fix_len_array(
+        &self,
+        ctx: &mut Context<F>,
+        inputs: &[AssignedValue<F>],
+    ) -> AssignedValue<F> {
+        let mut state = self.init_state(ctx);
+        for chunk in inputs.chunks(RATE) {
+            self.absorb(ctx, &mut state, chunk);
+        }
+        if inputs.len() % RATE == 0 {
+            self.absorb(ctx, &mut state, &[]);
+        }
+        state[1]
+    }
+
+    /// Constrains and returns hash of a witness array with a variable length.
+    ///
+    /// Assumes `len` is within [usize] and `len <= inputs.len()`.
+    /// * inputs: An right-padded array of [AssignedValue]. Constraints on paddings are not required.
+    /// * len: Length of `inputs`.
+    /// Return hash of `inputs`.
+    pub fn hash_var_len_array(
+        &self,
+        ctx: &mut Context<F>,
+        inputs: &[AssignedValue<F>],
+        len: AssignedValue<F>,
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        let max_len = inputs.len();
+        if max_len == 0 {
+            return self.hash_fix_len_array(ctx, &[]);
+        }
+        let range = self.range_chip;
+        let gate = range.gate();
+
+        // len <= max_len --> num_of_bits(len) <= num_of_bits(max_len)
+        let num_bits = (usize::BITS - max_len.leading_zeros()) as usize;
+        // num_perm = len // RATE + 1, len_last_chunk = len % RATE
+        let (num_perm, len_last_chunk) = range.div_mod(ctx, len, BigUint::from(RATE), num_bits);
+        let num_perm = gate.inc(ctx, num_perm);
+
+        let mut state = self.init_state(ctx);
+        let mut result = state;
+        let num_chunks = (max_len + RATE - 1) / RATE;
+        // if `max_len` is a multiple of `RATE`, a final permutation absorbs only the padding
+        let extra_chunk = (max_len % RATE == 0).then_some(&[][..]);
+        for (i, chunk) in inputs.chunks(RATE).chain(extra_chunk).enumerate() {
+            let is_last_perm = gate.is_equal(ctx, num_perm, Constant(F::from((i + 1) as u64)));
+            let len_chunk = if i < num_chunks {
+                gate.select(ctx, len_last_chunk, Constant(F::from(RATE as u64)), is_last_perm)
+            } else {
+                ctx.load_zero()
+            };
+            self.absorb_var_len(ctx, &mut state, chunk, len_chunk);
+            for (r, s) in result.iter_mut().zip(state.iter()) {
+                *r = gate.select(ctx, *s, *r, is_last_perm);
+            }
+        }
+        result[1]
+    }
+
+    fn init_state(&self, ctx: &mut Context<F>) -> [AssignedValue<F>; T] {
+        core::array::from_fn(|i| {
+            if i == 0 {
+                ctx.load_constant(F::from_u128(1u128 << 64))
+            } else {
+                ctx.load_zero()
+            }
+        })
+    }
+
+    /// Adds `chunk` to `state[1..]`, pads with a single 1 if there is room, and permutes.
+    fn absorb(
+        &self,
+        ctx: &mut Context<F>,
+        state: &mut [AssignedValue<F>; T],
+        chunk: &[AssignedValue<F>],
+    ) {
+        let gate = self.range_chip.gate();
+        for (x, input) in state.iter_mut().skip(1).zip(chunk) {
+            *x = gate.add(ctx, *x, *input);
+        }
+        if chunk.len() + 1 < T {
+            state[chunk.len() + 1] = gate.add(ctx, state[chunk.len() + 1], Constant(F::ONE));
+        }
+        *state = self.permutation(ctx, *state);
+    }
+
+    /// Same as [Self::absorb] for the first `len` elements of `chunk`, where `len <= RATE` is a witness.
+    fn absorb_var_len(
+        &self,
+        ctx: &mut Context<F>,
+        state: &mut [AssignedValue<F>; T],
+        chunk: &[AssignedValue<F>],
+        len: AssignedValue<F>,
+    ) {
+        let gate = self.range_chip.gate();
+        // len_indicator[i] = (i == len - 1), mask[i] = (i < len)
+        let idx = gate.dec(ctx, len);
+        let len_indicator = gate.idx_to_indicator(ctx, idx, RATE);
+        let mut mask =
+            gate.partial_sums(ctx, len_indicator.clone().into_iter().rev()).collect_vec();
+        mask.reverse();
+        for i in 0..RATE {
+            if let Some(input) = chunk.get(i) {
+                let input = gate.mul(ctx, *input, mask[i]);
+                state[i + 1] = gate.add(ctx, state[i + 1], input);
+            }
+            if i + 2 < T {
+                state[i + 2] = gate.add(ctx, state[i + 2], len_indicator[i]);
+            }
+        }
+        // if len == 0, the padding goes into state[1]
+        let empty = gate.not(ctx, mask[0]);
+        state[1] = gate.add(ctx, state[1], empty);
+        *state = self.permutation(ctx, *state);
+    }
+
+    fn full_round(
+        &self,
+        ctx: &mut Context<F>,
+        state: &mut [AssignedValue<F>; T],
+        constants: &[F; T],
+    ) {
+        let gate = self.range_chip.gate();
+        for (x, constant) in state.iter_mut().zip(constants.iter()) {
+            *x = sbox_with_constant(ctx, gate, *x, constant);
+        }
+        self.external_linear_layer(ctx, state);
+    }
+
+    fn external_linear_layer(&self, ctx: &mut Context<F>, state: &mut [AssignedValue<F>; T]) {
+        let gate = self.range_chip.gate();
+        if T % 4 == 0 {
+            let matrix = self.spec.external_matrix();
+            let out =
+                matrix.map(|row| gate.inner_product(ctx, state.iter().copied(), row.map(Constant)));
+            *state = out;
+        } else {
+            // circ(2, 1, ..., 1) adds the sum of the state to every element
+            let sum = gate.sum(ctx, *state);
+            for x in state.iter_mut() {
+                *x = gate.add(ctx, *x, sum);
+            }
+        }
+    }
+}
+
+/// Constrains and returns `(x + constant)^5`.
+fn sbox_with_constant<F: ScalarField>(
+    ctx: &mut Context<F>,
+    gate: &impl GateInstructions<F>,
+    x: AssignedValue<F>,
+    constant: &F,
+) -> AssignedValue<F> {
+    let x = gate.add(ctx, x, Constant(*constant));
+    let x2 = gate.mul(ctx, x, x);
+    let x4 = gate.mul(ctx, x2, x2);
+    gate.mul(ctx, x, x4)
+}
+
+impl<'a, F: ScalarField, const T: usize, const RATE: usize> PoseidonInstructions<F>
+    for Poseidon2Chip<'a, F, T, RATE>
+{
+    fn hash_var_len_bytes<const MAX_LEN: usize>(
+        &self,
+        ctx: &mut Context<F>,
+        inputs: &VarLenBytes<F, MAX_LEN>,
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        let inputs_len = inputs.len();
+        self.hash_var_len_array(ctx, inputs.bytes().map(|sb| *sb.as_ref()).as_ref(), *inputs_len)
+    }
+
+    fn hash_var_len_bytes_vec(
+        &self,
+        ctx: &mut Context<F>,
+        inputs: &VarLenBytesVec<F>,
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        let inputs_len = inputs.len();
+        self.hash_var_len_array(
+            ctx,
+            &inputs.bytes().iter().map(|sb| *sb.as_ref()).collect_vec(),
+            *inputs_len,
+        )
+    }
+
+    fn hash_fix_len_bytes<const MAX_LEN: usize>(
+        &self,
+        ctx: &mut Context<F>,
+        inputs: &FixLenBytes<F, MAX_LEN>,
+    ) -> AssignedValue<F>
+    where
+        F: BigPrimeField,
+    {
+        self.hash_fix_len_array(ctx, inputs.bytes().map(|sb| *sb.as_ref()).as_ref())
+    }
+}
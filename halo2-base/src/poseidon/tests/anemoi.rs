@@ -0,0 +1,136 @@
+use std::array;
+
+#[cfg(feature = "halo2-axiom")]
+use crate::halo2_proofs::halo2curves::bls12_381;
+use crate::{
+    ff::{Field, PrimeField},
+    halo2_proofs::halo2curves::bn256::Fr,
+    poseidon::anemoi::{AnemoiHasher, AnemoiSpec},
+    utils::{testing::base_test, BigPrimeField},
+};
+use test_case::test_case;
+
+fn native_linear_layer<const L: usize>(
+    spec: &AnemoiSpec<Fr, L>,
+    x: [Fr; L],
+    y: [Fr; L],
+) -> ([Fr; L], [Fr; L]) {
+    let mds = spec.mds();
+    let x: [Fr; L] = array::from_fn(|i| (0..L).map(|j| mds[i][j] * x[j]).sum());
+    let y: [Fr; L] = array::from_fn(|i| (0..L).map(|j| mds[i][j] * y[(j + 1) % L]).sum());
+    let y: [Fr; L] = array::from_fn(|i| y[i] + x[i]);
+    let x = array::from_fn(|i| x[i] + y[i]);
+    (x, y)
+}
+
+fn native_permutation<const L: usize>(
+    spec: &AnemoiSpec<Fr, L>,
+    mut x: [Fr; L],
+    mut y: [Fr; L],
+) -> ([Fr; L], [Fr; L]) {
+    for (c, d) in spec.round_constants() {
+        x = array::from_fn(|i| x[i] + c[i]);
+        y = array::from_fn(|i| y[i] + d[i]);
+        (x, y) = native_linear_layer(spec, x, y);
+        for i in 0..L {
+            x[i] -= spec.beta() * y[i].square();
+            y[i] -= x[i].pow_vartime(spec.alpha_inv());
+            x[i] += spec.beta() * y[i].square() + spec.delta();
+        }
+    }
+    native_linear_layer(spec, x, y)
+}
+
+fn native_hash<const L: usize>(spec: &AnemoiSpec<Fr, L>, inputs: &[Fr]) -> Fr {
+    let rate = 2 * L - 1;
+    let mut state = vec![Fr::zero(); 2 * L];
+    let mut inputs = inputs.to_vec();
+    if inputs.is_empty() || inputs.len() % rate != 0 {
+        inputs.push(Fr::one());
+        inputs.resize((inputs.len() + rate - 1) / rate * rate, Fr::zero());
+    } else {
+        state[2 * L - 1] = Fr::one();
+    }
+    for chunk in inputs.chunks(rate) {
+        for (s, input) in state.iter_mut().zip(chunk) {
+            *s += input;
+        }
+        let (x, y) = native_permutation(
+            spec,
+            array::from_fn(|i| state[i]),
+            array::from_fn(|i| state[L + i]),
+        );
+        state = x.into_iter().chain(y).collect();
+    }
+    state[0]
+}
+
+fn test_compress<const L: usize>(x: [u64; L], y: [u64; L]) {
+    let spec = AnemoiSpec::<Fr, L>::bn254();
+    let (x, y) = (x.map(Fr::from), y.map(Fr::from));
+    let (u, v) = native_permutation(&spec, x, y);
+    let expected: [Fr; L] = array::from_fn(|i| x[i] + y[i] + u[i] + v[i]);
+    let hasher = AnemoiHasher::new(spec);
+    let out = base_test().k(12).run_gate(|ctx, gate| {
+        let x = ctx.assign_witnesses(x).try_into().unwrap();
+        let y = ctx.assign_witnesses(y).try_into().unwrap();
+        hasher.compress(ctx, gate, x, y).map(|a| *a.value())
+    });
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn test_anemoi_compress_l1() {
+    test_compress::<1>([1], [2]);
+}
+
+#[test]
+fn test_anemoi_compress_l2() {
+    test_compress::<2>([1, 2], [3, 4]);
+}
+
+#[test_case(0; "empty input")]
+#[test_case(3; "multiple of rate")]
+#[test_case(5; "padded input")]
+fn test_anemoi_hash_fix_len_array(len: u64) {
+    let spec = AnemoiSpec::<Fr, 2>::bn254();
+    let inputs = (0..len).map(Fr::from).collect::<Vec<_>>();
+    let expected = native_hash(&spec, &inputs);
+    let hasher = AnemoiHasher::new(spec);
+    let out = base_test().k(12).run_gate(|ctx, gate| {
+        let inputs = ctx.assign_witnesses(inputs);
+        *hasher.hash_fix_len_array(ctx, gate, &inputs).value()
+    });
+    assert_eq!(out, expected);
+}
+
+// `alpha = 5` for both fields since 3 divides `p - 1`, so the first round constants are
+// `c_{0,0} = g + 2^5` and `d_{0,0} = g + 2^5 + g^{-1}`, which pins the generator of each field
+fn assert_pinned_parameters<F: BigPrimeField>(spec: &AnemoiSpec<F, 2>, g: u64) {
+    let g = F::from(g);
+    assert_eq!(*spec.alpha(), 5);
+    assert_eq!(*spec.beta(), g);
+    assert_eq!(*spec.delta() * g, F::ONE);
+    let (c, d) = &spec.round_constants()[0];
+    assert_eq!(c[0], g + F::from(32));
+    assert_eq!(d[0], g + F::from(32) + spec.delta());
+    // `alpha_inv` inverts `x -> x^5`
+    let x = F::from(123456789);
+    assert_eq!(x.pow_vartime([5]).pow_vartime(spec.alpha_inv()), x);
+}
+
+#[test]
+fn test_anemoi_bn254_parameters() {
+    let spec = AnemoiSpec::<Fr, 2>::bn254();
+    assert_ne!(Fr::MULTIPLICATIVE_GENERATOR, Fr::from(5));
+    assert_pinned_parameters(&spec, 5);
+    assert_eq!(spec.round_constants().len(), 14);
+}
+
+#[cfg(feature = "halo2-axiom")]
+#[test]
+fn test_anemoi_bls12_381_parameters() {
+    let spec = AnemoiSpec::<bls12_381::Fr, 2>::bls12_381();
+    assert_pinned_parameters(&spec, 7);
+    assert_eq!(spec.round_constants().len(), 14);
+}
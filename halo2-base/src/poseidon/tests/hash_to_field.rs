@@ -0,0 +1,51 @@
+use crate::{
+    ff::PrimeField,
+    halo2_proofs::halo2curves::bn256::Fr,
+    poseidon::{hasher::spec::OptimizedPoseidonSpec, PoseidonChip},
+    utils::{testing::base_test, ScalarField},
+};
+use pse_poseidon::Poseidon;
+use test_case::test_case;
+
+// reference implementation of `hash_to_field`, computed natively
+fn native_hash_to_field(bytes: &[u8]) -> Fr {
+    let bytes_per_elem = Fr::CAPACITY as usize / 8;
+    let mut inputs = vec![Fr::from(bytes.len() as u64)];
+    inputs.extend(bytes.chunks(bytes_per_elem).map(Fr::from_bytes_le));
+    let mut native_sponge = Poseidon::<Fr, 3, 2>::new(8, 57);
+    native_sponge.update(&inputs);
+    native_sponge.squeeze()
+}
+
+fn hash_to_field(bytes: &[u8]) -> Fr {
+    base_test().k(12).lookup_bits(8).run(|ctx, range| {
+        let spec = OptimizedPoseidonSpec::<Fr, 3, 2>::new::<8, 57, 0>();
+        let chip = PoseidonChip::new(ctx, spec, range);
+        let bytes = ctx.assign_witnesses(bytes.iter().map(|b| Fr::from(*b as u64)));
+        *chip.hash_to_field(ctx, &bytes).value()
+    })
+}
+
+#[test_case(0; "empty")]
+#[test_case(31; "one full chunk")]
+#[test_case(32; "chunk boundary")]
+#[test_case(70; "several chunks")]
+fn test_hash_to_field(len: usize) {
+    let bytes = (0..len).map(|i| (i * 37 + 11) as u8).collect::<Vec<_>>();
+    assert_eq!(hash_to_field(&bytes), native_hash_to_field(&bytes));
+}
+
+#[test]
+fn test_hash_to_field_trailing_zero() {
+    assert_ne!(hash_to_field(&[1, 2, 3]), hash_to_field(&[1, 2, 3, 0]));
+}
+
+#[test]
+fn test_hash_to_field_not_byte() {
+    base_test().k(12).lookup_bits(8).expect_satisfied(false).run(|ctx, range| {
+        let spec = OptimizedPoseidonSpec::<Fr, 3, 2>::new::<8, 57, 0>();
+        let chip = PoseidonChip::new(ctx, spec, range);
+        let bytes = ctx.assign_witnesses([1u64, 256, 3].map(Fr::from));
+        chip.hash_to_field(ctx, &bytes);
+    })
+}
@@ -0,0 +1,42 @@
+use crate::{
+    halo2_proofs::halo2curves::bn256::Fr,
+    poseidon::{hasher::spec::OptimizedPoseidonSpec, PoseidonChip},
+    utils::{testing::base_test, ScalarField},
+};
+use test_case::test_case;
+
+const KEY: [u64; 2] = [0xdead, 0xbeef];
+
+fn mac(key: [u64; 2], message: &[u64], tag: u64) -> Fr {
+    base_test().k(12).run(|ctx, range| {
+        let spec = OptimizedPoseidonSpec::<Fr, 3, 2>::new::<8, 57, 0>();
+        let chip = PoseidonChip::new(ctx, spec, range);
+        let key = ctx.assign_witnesses(key.map(Fr::from));
+        let message = ctx.assign_witnesses(message.iter().map(|m| Fr::from(*m)));
+        *chip.poseidon_mac(ctx, &key, &message, tag).value()
+    })
+}
+
+#[test]
+fn test_poseidon_mac_changes() {
+    let expected = mac(KEY, &[1, 2, 3], 0);
+    // flipping one bit of the message, the key, or changing the tag changes the MAC
+    assert_ne!(expected, mac(KEY, &[1, 2, 2], 0));
+    assert_ne!(expected, mac([0xdeac, 0xbeef], &[1, 2, 3], 0));
+    assert_ne!(expected, mac(KEY, &[1, 2, 3], 1));
+    assert_eq!(expected, mac(KEY, &[1, 2, 3], 0));
+}
+
+#[test_case(&[1, 2, 3] => 1; "valid mac")]
+#[test_case(&[1, 2, 7] => 0; "tampered message")]
+fn test_verify_poseidon_mac(message: &[u64]) -> u64 {
+    let claimed = mac(KEY, &[1, 2, 3], 42);
+    base_test().k(12).run(|ctx, range| {
+        let spec = OptimizedPoseidonSpec::<Fr, 3, 2>::new::<8, 57, 0>();
+        let chip = PoseidonChip::new(ctx, spec, range);
+        let key = ctx.assign_witnesses(KEY.map(Fr::from));
+        let message = ctx.assign_witnesses(message.iter().map(|m| Fr::from(*m)));
+        let claimed = ctx.load_witness(claimed);
+        chip.verify_poseidon_mac(ctx, &key, &message, 42, claimed).value().get_lower_64()
+    })
+}
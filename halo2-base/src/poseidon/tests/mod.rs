@@ -0,0 +1,6 @@
+mod anemoi;
+mod hash_to_field;
+mod mac;
+mod poseidon2;
+mod sorted_list;
+mod transcript;
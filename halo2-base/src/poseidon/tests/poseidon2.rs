@@ -0,0 +1,166 @@
+use crate::{
+    ff::{Field, PrimeField},
+    halo2_proofs::halo2curves::bn256::Fr,
+    poseidon::{
+        hasher::spec::OptimizedPoseidonSpec,
+        poseidon2::{Poseidon2Chip, Poseidon2Spec},
+        PoseidonChip, PoseidonInstructions,
+    },
+    safe_types::SafeTypeChip,
+    utils::{biguint_to_fe, testing::base_test},
+    AssignedValue, Context,
+};
+use num_bigint::BigUint;
+use test_case::test_case;
+
+fn fe(hex: &str) -> Fr {
+    biguint_to_fe(&BigUint::parse_bytes(hex.as_bytes(), 16).unwrap())
+}
+
+fn sbox(x: Fr) -> Fr {
+    x * x.square().square()
+}
+
+fn native_external_layer(state: &mut [Fr; 3]) {
+    let sum = state.iter().sum::<Fr>();
+    state.iter_mut().for_each(|x| *x += sum);
+}
+
+fn native_permutation(spec: &Poseidon2Spec<Fr, 3>, mut state: [Fr; 3]) -> [Fr; 3] {
+    let (first, last) = spec.external_constants().split_at(spec.r_f() / 2);
+    native_external_layer(&mut state);
+    for constants in first {
+        state = core::array::from_fn(|i| sbox(state[i] + constants[i]));
+        native_external_layer(&mut state);
+    }
+    for constant in spec.internal_constants() {
+        state[0] = sbox(state[0] + constant);
+        let sum = state.iter().sum::<Fr>();
+        state = core::array::from_fn(|i| state[i] * spec.internal_diag()[i] + sum);
+    }
+    for constants in last {
+        state = core::array::from_fn(|i| sbox(state[i] + constants[i]));
+        native_external_layer(&mut state);
+    }
+    state
+}
+
+// same sponge as `PoseidonHasher`, with the Poseidon2 permutation
+fn native_hash(spec: &Poseidon2Spec<Fr, 3>, inputs: &[Fr]) -> Fr {
+    let mut state = [Fr::from_u128(1u128 << 64), Fr::ZERO, Fr::ZERO];
+    let absorb = |state: &mut [Fr; 3], chunk: &[Fr]| {
+        for (s, input) in state[1..].iter_mut().zip(chunk) {
+            *s += input;
+        }
+        if chunk.len() + 1 < 3 {
+            state[chunk.len() + 1] += Fr::ONE;
+        }
+        *state = native_permutation(spec, *state);
+    };
+    for chunk in inputs.chunks(2) {
+        absorb(&mut state, chunk);
+    }
+    if inputs.len() % 2 == 0 {
+        absorb(&mut state, &[]);
+    }
+    state[1]
+}
+
+#[test]
+fn test_poseidon2_round_constants() {
+    let spec = Poseidon2Spec::<Fr, 3>::t3_bn254();
+    assert_eq!(spec.external_constants().len(), 8);
+    assert_eq!(spec.internal_constants().len(), 56);
+    assert_eq!(
+        spec.external_constants()[0][0],
+        fe("1d066a255517b7fd8bddd3a93f7804ef7f8fcde48bb4c37a59a09a1a97052816")
+    );
+}
+
+// test vector of the reference implementation: https://github.com/HorizenLabs/poseidon2
+#[test]
+fn test_poseidon2_permutation_vector() {
+    let expected = [
+        fe("0bb61d24daca55eebcb1929a82650f328134334da98ea4f847f760054f4a3033"),
+        fe("303b6f7c86d043bfcbcc80214f26a30277a15d3f74ca654992defe7ff8d03570"),
+        fe("1ed25194542b12eef8617361c3ba7c52e660b145994427cc86296242cf766ec8"),
+    ];
+    let spec = Poseidon2Spec::<Fr, 3>::t3_bn254();
+    assert_eq!(native_permutation(&spec, [0u64, 1, 2].map(Fr::from)), expected);
+    let out = base_test().k(12).lookup_bits(8).run(|ctx, range| {
+        let chip = Poseidon2Chip::<Fr, 3, 2>::new(spec, range);
+        let state = ctx.assign_witnesses([0u64, 1, 2].map(Fr::from)).try_into().unwrap();
+        chip.permutation(ctx, state).map(|x| *x.value())
+    });
+    assert_eq!(out, expected);
+}
+
+#[test_case(0; "empty input")]
+#[test_case(1; "padded input")]
+#[test_case(4; "multiple of rate")]
+#[test_case(7; "several chunks")]
+fn test_poseidon2_hash_fix_len_array(len: u64) {
+    let spec = Poseidon2Spec::<Fr, 3>::t3_bn254();
+    let inputs = (0..len).map(Fr::from).collect::<Vec<_>>();
+    let expected = native_hash(&spec, &inputs);
+    let out = base_test().k(12).lookup_bits(8).run(|ctx, range| {
+        let chip = Poseidon2Chip::<Fr, 3, 2>::new(spec, range);
+        let inputs = ctx.assign_witnesses(inputs);
+        *chip.hash_fix_len_array(ctx, &inputs).value()
+    });
+    assert_eq!(out, expected);
+}
+
+#[test_case(5; "padded max len")]
+#[test_case(6; "max len multiple of rate")]
+fn test_poseidon2_hash_var_len_array(max_len: usize) {
+    let spec = Poseidon2Spec::<Fr, 3>::t3_bn254();
+    let inputs = (0..max_len as u64).map(|x| Fr::from(x + 1)).collect::<Vec<_>>();
+    for len in 0..=max_len {
+        let expected = native_hash(&spec, &inputs[..len]);
+        let out = base_test().k(13).lookup_bits(8).run(|ctx, range| {
+            let chip = Poseidon2Chip::<Fr, 3, 2>::new(spec.clone(), range);
+            let inputs = ctx.assign_witnesses(inputs.clone());
+            let len = ctx.load_witness(Fr::from(len as u64));
+            *chip.hash_var_len_array(ctx, &inputs, len).value()
+        });
+        assert_eq!(out, expected, "len = {len}");
+    }
+}
+
+// callers only depend on `PoseidonInstructions`, so either chip can be plugged in
+fn hash_bytes<const MAX_LEN: usize>(
+    ctx: &mut Context<Fr>,
+    chip: &impl PoseidonInstructions<Fr>,
+    safe: &SafeTypeChip<Fr>,
+    bytes: [AssignedValue<Fr>; MAX_LEN],
+    len: AssignedValue<Fr>,
+) -> (Fr, Fr) {
+    let fix_len = safe.raw_to_fix_len_bytes(ctx, bytes);
+    let var_len = safe.raw_to_var_len_bytes(ctx, bytes, len);
+    let fix_hash = *chip.hash_fix_len_bytes(ctx, &fix_len).value();
+    let var_hash = *chip.hash_var_len_bytes(ctx, &var_len).value();
+    (fix_hash, var_hash)
+}
+
+#[test]
+fn test_poseidon2_chip_matches_instructions() {
+    let bytes = [3u64, 1, 4, 1, 5].map(Fr::from);
+    let spec = Poseidon2Spec::<Fr, 3>::t3_bn254();
+    let expected = native_hash(&spec, &bytes);
+    let expected_prefix = native_hash(&spec, &bytes[..3]);
+    let (poseidon, poseidon2) = base_test().k(13).lookup_bits(8).run(|ctx, range| {
+        let safe = SafeTypeChip::new(range);
+        let bytes: [_; 5] = ctx.assign_witnesses(bytes).try_into().unwrap();
+        let len = ctx.load_witness(Fr::from(3));
+        let poseidon_spec = OptimizedPoseidonSpec::<Fr, 3, 2>::new::<8, 57, 0>();
+        let poseidon_chip = PoseidonChip::new(ctx, poseidon_spec, range);
+        let poseidon2_chip = Poseidon2Chip::<Fr, 3, 2>::new(spec, range);
+        (
+            hash_bytes(ctx, &poseidon_chip, &safe, bytes, len),
+            hash_bytes(ctx, &poseidon2_chip, &safe, bytes, len),
+        )
+    });
+    assert_eq!(poseidon2, (expected, expected_prefix));
+    assert_ne!(poseidon.0, poseidon2.0);
+}
@@ -0,0 +1,45 @@
+use crate::{
+    halo2_proofs::halo2curves::bn256::Fr,
+    poseidon::{hasher::spec::OptimizedPoseidonSpec, PoseidonChip},
+    utils::{testing::base_test, ScalarField},
+};
+use test_case::test_case;
+
+#[test_case(vec![1, 3, 5, 7], 5 => 1; "elem in list")]
+#[test_case(vec![1, 3, 5, 7], 1 => 1; "first elem in list")]
+#[test_case(vec![1, 3, 5, 7], 4 => 0; "elem not in list")]
+fn test_sorted_list_inclusion(sorted: Vec<u64>, elem: u64) -> u64 {
+    base_test().k(12).run(|ctx, range| {
+        let spec = OptimizedPoseidonSpec::<Fr, 3, 2>::new::<8, 57, 0>();
+        let chip = PoseidonChip::new(ctx, spec, range);
+        let sorted = ctx.assign_witnesses(sorted.into_iter().map(Fr::from));
+        let commitment = chip.commit_sorted_list(ctx, &sorted, 64);
+        let elem = ctx.load_witness(Fr::from(elem));
+        chip.inclusion_proof(ctx, elem, &sorted, commitment).value().get_lower_64()
+    })
+}
+
+#[test_case(vec![1, 5, 3, 7]; "unsorted")]
+#[test_case(vec![1, 3, 3, 7]; "duplicate")]
+fn neg_commit_sorted_list(sorted: Vec<u64>) {
+    base_test().k(12).expect_satisfied(false).run(|ctx, range| {
+        let spec = OptimizedPoseidonSpec::<Fr, 3, 2>::new::<8, 57, 0>();
+        let chip = PoseidonChip::new(ctx, spec, range);
+        let sorted = ctx.assign_witnesses(sorted.into_iter().map(Fr::from));
+        chip.commit_sorted_list(ctx, &sorted, 64);
+    })
+}
+
+#[test]
+fn neg_inclusion_proof_wrong_commitment() {
+    base_test().k(12).expect_satisfied(false).run(|ctx, range| {
+        let spec = OptimizedPoseidonSpec::<Fr, 3, 2>::new::<8, 57, 0>();
+        let chip = PoseidonChip::new(ctx, spec, range);
+        let sorted = ctx.assign_witnesses([1, 3, 5, 7].map(Fr::from));
+        chip.commit_sorted_list(ctx, &sorted, 64);
+        let other = ctx.assign_witnesses([1, 3, 5, 8].map(Fr::from));
+        let commitment = chip.commit_sorted_list(ctx, &other, 64);
+        let elem = ctx.load_witness(Fr::from(5));
+        chip.inclusion_proof(ctx, elem, &sorted, commitment);
+    })
+}
@@ -0,0 +1,68 @@
+use crate::{
+    halo2_proofs::halo2curves::bn256::Fr,
+    poseidon::{
+        hasher::{spec::OptimizedPoseidonSpec, PoseidonSponge},
+        transcript::PoseidonTranscript,
+    },
+    utils::{testing::base_test, ScalarField},
+};
+
+fn spec() -> OptimizedPoseidonSpec<Fr, 3, 2> {
+    OptimizedPoseidonSpec::new::<8, 57, 0>()
+}
+
+/// Returns the first two challenges of a transcript absorbing `msgs` under `labels`, one challenge after each message.
+fn challenges(domain: u64, labels: [&[u8]; 2], msgs: [u64; 2]) -> [Fr; 2] {
+    base_test().k(12).run_gate(|ctx, gate| {
+        let mut transcript = PoseidonTranscript::new(ctx, spec(), &[Fr::from(domain)]);
+        let mut challenge = |label, msg| {
+            let msg = ctx.load_witness(Fr::from(msg));
+            transcript.append_message(ctx, label, msg);
+            *transcript.get_challenge(ctx, gate).value()
+        };
+        [challenge(labels[0], msgs[0]), challenge(labels[1], msgs[1])]
+    })
+}
+
+#[test]
+fn test_transcript_matches_sponge() {
+    let label = b"a label that is longer than thirty one bytes";
+    let expected = base_test().k(12).run_gate(|ctx, gate| {
+        let mut sponge = PoseidonSponge::from_spec(ctx, spec());
+        let mut inputs = vec![Fr::from(7)];
+        inputs.extend(PoseidonTranscript::<Fr, 3, 2>::encode_label(label));
+        inputs.push(Fr::from(42));
+        let inputs = ctx.assign_witnesses(inputs);
+        sponge.update(&inputs);
+        *sponge.squeeze(ctx, gate).value()
+    });
+    let out = base_test().k(12).run_gate(|ctx, gate| {
+        let mut transcript = PoseidonTranscript::new(ctx, spec(), &[Fr::from(7)]);
+        let msg = ctx.load_witness(Fr::from(42));
+        transcript.append_message(ctx, label, msg);
+        *transcript.get_challenge(ctx, gate).value()
+    });
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn test_transcript_encode_label() {
+    let label = [1u8; 32];
+    let encoded = PoseidonTranscript::<Fr, 3, 2>::encode_label(&label);
+    assert_eq!(encoded, vec![Fr::from(32), Fr::from_bytes_le(&[1u8; 31]), Fr::from(1)]);
+}
+
+#[test]
+fn test_transcript_challenges() {
+    let base = challenges(0, [b"a", b"b"], [1, 2]);
+    assert_eq!(base, challenges(0, [b"a", b"b"], [1, 2]));
+    assert_ne!(base[0], base[1]);
+    // every input to the transcript changes the challenges
+    assert_ne!(base, challenges(1, [b"a", b"b"], [1, 2]));
+    assert_ne!(base, challenges(0, [b"c", b"b"], [1, 2]));
+    assert_ne!(base, challenges(0, [b"a", b"b"], [3, 2]));
+    // later messages do not change earlier challenges
+    let changed = challenges(0, [b"a", b"c"], [1, 2]);
+    assert_eq!(base[0], changed[0]);
+    assert_ne!(base[1], changed[1]);
+}
@@ -0,0 +1,61 @@
+use crate::{
+    gates::GateInstructions,
+    poseidon::hasher::{spec::OptimizedPoseidonSpec, PoseidonSponge},
+    utils::ScalarField,
+    AssignedValue, Context,
+};
+
+/// Number of label bytes packed into each field element, chosen so that any field of at least 256 bits fits them.
+const LABEL_BYTES_PER_ELEMENT: usize = 31;
+
+/// Public-coin transcript for Fiat-Shamir transformations, built on a [PoseidonSponge].
+///
+/// Every message is absorbed together with a label, and every challenge is squeezed from everything absorbed so far,
+/// so challenges bind the whole history of the protocol.
+pub struct PoseidonTranscript<F: ScalarField, const T: usize, const RATE: usize> {
+    sponge: PoseidonSponge<F, T, RATE>,
+}
+
+impl<F: ScalarField, const T: usize, const RATE: usize> PoseidonTranscript<F, T, RATE> {
+    /// Creates a new transcript and absorbs `initial_state` as constants.
+    ///
+    /// `initial_state` should identify the protocol and its public parameters, so transcripts of different protocols
+    /// never produce the same challenges.
+    pub fn new(
+        ctx: &mut Context<F>,
+        spec: OptimizedPoseidonSpec<F, T, RATE>,
+        initial_state: &[F],
+    ) -> Self {
+        let mut sponge = PoseidonSponge::from_spec(ctx, spec);
+        let initial_state = initial_state.iter().map(|f| ctx.load_constant(*f)).collect::<Vec<_>>();
+        sponge.update(&initial_state);
+        Self { sponge }
+    }
+
+    /// Absorbs `msg` under `label`.
+    ///
+    /// The label is absorbed as constants before `msg`: first its length in bytes, then its bytes packed into
+    /// field elements of 31 bytes each, in little endian.
+    pub fn append_message(&mut self, ctx: &mut Context<F>, label: &[u8], msg: AssignedValue<F>) {
+        let label =
+            Self::encode_label(label).into_iter().map(|f| ctx.load_constant(f)).collect::<Vec<_>>();
+        self.sponge.update(&label);
+        self.sponge.update(&[msg]);
+    }
+
+    /// Squeezes a challenge from everything absorbed so far.
+    pub fn get_challenge(
+        &mut self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+    ) -> AssignedValue<F> {
+        self.sponge.squeeze(ctx, gate)
+    }
+
+    /// Returns the field elements `label` is absorbed as.
+    pub fn encode_label(label: &[u8]) -> Vec<F> {
+        let len = F::from(label.len() as u64);
+        let chunks = label.chunks(LABEL_BYTES_PER_ELEMENT).map(F::from_bytes_le);
+        [len].into_iter().chain(chunks).collect()
+    }
+}
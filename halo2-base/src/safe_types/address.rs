@@ -0,0 +1,88 @@
+use super::*;
+
+/// Number of bytes of an Ethereum address.
+const ADDRESS_BYTES: usize = 20;
+
+impl<F: ScalarField> SafeAddress<F> {
+    /// Creates a [SafeAddress] from its little-endian bytes. Adds no constraints since the bytes are already safe.
+    pub fn from_bytes_le(bytes: [SafeByte<F>; ADDRESS_BYTES]) -> Self {
+        Self::new(bytes.map(|b| b.0).to_vec())
+    }
+
+    /// Creates a [SafeAddress] from its big-endian bytes, the usual encoding of an address.
+    /// Adds no constraints since the bytes are already safe.
+    pub fn from_bytes_be(mut bytes: [SafeByte<F>; ADDRESS_BYTES]) -> Self {
+        bytes.reverse();
+        Self::from_bytes_le(bytes)
+    }
+
+    /// Returns the little-endian bytes of the address.
+    pub fn to_bytes_le(&self) -> [SafeByte<F>; ADDRESS_BYTES] {
+        core::array::from_fn(|i| SafeByte(self.value[i]))
+    }
+
+    /// Returns the big-endian bytes of the address.
+    pub fn to_bytes_be(&self) -> [SafeByte<F>; ADDRESS_BYTES] {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Constrains and returns the address as a 160-bit unsigned integer, e.g. [SafeUint160].
+    ///
+    /// Packs `BYTES_PER_ELE` bytes into each element, so no range checks are needed.
+    pub fn to_u160<const BYTES_PER_ELE: usize>(
+        &self,
+        ctx: &mut Context<F>,
+        gate: &impl GateInstructions<F>,
+    ) -> SafeType<F, BYTES_PER_ELE, 160> {
+        assert!(BYTES_PER_ELE * BITS_PER_BYTE <= F::CAPACITY as usize);
+        let byte_base = (0..BYTES_PER_ELE)
+            .map(|i| Constant(gate.pow_of_two()[i * BITS_PER_BYTE]))
+            .collect_vec();
+        let value = self
+            .value
+            .chunks(BYTES_PER_ELE)
+            .map(|chunk| gate.inner_product(ctx, chunk.to_vec(), byte_base[..chunk.len()].to_vec()))
+            .collect();
+        SafeType::new(value)
+    }
+
+    /// Constrains that every byte of the address is in `[0, 256)`.
+    ///
+    /// Only needed for addresses obtained without checks, e.g. through [SafeTypeChip::unsafe_to_safe_type].
+    pub fn assert_valid(&self, ctx: &mut Context<F>, chip: &SafeTypeChip<F>) {
+        for byte in &self.value {
+            chip.assert_byte(ctx, *byte);
+        }
+    }
+}
+
+impl<'a, F: ScalarField> SafeTypeChip<'a, F> {
+    /// Constrains and returns the [SafeAddress] whose value is the 160-bit unsigned integer `a`, e.g. a [SafeUint160].
+    pub fn u160_to_address<const BYTES_PER_ELE: usize>(
+        &self,
+        ctx: &mut Context<F>,
+        a: &SafeType<F, BYTES_PER_ELE, 160>,
+    ) -> SafeAddress<F> {
+        let gate = self.range_chip.gate();
+        let element_bytes = SafeType::<F, BYTES_PER_ELE, 160>::bits_per_ele() / BITS_PER_BYTE;
+        let bytes = a
+            .value()
+            .iter()
+            .enumerate()
+            .flat_map(|(i, ele)| {
+                let num_bytes = element_bytes.min(ADDRESS_BYTES - i * element_bytes);
+                let bytes = ele.value().to_bytes_le()[..num_bytes]
+                    .iter()
+                    .map(|b| *self.load_byte(ctx, *b).as_ref())
+                    .collect_vec();
+                let powers = (0..num_bytes).map(|j| Constant(gate.pow_of_two()[j * BITS_PER_BYTE]));
+                let recomposed = gate.inner_product(ctx, bytes.clone(), powers);
+                ctx.constrain_equal(&recomposed, ele);
+                bytes
+            })
+            .collect();
+        SafeAddress::new(bytes)
+    }
+}
@@ -0,0 +1,30 @@
+use super::*;
+
+/// Number of bytes of a [SafeBytes32].
+const BYTES32_LEN: usize = 32;
+
+impl<F: ScalarField> SafeBytes32<F> {
+    /// Creates a [SafeBytes32] from its bytes. Adds no constraints since the bytes are already safe.
+    pub fn from_bytes(bytes: [SafeByte<F>; BYTES32_LEN]) -> Self {
+        Self::new(bytes.map(|b| b.0).to_vec())
+    }
+
+    /// Returns the bytes of the value.
+    pub fn to_bytes(&self) -> [SafeByte<F>; BYTES32_LEN] {
+        core::array::from_fn(|i| SafeByte(self.value[i]))
+    }
+
+    /// Constrains every byte of `bytes` to be in `[0, 256)` and returns them as a [SafeBytes32].
+    pub fn from_assigned_bytes(
+        ctx: &mut Context<F>,
+        chip: &SafeTypeChip<F>,
+        bytes: [AssignedValue<F>; BYTES32_LEN],
+    ) -> Self {
+        Self::from_bytes(bytes.map(|b| chip.assert_byte(ctx, b)))
+    }
+
+    /// Returns the bytes of the value as raw [AssignedValue]s.
+    pub fn to_assigned_bytes(&self) -> [AssignedValue<F>; BYTES32_LEN] {
+        self.value.clone().try_into().unwrap()
+    }
+}
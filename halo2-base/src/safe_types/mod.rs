@@ -10,16 +10,20 @@ use crate::{
     },
     utils::ScalarField,
     AssignedValue, Context,
-    QuantumCell::Witness,
+    QuantumCell::{Constant, Witness},
 };
 
 use itertools::Itertools;
 
+mod address;
 mod bytes;
+mod bytes32;
 mod primitives;
+mod u256;
 
 pub use bytes::*;
 pub use primitives::*;
+pub use u256::*;
 
 #[cfg(test)]
 pub mod tests;
@@ -113,7 +117,7 @@ pub type SafeUint128<F> = CompactSafeType<F, 128>;
 pub type SafeUint160<F> = CompactSafeType<F, 160>;
 /// SafeType for uint256.
 pub type SafeUint256<F> = CompactSafeType<F, 256>;
-/// SafeType for Address.
+/// SafeType for Address, stored as 20 little-endian bytes.
 pub type SafeAddress<F> = SafeType<F, 1, 160>;
 /// SafeType for bytes32.
 pub type SafeBytes32<F> = SafeType<F, 1, 256>;
@@ -165,6 +169,25 @@ impl<'a, F: ScalarField> SafeTypeChip<'a, F> {
         SafeType::<F, BYTES_PER_ELE, TOTAL_BITS>::new(value)
     }
 
+    /// Constrains that `input` has at most `TOTAL_BITS` bits and wraps it in a single element [SafeType],
+    /// e.g. [SafeUint64].
+    ///
+    /// Costs exactly one range check, the same as calling [RangeInstructions::range_check] directly.
+    /// See [SafeTypeChip::assert_byte] for bytes.
+    pub fn assert_in_range<const BYTES_PER_ELE: usize, const TOTAL_BITS: usize>(
+        &self,
+        ctx: &mut Context<F>,
+        input: AssignedValue<F>,
+    ) -> SafeType<F, BYTES_PER_ELE, TOTAL_BITS> {
+        assert_eq!(
+            SafeType::<F, BYTES_PER_ELE, TOTAL_BITS>::VALUE_LENGTH,
+            1,
+            "assert_in_range is only defined for single element SafeType"
+        );
+        self.range_chip.range_check(ctx, input, TOTAL_BITS);
+        SafeType::new(vec![input])
+    }
+
     /// Unsafe method that directly converts `input` to [`SafeType`] **without any checks**.
     /// This should **only** be used if an external library needs to convert their types to [`SafeType`].
     pub fn unsafe_to_safe_type<const BYTES_PER_ELE: usize, const TOTAL_BITS: usize>(
@@ -320,6 +343,103 @@ impl<'a, F: ScalarField> SafeTypeChip<'a, F> {
         )
     }
 
+    /// Constrains that only the lowest `used_bits` bits of `input` may be non-zero.
+    ///
+    /// Elements entirely above `used_bits` are constrained to be zero and the element containing bit `used_bits`
+    /// is range checked to the remaining bits, so this is cheaper than decomposing all `TOTAL_BITS` bits.
+    /// * ctx: Circuit [Context]<F> to assign witnesses to.
+    /// * input: [SafeType] to check.
+    /// * used_bits: number of low bits allowed to be non-zero.
+    pub fn check_upper_bits_zero<const BYTES_PER_ELE: usize, const TOTAL_BITS: usize>(
+        &self,
+        ctx: &mut Context<F>,
+        input: &SafeType<F, BYTES_PER_ELE, TOTAL_BITS>,
+        used_bits: usize,
+    ) {
+        let element_bits = SafeType::<F, BYTES_PER_ELE, TOTAL_BITS>::bits_per_ele();
+        for (i, ele) in input.value().iter().enumerate() {
+            let start = i * element_bits;
+            if used_bits <= start {
+                self.range_chip.gate().assert_is_const(ctx, ele, &F::ZERO);
+            } else if used_bits < start + element_bits {
+                self.range_chip.range_check(ctx, *ele, used_bits - start);
+            }
+        }
+    }
+
+    /// Constrains and returns the field negation `-a mod p` of a single element [SafeType].
+    ///
+    /// The result is a raw [AssignedValue] because `-a mod p` is generally not within `TOTAL_BITS` bits.
+    /// * ctx: Circuit [Context]<F> to assign witnesses to.
+    /// * a: [SafeType] consisting of one element, e.g. [SafeUint64].
+    pub fn negate<const BYTES_PER_ELE: usize, const TOTAL_BITS: usize>(
+        &self,
+        ctx: &mut Context<F>,
+        a: &SafeType<F, BYTES_PER_ELE, TOTAL_BITS>,
+    ) -> AssignedValue<F> {
+        assert_eq!(a.value().len(), 1, "negate is only defined for single element SafeType");
+        self.range_chip.gate().neg(ctx, a.value()[0])
+    }
+
+    /// Constrains and returns `-a` if `is_negative` is true, otherwise `a`.
+    /// * ctx: Circuit [Context]<F> to assign witnesses to.
+    /// * a: [AssignedValue] to conditionally negate.
+    /// * is_negative: [SafeBool] selecting the negation.
+    pub fn signed_negate(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+        is_negative: SafeBool<F>,
+    ) -> AssignedValue<F> {
+        // a - 2 * is_negative * a
+        let gate = self.range_chip.gate();
+        let neg_part = gate.mul(ctx, *is_negative.as_ref(), a);
+        gate.sub_mul(ctx, a, Constant(F::from(2u64)), neg_part)
+    }
+
+    /// Returns `a` with its byte order reversed.
+    ///
+    /// This only reorders the [SafeByte]s, so it adds no constraints.
+    pub fn byte_swap<const N: usize>(&self, a: FixLenBytes<F, N>) -> FixLenBytes<F, N> {
+        let mut bytes = a.into_bytes();
+        bytes.reverse();
+        FixLenBytes::new(bytes)
+    }
+
+    /// Constrains and returns `a` with the order of its 4 bytes reversed.
+    /// * ctx: Circuit [Context]<F> to assign witnesses to.
+    /// * a: [SafeUint32] to swap.
+    pub fn word_byte_swap32(&self, ctx: &mut Context<F>, a: SafeUint32<F>) -> SafeUint32<F> {
+        SafeUint32::new(vec![self.swap_element_bytes::<4>(ctx, a.value()[0])])
+    }
+
+    /// Constrains and returns `a` with the order of its 8 bytes reversed.
+    /// * ctx: Circuit [Context]<F> to assign witnesses to.
+    /// * a: [SafeUint64] to swap.
+    pub fn word_byte_swap64(&self, ctx: &mut Context<F>, a: SafeUint64<F>) -> SafeUint64<F> {
+        SafeUint64::new(vec![self.swap_element_bytes::<8>(ctx, a.value()[0])])
+    }
+
+    /// Decomposes `a` into `N` little-endian bytes and recomposes them in reverse order.
+    ///
+    /// Assumes `a` is less than `2^(8 * N)`, which holds for a [SafeType] element of `N` bytes.
+    fn swap_element_bytes<const N: usize>(
+        &self,
+        ctx: &mut Context<F>,
+        a: AssignedValue<F>,
+    ) -> AssignedValue<F> {
+        let gate = self.range_chip.gate();
+        let bytes =
+            a.value().to_bytes_le()[..N].iter().map(|b| self.load_byte(ctx, *b)).collect_vec();
+        let bytes = FixLenBytes::<F, N>::new(bytes.try_into().unwrap());
+        let powers = (0..N).map(|i| Constant(gate.pow_of_two()[BITS_PER_BYTE * i])).collect_vec();
+        let recomposed =
+            gate.inner_product(ctx, bytes.bytes().iter().map(|b| *b.as_ref()), powers.clone());
+        ctx.constrain_equal(&recomposed, &a);
+        let swapped = self.byte_swap(bytes);
+        gate.inner_product(ctx, swapped.bytes().iter().map(|b| *b.as_ref()), powers)
+    }
+
     fn add_bytes_constraints(
         &self,
         ctx: &mut Context<F>,
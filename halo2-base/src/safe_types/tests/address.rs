@@ -0,0 +1,72 @@
+use crate::{
+    gates::RangeInstructions,
+    halo2_proofs::halo2curves::bn256::Fr,
+    safe_types::{SafeAddress, SafeTypeChip, SafeUint160},
+    utils::{biguint_to_fe, fe_to_biguint, testing::base_test, ScalarField},
+};
+use num_bigint::BigUint;
+use test_case::test_case;
+
+const ADDRESS: [u8; 20] = [
+    0xd8, 0xda, 0x6b, 0xf2, 0x69, 0x64, 0xaf, 0x9d, 0x7e, 0xed, 0x9e, 0x03, 0xe5, 0x34, 0x15, 0xd3,
+    0x7a, 0xa9, 0x60, 0x45,
+];
+
+#[test]
+fn test_address_bytes_round_trip() {
+    base_test().k(10).lookup_bits(8).run(|ctx, range| {
+        let safe = SafeTypeChip::new(range);
+        let bytes = ADDRESS.map(|b| safe.load_byte(ctx, b));
+        let address = SafeAddress::from_bytes_be(bytes);
+        let be = address.to_bytes_be().map(|b| b.as_ref().value().get_lower_64() as u8);
+        assert_eq!(be, ADDRESS);
+        let le = address.to_bytes_le().map(|b| b.as_ref().value().get_lower_64() as u8);
+        let mut expected = ADDRESS;
+        expected.reverse();
+        assert_eq!(le, expected);
+    });
+}
+
+#[test]
+fn test_address_to_u160_and_back() {
+    let expected = BigUint::from_bytes_be(&ADDRESS);
+    base_test().k(10).lookup_bits(8).run(|ctx, range| {
+        let safe = SafeTypeChip::new(range);
+        let bytes = ADDRESS.map(|b| safe.load_byte(ctx, b));
+        let address = SafeAddress::from_bytes_be(bytes);
+
+        let uint: SafeUint160<Fr> = address.to_u160(ctx, range.gate());
+        assert_eq!(fe_to_biguint(uint.value()[0].value()), expected);
+
+        let address_again = safe.u160_to_address(ctx, &uint);
+        for (a, b) in address_again.value().iter().zip(address.value()) {
+            assert_eq!(a.value(), b.value());
+        }
+    });
+}
+
+#[test_case(false, true; "valid bytes")]
+#[test_case(true, false; "byte out of range")]
+fn test_address_assert_valid(overflow: bool, expect_satisfied: bool) {
+    base_test().k(10).lookup_bits(8).expect_satisfied(expect_satisfied).run(|ctx, range| {
+        let safe = SafeTypeChip::new(range);
+        let mut bytes = ADDRESS.map(|b| b as u64).to_vec();
+        if overflow {
+            bytes[3] = 256;
+        }
+        let bytes = ctx.assign_witnesses(bytes.into_iter().map(Fr::from));
+        let address = SafeTypeChip::unsafe_to_safe_type::<1, 160>(bytes);
+        address.assert_valid(ctx, &safe);
+    });
+}
+
+#[test]
+fn test_u160_to_address_bad_decomposition() {
+    // u160 value of 2^160 does not fit in 20 bytes
+    base_test().k(10).lookup_bits(8).expect_satisfied(false).run(|ctx, range| {
+        let safe = SafeTypeChip::new(range);
+        let value = ctx.load_witness(biguint_to_fe(&(BigUint::from(1u8) << 160)));
+        let uint: SafeUint160<Fr> = SafeTypeChip::unsafe_to_safe_type(vec![value]);
+        safe.u160_to_address(ctx, &uint);
+    });
+}
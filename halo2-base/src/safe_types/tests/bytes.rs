@@ -142,6 +142,49 @@ fn pos_fix_len_bytes() {
     });
 }
 
+#[test]
+fn pos_byte_swap() {
+    base_test().k(10).lookup_bits(8).run(|ctx, range| {
+        let safe = SafeTypeChip::new(range);
+        let raw = ctx.assign_witnesses([1u64, 2, 3, 4].map(Fr::from));
+        let bytes = safe.raw_to_fix_len_bytes::<4>(ctx, raw.try_into().unwrap());
+        let swapped = safe.byte_swap(bytes.clone());
+        let values = swapped.bytes().iter().map(|b| b.as_ref().value().get_lower_64());
+        assert_eq!(values.collect::<Vec<_>>(), [4, 3, 2, 1]);
+        let restored = safe.byte_swap(swapped);
+        for (a, b) in restored.bytes().iter().zip(bytes.bytes()) {
+            assert_eq!(a.as_ref().cell, b.as_ref().cell);
+        }
+    });
+}
+
+#[test_case(0x01020304 => 0x04030201; "word_byte_swap32(): 0x01020304")]
+#[test_case(0xff000000 => 0xff; "word_byte_swap32(): high byte only")]
+fn pos_word_byte_swap32(a: u64) -> u64 {
+    base_test().k(10).lookup_bits(8).run(|ctx, range| {
+        let safe = SafeTypeChip::new(range);
+        let a = ctx.load_witness(Fr::from(a));
+        range.range_check(ctx, a, 32);
+        let a = SafeTypeChip::unsafe_to_safe_type(vec![a]);
+        let swapped = safe.word_byte_swap32(ctx, a.clone());
+        let restored = safe.word_byte_swap32(ctx, swapped.clone());
+        assert_eq!(restored.value()[0].value(), a.value()[0].value());
+        swapped.value()[0].value().get_lower_64()
+    })
+}
+
+#[test]
+fn pos_word_byte_swap64() {
+    let out = base_test().k(10).lookup_bits(8).run(|ctx, range| {
+        let safe = SafeTypeChip::new(range);
+        let a = ctx.load_witness(Fr::from(0x0102030405060708));
+        range.range_check(ctx, a, 64);
+        let a = SafeTypeChip::unsafe_to_safe_type(vec![a]);
+        safe.word_byte_swap64(ctx, a).value()[0].value().get_lower_64()
+    });
+    assert_eq!(out, 0x0807060504030201);
+}
+
 // Assert inputs.len() == len
 #[test]
 #[should_panic]
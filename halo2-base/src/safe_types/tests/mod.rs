@@ -1,2 +1,4 @@
+pub(crate) mod address;
 pub(crate) mod bytes;
 pub(crate) mod safe_type;
+pub(crate) mod u256;
@@ -1,12 +1,14 @@
 use crate::{
     gates::circuit::{builder::RangeCircuitBuilder, CircuitBuilderStage},
-    halo2_proofs::plonk::{keygen_pk, keygen_vk, Assigned},
+    gates::RangeInstructions,
+    halo2_proofs::plonk::{keygen_pk, keygen_vk},
     halo2_proofs::{halo2curves::bn256::Fr, poly::kzg::commitment::ParamsKZG},
     safe_types::*,
-    utils::testing::{check_proof, gen_proof},
+    utils::testing::{base_test, check_proof, gen_proof},
 };
 use itertools::Itertools;
 use rand::rngs::OsRng;
+use test_case::test_case;
 
 // soundness checks for `raw_bytes_to` function
 fn test_raw_bytes_to_gen<const BYTES_PER_ELE: usize, const TOTAL_BITS: usize>(
@@ -53,7 +55,7 @@ fn test_raw_bytes_to_gen<const BYTES_PER_ELE: usize, const TOTAL_BITS: usize>(
             .raw_bytes_to::<BYTES_PER_ELE, TOTAL_BITS>(builder.main(0), assigned_raw_bytes);
         // prank the safe value cells
         for (offset, witness) in safe_value_offsets.iter().zip_eq(outputs) {
-            builder.main(0).advice[*offset] = Assigned::<Fr>::Trivial(*witness);
+            builder.main(0).debug_prank(*offset, *witness);
         }
         gen_proof(&params, &pk, builder)
     };
@@ -244,3 +246,81 @@ fn test_raw_bytes_to_bytes32() {
         false,
     );
 }
+
+#[test_case(&[0xff; 8], 64, true; "uint256 fits in 64 bits")]
+#[test_case(&[0xff; 9], 64, false; "uint256 does not fit in 64 bits")]
+#[test_case(&[0xff; 31], 248, true; "uint256 fits in first element")]
+#[test_case(&[0xff; 32], 248, false; "uint256 non-zero second element")]
+fn test_check_upper_bits_zero(low_bytes: &[u64], used_bits: usize, expect_satisfied: bool) {
+    const BYTES_PER_ELE: usize = SafeUint256::<Fr>::BYTES_PER_ELE;
+    const TOTAL_BITS: usize = SafeUint256::<Fr>::TOTAL_BITS;
+    base_test().k(10).lookup_bits(8).expect_satisfied(expect_satisfied).run(|ctx, range| {
+        let safe_type_chip = SafeTypeChip::new(range);
+        let mut bytes = low_bytes.iter().map(|b| Fr::from(*b)).collect_vec();
+        bytes.resize(32, Fr::zero());
+        let bytes = ctx.assign_witnesses(bytes);
+        let uint256 = safe_type_chip.raw_bytes_to::<BYTES_PER_ELE, TOTAL_BITS>(ctx, bytes);
+        safe_type_chip.check_upper_bits_zero(ctx, &uint256, used_bits);
+    });
+}
+
+#[test_case(0 => Fr::zero(); "negate 0")]
+#[test_case(1 => -Fr::one(); "negate 1")]
+#[test_case(u64::MAX => -Fr::from(u64::MAX); "negate u64::MAX")]
+fn test_negate(a: u64) -> Fr {
+    const BYTES_PER_ELE: usize = SafeUint64::<Fr>::BYTES_PER_ELE;
+    const TOTAL_BITS: usize = SafeUint64::<Fr>::TOTAL_BITS;
+    base_test().k(10).lookup_bits(8).run(|ctx, range| {
+        let safe_type_chip = SafeTypeChip::new(range);
+        let bytes = ctx.assign_witnesses(a.to_le_bytes().map(|b| Fr::from(b as u64)));
+        let a = safe_type_chip.raw_bytes_to::<BYTES_PER_ELE, TOTAL_BITS>(ctx, bytes);
+        *safe_type_chip.negate(ctx, &a).value()
+    })
+}
+
+#[test_case(Fr::from(12345), false => Fr::from(12345); "signed_negate positive")]
+#[test_case(Fr::from(12345), true => -Fr::from(12345); "signed_negate negative")]
+fn test_signed_negate(a: Fr, is_negative: bool) -> Fr {
+    base_test().run(|ctx, range| {
+        let safe_type_chip = SafeTypeChip::new(range);
+        let a = ctx.load_witness(a);
+        let is_negative = safe_type_chip.load_bool(ctx, is_negative);
+        *safe_type_chip.signed_negate(ctx, a, is_negative).value()
+    })
+}
+
+#[test]
+fn test_negate_round_trip() {
+    base_test().run(|ctx, range| {
+        let safe_type_chip = SafeTypeChip::new(range);
+        let a = ctx.load_witness(Fr::from(777));
+        let is_negative = safe_type_chip.load_bool(ctx, true);
+        let neg = safe_type_chip.signed_negate(ctx, a, is_negative);
+        let a_again = safe_type_chip.signed_negate(ctx, neg, is_negative);
+        assert_eq!(a_again.value(), a.value());
+    });
+}
+
+#[test_case((1 << 32) - 1, true; "assert_in_range max u32")]
+#[test_case(1 << 32, false; "assert_in_range overflow u32")]
+fn test_assert_in_range(a: u64, expect_satisfied: bool) {
+    base_test().k(10).lookup_bits(8).expect_satisfied(expect_satisfied).run(|ctx, range| {
+        let safe_type_chip = SafeTypeChip::new(range);
+        let a = ctx.load_witness(Fr::from(a));
+        let safe: SafeUint32<Fr> = safe_type_chip.assert_in_range(ctx, a);
+        assert_eq!(safe.value(), &[a]);
+    });
+}
+
+#[test]
+fn test_assert_in_range_no_overhead() {
+    base_test().k(10).lookup_bits(8).run(|ctx, range| {
+        let safe_type_chip = SafeTypeChip::new(range);
+        let a = ctx.load_witness(Fr::from(1234));
+        let start = ctx.advice_len();
+        range.range_check(ctx, a, 32);
+        let direct = ctx.advice_len() - start;
+        let _: SafeUint32<Fr> = safe_type_chip.assert_in_range(ctx, a);
+        assert_eq!(ctx.advice_len() - start, 2 * direct);
+    });
+}
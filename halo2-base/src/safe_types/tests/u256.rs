@@ -0,0 +1,148 @@
+use crate::{
+    ff::Field,
+    halo2_proofs::halo2curves::bn256::Fr,
+    safe_types::{SafeTypeChip, SafeU256, SafeU256Instructions},
+    utils::{fe_to_biguint, testing::base_test},
+};
+use num_bigint::BigUint;
+use num_traits::{Num, One, Zero};
+use test_case::test_case;
+
+fn u256(hex: &str) -> BigUint {
+    BigUint::from_str_radix(hex, 16).unwrap()
+}
+
+fn max_u256() -> BigUint {
+    (BigUint::one() << 256) - 1u64
+}
+
+fn value_of(a: &SafeU256<Fr>) -> BigUint {
+    a.value()
+        .iter()
+        .rev()
+        .fold(BigUint::zero(), |acc, limb| (acc << 64) + fe_to_biguint(limb.value()))
+}
+
+fn value_of_bytes(bytes: &[Fr]) -> BigUint {
+    bytes.iter().fold(BigUint::zero(), |acc, b| (acc << 8) + fe_to_biguint(b))
+}
+
+#[test_case(u256("1"), u256("2") => u256("3"); "add_mod_p256(): small")]
+#[test_case(u256("ffffffffffffffff"), u256("1") => u256("10000000000000000"); "add_mod_p256(): limb carry")]
+#[test_case(max_u256(), u256("1") => BigUint::zero(); "add_mod_p256(): wraps")]
+fn test_add_mod_p256(a: BigUint, b: BigUint) -> BigUint {
+    base_test().k(12).run(|ctx, range| {
+        let chip = SafeTypeChip::new(range);
+        let [a, b] = [a, b].map(|x| chip.load_u256(ctx, &x));
+        value_of(&chip.add_mod_p256(ctx, &a, &b))
+    })
+}
+
+#[test_case(u256("ffffffffffffffff"), u256("ffffffffffffffff") => u256("fffffffffffffffe0000000000000001"); "mul_mod_p256(): limb product")]
+#[test_case(max_u256(), max_u256() => u256("1"); "mul_mod_p256(): wraps")]
+#[test_case(u256("123456789abcdef0123456789abcdef"), BigUint::zero() => BigUint::zero(); "mul_mod_p256(): zero")]
+fn test_mul_mod_p256(a: BigUint, b: BigUint) -> BigUint {
+    base_test().k(12).run(|ctx, range| {
+        let chip = SafeTypeChip::new(range);
+        let [a, b] = [a, b].map(|x| chip.load_u256(ctx, &x));
+        value_of(&chip.mul_mod_p256(ctx, &a, &b))
+    })
+}
+
+#[test_case(max_u256(), u256("10") => (u256("fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"), u256("f")); "div_mod_p256(): max by 16")]
+#[test_case(u256("5"), u256("7") => (BigUint::zero(), u256("5")); "div_mod_p256(): a < b")]
+#[test_case(max_u256(), max_u256() => (u256("1"), BigUint::zero()); "div_mod_p256(): a == b")]
+fn test_div_mod_p256(a: BigUint, b: BigUint) -> (BigUint, BigUint) {
+    base_test().k(12).run(|ctx, range| {
+        let chip = SafeTypeChip::new(range);
+        let [a, b] = [a, b].map(|x| chip.load_u256(ctx, &x));
+        let (q, r) = chip.div_mod_p256(ctx, &a, &b);
+        (value_of(&q), value_of(&r))
+    })
+}
+
+#[test]
+fn test_div_mod_p256_by_zero() {
+    base_test().k(12).expect_satisfied(false).run(|ctx, range| {
+        let chip = SafeTypeChip::new(range);
+        let [a, b] = [u256("5"), BigUint::zero()].map(|x| chip.load_u256(ctx, &x));
+        chip.div_mod_p256(ctx, &a, &b);
+    })
+}
+
+#[test_case(u256("3"), u256("a"), 4 => u256("e6a9"); "mod_exp_u256(): 3^10")]
+#[test_case(u256("2"), u256("ff"), 8 => u256("8000000000000000000000000000000000000000000000000000000000000000"); "mod_exp_u256(): 2^255")]
+#[test_case(u256("2"), u256("100"), 9 => BigUint::zero(); "mod_exp_u256(): 2^256 wraps")]
+fn test_mod_exp_u256(base: BigUint, exp: BigUint, exp_bits: usize) -> BigUint {
+    base_test().k(15).run(|ctx, range| {
+        let chip = SafeTypeChip::new(range);
+        let [base, exp] = [base, exp].map(|x| chip.load_u256(ctx, &x));
+        value_of(&chip.mod_exp_u256(ctx, &base, &exp, exp_bits))
+    })
+}
+
+#[test]
+fn test_mod_exp_u256_exp_too_large() {
+    base_test().k(12).expect_satisfied(false).run(|ctx, range| {
+        let chip = SafeTypeChip::new(range);
+        let [base, exp] = [u256("3"), u256("10")].map(|x| chip.load_u256(ctx, &x));
+        chip.mod_exp_u256(ctx, &base, &exp, 4);
+    })
+}
+
+// with a zero exponent the result is the initial accumulator, which must be the constant one
+#[test]
+fn test_mod_exp_u256_prank_initial_acc() {
+    base_test().k(12).expect_satisfied(false).run(|ctx, range| {
+        let chip = SafeTypeChip::new(range);
+        let [base, exp] = [u256("3"), BigUint::zero()].map(|x| chip.load_u256(ctx, &x));
+        let res = chip.mod_exp_u256(ctx, &base, &exp, 0);
+        res.value()[0].debug_prank(ctx, Fr::from(2));
+    })
+}
+
+#[test_case(u256("1"), u256("2"), true; "assert_less_than_u256(): less")]
+#[test_case(u256("1ffffffffffffffff"), u256("20000000000000000"), true; "assert_less_than_u256(): borrow")]
+#[test_case(u256("2"), u256("2"), false; "assert_less_than_u256(): equal")]
+#[test_case(max_u256(), u256("1"), false; "assert_less_than_u256(): greater")]
+fn test_assert_less_than_u256(a: BigUint, b: BigUint, expect_satisfied: bool) {
+    base_test().k(12).expect_satisfied(expect_satisfied).run(|ctx, range| {
+        let chip = SafeTypeChip::new(range);
+        let [a, b] = [a, b].map(|x| chip.load_u256(ctx, &x));
+        chip.assert_less_than_u256(ctx, &a, &b);
+    })
+}
+
+#[test_case(u256("1"), u256("2") => Fr::ONE; "is_less_than_u256(): less")]
+#[test_case(u256("1ffffffffffffffff"), u256("20000000000000000") => Fr::ONE; "is_less_than_u256(): borrow")]
+#[test_case(u256("2"), u256("2") => Fr::ZERO; "is_less_than_u256(): equal")]
+#[test_case(max_u256(), u256("1") => Fr::ZERO; "is_less_than_u256(): greater")]
+fn test_is_less_than_u256(a: BigUint, b: BigUint) -> Fr {
+    base_test().k(12).run(|ctx, range| {
+        let chip = SafeTypeChip::new(range);
+        let [a, b] = [a, b].map(|x| chip.load_u256(ctx, &x));
+        *chip.is_less_than_u256(ctx, &a, &b).value()
+    })
+}
+
+#[test]
+fn test_u256_bytes_be_less_than_max() {
+    // WETH token address, left padded to a storage word
+    let value = u256("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+    let to_bytes = |x: &BigUint| {
+        let mut bytes = x.to_bytes_le();
+        bytes.resize(32, 0);
+        bytes.into_iter().rev().map(|b| Fr::from(b as u64)).collect::<Vec<_>>()
+    };
+    let (round_trip, is_less) = base_test().k(12).run(|ctx, range| {
+        let chip = SafeTypeChip::new(range);
+        let [a, max] = [&value, &max_u256()].map(|x| {
+            let bytes: [_; 32] = ctx.assign_witnesses(to_bytes(x)).try_into().unwrap();
+            SafeU256::from_bytes_be(ctx, &chip, bytes)
+        });
+        let round_trip = a.to_bytes_be(ctx, &chip).map(|b| *b.as_ref().value());
+        (round_trip, *chip.is_less_than_u256(ctx, &a, &max).value())
+    });
+    assert_eq!(value_of_bytes(&round_trip), value);
+    assert_eq!(is_less, Fr::ONE);
+}
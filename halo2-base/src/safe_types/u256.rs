@@ -0,0 +1,277 @@
+use crate::{
+    gates::{GateInstructions, RangeInstructions},
+    AssignedValue, Context,
+    QuantumCell::{Constant, Existing},
+};
+
+use super::{SafeByte, SafeType, SafeTypeChip, ScalarField, BITS_PER_BYTE};
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::Zero;
+
+/// Number of bits of each limb of a [SafeU256].
+const LIMB_BITS: usize = 64;
+/// Number of limbs of a [SafeU256].
+const NUM_LIMBS: usize = 4;
+
+/// SafeType for an Ethereum `uint256`, stored as 4 little-endian limbs of 64 bits each.
+///
+/// Unlike [`super::SafeUint256`], which packs the value into as few elements as possible,
+/// the 64-bit limbs leave room in the field for limb products, which is what multiplication needs.
+pub type SafeU256<F> = SafeType<F, 8, 256>;
+
+/// Returns the integer value of `a`.
+fn value_of<F: ScalarField>(a: &SafeU256<F>) -> BigUint {
+    a.value()
+        .iter()
+        .rev()
+        .fold(BigUint::zero(), |acc, limb| (acc << LIMB_BITS) + limb.value().get_lower_64())
+}
+
+impl<F: ScalarField> SafeU256<F> {
+    /// Constrains every byte of `bytes` to be in `[0, 256)` and packs the big-endian bytes into a [SafeU256].
+    ///
+    /// Each limb is the inner product of 8 bytes, so the limbs need no further range checks.
+    pub fn from_bytes_be(
+        ctx: &mut Context<F>,
+        chip: &SafeTypeChip<F>,
+        bytes: [AssignedValue<F>; 32],
+    ) -> Self {
+        let bytes = bytes.map(|b| chip.assert_byte(ctx, b).0);
+        let limbs = bytes
+            .rchunks(LIMB_BITS / BITS_PER_BYTE)
+            .map(|limb_bytes| chip.range_chip.recompose_be(ctx, limb_bytes, BITS_PER_BYTE))
+            .collect();
+        Self::new(limbs)
+    }
+
+    /// Constrains and returns the big-endian bytes of the value, the inverse of [SafeU256::from_bytes_be].
+    pub fn to_bytes_be(&self, ctx: &mut Context<F>, chip: &SafeTypeChip<F>) -> [SafeByte<F>; 32] {
+        let bytes = self
+            .value()
+            .iter()
+            .rev()
+            .flat_map(|limb| {
+                chip.range_chip.decompose_be(ctx, *limb, BITS_PER_BYTE, LIMB_BITS / BITS_PER_BYTE)
+            })
+            .map(SafeByte)
+            .collect::<Vec<_>>();
+        bytes.try_into().unwrap()
+    }
+}
+
+/// Instructions for arithmetic on [SafeU256] values.
+pub trait SafeU256Instructions<F: ScalarField> {
+    /// Loads `value` as a [SafeU256], range checking each limb to 64 bits.
+    ///
+    /// Assumes `value < 2^256`.
+    fn load_u256(&self, ctx: &mut Context<F>, value: &BigUint) -> SafeU256<F>;
+
+    /// Constrains and returns `(a + b) mod 2^256`.
+    fn add_mod_p256(&self, ctx: &mut Context<F>, a: &SafeU256<F>, b: &SafeU256<F>) -> SafeU256<F>;
+
+    /// Constrains and returns `(a * b) mod 2^256`.
+    fn mul_mod_p256(&self, ctx: &mut Context<F>, a: &SafeU256<F>, b: &SafeU256<F>) -> SafeU256<F>;
+
+    /// Constrains and returns `(a / b, a % b)`.
+    ///
+    /// Constrains `a = q * b + r` without overflow and `r < b`, which fails if `b = 0`.
+    fn div_mod_p256(
+        &self,
+        ctx: &mut Context<F>,
+        a: &SafeU256<F>,
+        b: &SafeU256<F>,
+    ) -> (SafeU256<F>, SafeU256<F>);
+
+    /// Constrains and returns `base^exp mod 2^256` using square-and-multiply over the low `exp_bits` bits of `exp`.
+    ///
+    /// Constrains that the bits of `exp` above `exp_bits` are zero.
+    fn mod_exp_u256(
+        &self,
+        ctx: &mut Context<F>,
+        base: &SafeU256<F>,
+        exp: &SafeU256<F>,
+        exp_bits: usize,
+    ) -> SafeU256<F>;
+
+    /// Constrains that `a < b`.
+    fn assert_less_than_u256(&self, ctx: &mut Context<F>, a: &SafeU256<F>, b: &SafeU256<F>);
+
+    /// Returns `1` if `a < b`, otherwise `0`.
+    fn is_less_than_u256(
+        &self,
+        ctx: &mut Context<F>,
+        a: &SafeU256<F>,
+        b: &SafeU256<F>,
+    ) -> AssignedValue<F>;
+}
+
+impl<'a, F: ScalarField> SafeU256Instructions<F> for SafeTypeChip<'a, F> {
+    fn load_u256(&self, ctx: &mut Context<F>, value: &BigUint) -> SafeU256<F> {
+        assert!(value.bits() <= 256, "value does not fit in 256 bits");
+        let mut limbs = value.to_u64_digits();
+        limbs.resize(NUM_LIMBS, 0);
+        let limbs = ctx.assign_witnesses(limbs.into_iter().map(F::from));
+        for limb in &limbs {
+            self.range_chip.range_check(ctx, *limb, LIMB_BITS);
+        }
+        SafeU256::new(limbs)
+    }
+
+    fn add_mod_p256(&self, ctx: &mut Context<F>, a: &SafeU256<F>, b: &SafeU256<F>) -> SafeU256<F> {
+        let gate = self.range_chip.gate();
+        let mut carry = None;
+        let limbs = a
+            .value()
+            .iter()
+            .zip(b.value())
+            .map(|(a, b)| {
+                // a + b + carry < 2^65, so the carry out is still a single bit
+                let a = match carry {
+                    Some(carry) => gate.add(ctx, *a, carry),
+                    None => *a,
+                };
+                let (low, next_carry) = self.range_chip.carry_add(ctx, a, *b, LIMB_BITS);
+                carry = Some(next_carry);
+                low
+            })
+            .collect();
+        SafeU256::new(limbs)
+    }
+
+    fn mul_mod_p256(&self, ctx: &mut Context<F>, a: &SafeU256<F>, b: &SafeU256<F>) -> SafeU256<F> {
+        let (a, b) = (a.value(), b.value());
+        let mut carry = None;
+        let limbs = (0..NUM_LIMBS)
+            .map(|k| {
+                let addends = carry.into_iter().collect::<Vec<_>>();
+                let (low, next_carry) = self.mul_column(ctx, a, b, k, &addends);
+                carry = Some(next_carry);
+                low
+            })
+            .collect();
+        SafeU256::new(limbs)
+    }
+
+    fn div_mod_p256(
+        &self,
+        ctx: &mut Context<F>,
+        a: &SafeU256<F>,
+        b: &SafeU256<F>,
+    ) -> (SafeU256<F>, SafeU256<F>) {
+        let b_val = value_of(b);
+        let (q_val, r_val) = if b_val.is_zero() {
+            (BigUint::zero(), BigUint::zero())
+        } else {
+            value_of(a).div_rem(&b_val)
+        };
+        let q = self.load_u256(ctx, &q_val);
+        let r = self.load_u256(ctx, &r_val);
+
+        // q * b + r, computed column by column, must equal a and must not overflow 256 bits
+        let zero = ctx.load_zero();
+        let mut carry = None;
+        for k in 0..2 * NUM_LIMBS - 1 {
+            let addends = carry.into_iter().chain(r.value().get(k).copied()).collect::<Vec<_>>();
+            let (low, next_carry) = self.mul_column(ctx, q.value(), b.value(), k, &addends);
+            carry = Some(next_carry);
+            ctx.constrain_equal(&low, a.value().get(k).unwrap_or(&zero));
+        }
+        ctx.constrain_equal(&carry.unwrap(), &zero);
+        self.assert_less_than_u256(ctx, &r, b);
+        (q, r)
+    }
+
+    fn mod_exp_u256(
+        &self,
+        ctx: &mut Context<F>,
+        base: &SafeU256<F>,
+        exp: &SafeU256<F>,
+        exp_bits: usize,
+    ) -> SafeU256<F> {
+        assert!(exp_bits <= 256);
+        self.check_upper_bits_zero(ctx, exp, exp_bits);
+        let gate = self.range_chip.gate();
+        let bits = exp
+            .value()
+            .iter()
+            .flat_map(|limb| gate.num_to_bits(ctx, *limb, LIMB_BITS))
+            .take(exp_bits)
+            .collect::<Vec<_>>();
+        let one = ctx.load_constant(F::ONE);
+        let zero = ctx.load_zero();
+        let mut acc = SafeU256::new(vec![one, zero, zero, zero]);
+        for bit in bits.into_iter().rev() {
+            acc = self.mul_mod_p256(ctx, &acc, &acc);
+            let prod = self.mul_mod_p256(ctx, &acc, base);
+            let limbs = prod
+                .value()
+                .iter()
+                .zip(acc.value())
+                .map(|(p, a)| gate.select(ctx, *p, *a, bit))
+                .collect();
+            acc = SafeU256::new(limbs);
+        }
+        acc
+    }
+
+    fn assert_less_than_u256(&self, ctx: &mut Context<F>, a: &SafeU256<F>, b: &SafeU256<F>) {
+        let is_less = self.is_less_than_u256(ctx, a, b);
+        self.range_chip.gate().assert_is_const(ctx, &is_less, &F::ONE);
+    }
+
+    fn is_less_than_u256(
+        &self,
+        ctx: &mut Context<F>,
+        a: &SafeU256<F>,
+        b: &SafeU256<F>,
+    ) -> AssignedValue<F> {
+        // a - b is computed limb by limb with borrows, and borrows out of the top limb exactly when a < b
+        let gate = self.range_chip.gate();
+        let mut borrow: Option<AssignedValue<F>> = None;
+        for (a, b) in a.value().iter().zip(b.value()) {
+            let mut diff = gate.sub(ctx, *a, *b);
+            let mut diff_v = a.value().get_lower_64() as i128 - b.value().get_lower_64() as i128;
+            if let Some(borrow) = borrow {
+                diff = gate.sub(ctx, diff, borrow);
+                diff_v -= borrow.value().get_lower_64() as i128;
+            }
+            let next_borrow = ctx.load_witness(F::from(diff_v < 0));
+            gate.assert_bit(ctx, next_borrow);
+            diff = gate.mul_add(ctx, next_borrow, Constant(gate.pow_of_two()[LIMB_BITS]), diff);
+            self.range_chip.range_check(ctx, diff, LIMB_BITS);
+            borrow = Some(next_borrow);
+        }
+        borrow.unwrap()
+    }
+}
+
+impl<'a, F: ScalarField> SafeTypeChip<'a, F> {
+    /// Constrains and returns column `k` of the schoolbook product of `a` and `b` plus `addends`,
+    /// split into its low 64 bits and the carry into the next column.
+    ///
+    /// Assumes `addends` are at most a carry from the previous column and one limb.
+    fn mul_column(
+        &self,
+        ctx: &mut Context<F>,
+        a: &[AssignedValue<F>],
+        b: &[AssignedValue<F>],
+        k: usize,
+        addends: &[AssignedValue<F>],
+    ) -> (AssignedValue<F>, AssignedValue<F>) {
+        let gate = self.range_chip.gate();
+        let (lo, hi) = (k.saturating_sub(NUM_LIMBS - 1), k.min(NUM_LIMBS - 1));
+        let terms = (lo..=hi).map(|i| (a[i], b[k - i]));
+        let mut col =
+            gate.inner_product(ctx, terms.clone().map(|(a, _)| a), terms.map(|(_, b)| Existing(b)));
+        for addend in addends {
+            col = gate.add(ctx, col, *addend);
+        }
+        // each column is less than 4 * 2^128 + 2^67 + 2^64 < 2^192, so it fits in three limbs and the
+        // carry into the next column is the upper two
+        let limbs = self.range_chip.decompose_le(ctx, col, LIMB_BITS, 3);
+        let carry = gate.mul_add(ctx, limbs[2], Constant(gate.pow_of_two()[LIMB_BITS]), limbs[1]);
+        (limbs[0], carry)
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use crate::{utils::ScalarField, AssignedValue, Context, ContextTag};
+
+/// Interns constants so that each distinct constant is loaded at most once per [Context].
+///
+/// Cells are keyed by the [ContextTag] of the context they were loaded in, so a single cache can be shared
+/// between several contexts without returning a cell from a different context.
+#[derive(Clone, Debug, Default)]
+pub struct FieldElementCache<F: ScalarField> {
+    cells: HashMap<(ContextTag, F), AssignedValue<F>>,
+}
+
+impl<F: ScalarField> FieldElementCache<F> {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self { cells: HashMap::new() }
+    }
+
+    /// Returns the cell previously loaded with constant `value` in `ctx`, or loads it with
+    /// [Context::load_constant] and caches it.
+    /// * `ctx`: [Context] to load the constant in
+    /// * `value`: the constant
+    pub fn get_or_insert(&mut self, ctx: &mut Context<F>, value: F) -> AssignedValue<F> {
+        *self.cells.entry((ctx.tag(), value)).or_insert_with(|| ctx.load_constant(value))
+    }
+
+    /// Returns the number of cached cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `true` if no cells are cached.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Removes all cached cells. Must be called whenever the contexts they belong to are cleared.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use crate::{
+        halo2_proofs::halo2curves::bn256::Fr,
+        virtual_region::copy_constraints::SharedCopyConstraintManager, Context,
+    };
+
+    use super::FieldElementCache;
+
+    #[test]
+    fn test_get_or_insert_dedups_per_context() {
+        let copy_manager = SharedCopyConstraintManager::<Fr>::default();
+        let type_id = TypeId::of::<FieldElementCache<Fr>>();
+        let mut ctx0 = Context::new(false, 0, type_id, 0, copy_manager.clone());
+        let mut ctx1 = Context::new(false, 0, type_id, 1, copy_manager);
+        let mut cache = FieldElementCache::new();
+
+        let a = cache.get_or_insert(&mut ctx0, Fr::from(7));
+        let b = cache.get_or_insert(&mut ctx0, Fr::from(7));
+        assert_eq!(a.cell, b.cell);
+        assert_eq!(ctx0.advice_len(), 1);
+
+        cache.get_or_insert(&mut ctx0, Fr::from(8));
+        let c = cache.get_or_insert(&mut ctx1, Fr::from(7));
+        assert_ne!(a.cell, c.cell);
+        assert_eq!(*c.value(), Fr::from(7));
+        assert_eq!(cache.len(), 3);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -13,8 +13,13 @@ use num_bigint::Sign;
 use num_traits::Signed;
 use num_traits::{One, Zero};
 
+/// Interning of constant cells
+pub mod cache;
 /// Helper functions for raw halo2 operations to unify slight differences in API for halo2-axiom and halo2-pse
 pub mod halo2;
+/// Serialization of [AssignedValue](crate::AssignedValue), [ContextCell](crate::ContextCell) and [ContextTag](crate::ContextTag)
+#[cfg(feature = "serde")]
+pub mod serialization;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod testing;
 
@@ -162,6 +167,114 @@ pub fn log2_ceil(x: u64) -> usize {
     (u64::BITS - x.leading_zeros()) as usize - usize::from(x.is_power_of_two())
 }
 
+/// Exponents up to this bound get a shortest star addition chain by exhaustive search in [addition_chain].
+const ADDITION_CHAIN_SEARCH_LIMIT: u64 = 1 << 10;
+
+/// Returns a short addition chain for `exp` as a list of steps.
+///
+/// The chain starts as `[1]` and step `k` appends `chain[i] + chain[j]` for `steps[k] = (i, j)`, so the
+/// last element of the chain is `exp`. Each step costs one multiplication when the chain is used to exponentiate.
+///
+/// The shortest sliding window chain over window sizes `1..=6` is always computed. For `exp` up to
+/// `ADDITION_CHAIN_SEARCH_LIMIT` it is then replaced by a shortest star chain (every step adds the previous
+/// element), found by depth-first search, which is optimal for all such exponents.
+/// * `exp`: the exponent, must be nonzero
+pub fn addition_chain(exp: u64) -> Vec<(usize, usize)> {
+    assert_ne!(exp, 0, "addition chains start at 1");
+    let mut best =
+        (1..=6).map(|window| sliding_window_chain(exp, window)).min_by_key(Vec::len).unwrap();
+    if exp <= ADDITION_CHAIN_SEARCH_LIMIT {
+        loop {
+            let (mut chain, mut steps) = (vec![1], vec![]);
+            if best.is_empty() || !star_chain_search(exp, best.len() - 1, &mut chain, &mut steps) {
+                break;
+            }
+            best = steps;
+        }
+    }
+    best
+}
+
+/// Extends `chain` to a star chain ending in `exp` with at most `max_len` steps in total, returning whether one exists.
+fn star_chain_search(
+    exp: u64,
+    max_len: usize,
+    chain: &mut Vec<u64>,
+    steps: &mut Vec<(usize, usize)>,
+) -> bool {
+    let last = *chain.last().unwrap();
+    if last == exp {
+        return true;
+    }
+    // each remaining step can at most double the largest element
+    let remaining = max_len - steps.len();
+    if remaining == 0 || last.checked_shl(remaining as u32).map_or(false, |bound| bound < exp) {
+        return false;
+    }
+    let top = chain.len() - 1;
+    for i in (0..chain.len()).rev() {
+        let next = last + chain[i];
+        if next > exp {
+            continue;
+        }
+        chain.push(next);
+        steps.push((top, i));
+        if star_chain_search(exp, max_len, chain, steps) {
+            return true;
+        }
+        chain.pop();
+        steps.pop();
+    }
+    false
+}
+
+/// Left-to-right sliding window addition chain for `exp`, precomputing the odd powers below 2<sup>`window`</sup>.
+fn sliding_window_chain(exp: u64, window: usize) -> Vec<(usize, usize)> {
+    let top = bit_length(exp) - 1;
+    let window = window.min(top + 1);
+    let mut steps = vec![];
+    // odd_powers[k] is the chain index of 2k + 1
+    let mut odd_powers = vec![0];
+    if window > 1 {
+        steps.push((0, 0));
+        let square = steps.len();
+        for _ in 1..(1 << (window - 1)) {
+            steps.push((*odd_powers.last().unwrap(), square));
+            odd_powers.push(steps.len());
+        }
+    }
+    let mut acc: Option<usize> = None;
+    let mut hi = top as isize;
+    while hi >= 0 {
+        if (exp >> hi) & 1 == 0 {
+            let a = acc.unwrap();
+            steps.push((a, a));
+            acc = Some(steps.len());
+            hi -= 1;
+            continue;
+        }
+        let mut lo = (hi - window as isize + 1).max(0);
+        while (exp >> lo) & 1 == 0 {
+            lo += 1;
+        }
+        let digit = (exp >> lo) & ((1 << (hi - lo + 1)) - 1);
+        let power = odd_powers[(digit >> 1) as usize];
+        acc = Some(match acc {
+            None => power,
+            Some(mut a) => {
+                for _ in lo..=hi {
+                    steps.push((a, a));
+                    a = steps.len();
+                }
+                steps.push((a, power));
+                steps.len()
+            }
+        });
+        hi = lo - 1;
+    }
+    steps
+}
+
 /// Returns the modulus of [BigPrimeField].
 pub fn modulus<F: BigPrimeField>() -> BigUint {
     fe_to_biguint(&-F::ONE) + 1u64
@@ -535,6 +648,24 @@ mod tests {
         assert_eq!(log2_ceil(0), 0);
     }
 
+    #[test]
+    fn test_addition_chain() {
+        let eval = |exp: u64| {
+            let mut chain = vec![1u64];
+            for (i, j) in addition_chain(exp) {
+                chain.push(chain[i] + chain[j]);
+            }
+            *chain.last().unwrap()
+        };
+        for exp in (1..2048).chain([u64::MAX, 0xffff_0000_ffff, 1 << 63]) {
+            assert_eq!(eval(exp), exp);
+        }
+        // known optimal chain lengths
+        for (exp, len) in [(1, 0), (3, 2), (5, 3), (15, 5), (127, 10), (255, 10), (1024, 10)] {
+            assert_eq!(addition_chain(exp).len(), len, "exp = {exp}");
+        }
+    }
+
     #[test]
     fn test_get_lower_32() {
         let mut rng = StdRng::seed_from_u64(0);
@@ -0,0 +1,221 @@
+//! A [TypeId] has no stable representation, so the virtual region of a [ContextCell](crate::ContextCell) is serialized
+//! by a stable name instead. Virtual region managers register the names of their regions with [register_region], and
+//! the reading binary maps each name back to its own [TypeId], so it must register the same regions before deserializing.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::halo2_proofs::{circuit::Cell, plonk::Assigned};
+use crate::utils::ScalarField;
+use crate::ContextTag;
+
+/// Names of the registered virtual regions, in both directions.
+#[derive(Default)]
+struct RegionRegistry {
+    names: HashMap<TypeId, String>,
+    type_ids: HashMap<String, TypeId>,
+}
+
+impl RegionRegistry {
+    fn insert(&mut self, type_id: TypeId, name: String) -> Result<(), String> {
+        if let Some(registered) = self.names.get(&type_id) {
+            if registered != &name {
+                return Err(format!(
+                    "virtual region {registered} is already registered, not as {name}"
+                ));
+            }
+            return Ok(());
+        }
+        if self.type_ids.contains_key(&name) {
+            return Err(format!("virtual region name {name} is already registered"));
+        }
+        self.type_ids.insert(name.clone(), type_id);
+        self.names.insert(type_id, name);
+        Ok(())
+    }
+}
+
+fn regions() -> &'static Mutex<RegionRegistry> {
+    static REGIONS: OnceLock<Mutex<RegionRegistry>> = OnceLock::new();
+    REGIONS.get_or_init(|| {
+        let mut regions = RegionRegistry::default();
+        // external cells, see `CopyConstraintManager::load_external_cell`
+        regions.insert(TypeId::of::<Cell>(), "external".to_string()).unwrap();
+        Mutex::new(regions)
+    })
+}
+
+/// Registers `name` as the stable identifier of the virtual region with `type_id`, so that the [ContextCell](crate::ContextCell)s
+/// and [ContextTag]s of the region can be serialized. Registering the same pair again has no effect.
+///
+/// [SinglePhaseCoreManager](crate::gates::flex_gate::threads::SinglePhaseCoreManager) registers its regions itself.
+///
+/// Panics if `type_id` or `name` is already registered with a different counterpart.
+pub fn register_region(type_id: TypeId, name: impl Into<String>) {
+    // panic only after releasing the lock, so that the registry stays usable
+    let result = regions().lock().unwrap().insert(type_id, name.into());
+    if let Err(err) = result {
+        panic!("{err}");
+    }
+}
+
+/// `serde(with)` module for the [TypeId] of a virtual region, serialized as the name it was registered with by [register_region].
+pub mod region_type_id {
+    use super::*;
+
+    /// Serializes `type_id` as its registered name, failing if the region is not registered.
+    pub fn serialize<S: Serializer>(type_id: &TypeId, serializer: S) -> Result<S::Ok, S::Error> {
+        let regions = regions().lock().unwrap();
+        let name = regions
+            .names
+            .get(type_id)
+            .ok_or_else(|| serde::ser::Error::custom("virtual region is not registered"))?;
+        name.serialize(serializer)
+    }
+
+    /// Deserializes the [TypeId] of a virtual region from its registered name, failing if no region has this name.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TypeId, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        let regions = regions().lock().unwrap();
+        regions
+            .type_ids
+            .get(&name)
+            .copied()
+            .ok_or_else(|| D::Error::custom(format!("virtual region {name} is not registered")))
+    }
+}
+
+/// `serde(with)` module for a [ContextTag], which cannot implement [Serialize] as a tuple of foreign types.
+pub mod context_tag {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct ContextTagRepr(#[serde(with = "region_type_id")] TypeId, usize);
+
+    /// Serializes `tag` as the pair of its region name and context id.
+    pub fn serialize<S: Serializer>(tag: &ContextTag, serializer: S) -> Result<S::Ok, S::Error> {
+        ContextTagRepr(tag.0, tag.1).serialize(serializer)
+    }
+
+    /// Deserializes a [ContextTag], mapping its region name back to the registered [TypeId].
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ContextTag, D::Error> {
+        let ContextTagRepr(type_id, context_id) = ContextTagRepr::deserialize(deserializer)?;
+        Ok((type_id, context_id))
+    }
+}
+
+/// `serde(with)` module for an [Assigned] value, whose field elements are serialized as little-endian bytes.
+pub mod assigned {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    enum AssignedRepr {
+        Zero,
+        Trivial(Vec<u8>),
+        Rational(Vec<u8>, Vec<u8>),
+    }
+
+    fn fe_from_bytes_le<F: ScalarField, E: Error>(bytes: &[u8]) -> Result<F, E> {
+        let mut repr = F::Repr::default();
+        if bytes.len() != repr.as_ref().len() {
+            return Err(E::invalid_length(bytes.len(), &"the byte length of a field element"));
+        }
+        repr.as_mut().copy_from_slice(bytes);
+        Option::from(F::from_repr(repr)).ok_or_else(|| E::custom("non-canonical field element"))
+    }
+
+    /// Serializes `value` keeping its variant, with field elements as little-endian bytes.
+    pub fn serialize<F: ScalarField, S: Serializer>(
+        value: &Assigned<F>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let repr = match value {
+            Assigned::Zero => AssignedRepr::Zero,
+            Assigned::Trivial(a) => AssignedRepr::Trivial(a.to_bytes_le()),
+            Assigned::Rational(a, b) => AssignedRepr::Rational(a.to_bytes_le(), b.to_bytes_le()),
+        };
+        repr.serialize(serializer)
+    }
+
+    /// Deserializes an [Assigned] value, failing on non-canonical field elements.
+    pub fn deserialize<'de, F: ScalarField, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Assigned<F>, D::Error> {
+        Ok(match AssignedRepr::deserialize(deserializer)? {
+            AssignedRepr::Zero => Assigned::Zero,
+            AssignedRepr::Trivial(a) => Assigned::Trivial(fe_from_bytes_le(&a)?),
+            AssignedRepr::Rational(a, b) => {
+                Assigned::Rational(fe_from_bytes_le(&a)?, fe_from_bytes_le(&b)?)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::halo2_proofs::halo2curves::bn256::Fr;
+    use crate::{AssignedValue, Context, ContextCell};
+
+    fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> T {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    fn test_region() -> TypeId {
+        let type_id = TypeId::of::<Context<Fr>>();
+        register_region(type_id, "test");
+        type_id
+    }
+
+    #[test]
+    fn test_assigned_value_round_trip() {
+        let cell = Some(ContextCell::new(test_region(), 3, 17));
+        for value in [
+            Assigned::Zero,
+            Assigned::Trivial(Fr::from(42)),
+            Assigned::Trivial(-Fr::from(1)),
+            Assigned::Rational(Fr::from(1), Fr::from(3)),
+        ] {
+            for cell in [cell, None] {
+                let assigned = AssignedValue { value, cell };
+                let decoded = round_trip(&assigned);
+                // `Assigned` compares by evaluation, so also check that the variant is kept
+                assert_eq!(format!("{:?}", decoded.value), format!("{value:?}"));
+                assert_eq!(decoded.cell, cell);
+            }
+        }
+    }
+
+    #[test]
+    fn test_context_tag_round_trip() {
+        #[derive(Serialize, Deserialize)]
+        struct Tagged(#[serde(with = "context_tag")] ContextTag);
+
+        let tag = (test_region(), 5);
+        assert_eq!(round_trip(&Tagged(tag)).0, tag);
+        assert_eq!(serde_json::to_string(&Tagged(tag)).unwrap(), "[\"test\",5]");
+    }
+
+    #[test]
+    fn test_unregistered_region() {
+        let cell = ContextCell::new(TypeId::of::<u8>(), 0, 0);
+        assert!(serde_json::to_string(&cell).is_err());
+        let json = "{\"type_id\":\"unknown\",\"context_id\":0,\"offset\":0}";
+        assert!(serde_json::from_str::<ContextCell>(json).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "virtual region test is already registered, not as other")]
+    fn test_register_region_twice() {
+        register_region(test_region(), "other");
+    }
+
+    #[test]
+    fn test_non_canonical_field_element() {
+        let json = format!("{{\"value\":{{\"Trivial\":{:?}}},\"cell\":null}}", [0xffu8; 32]);
+        assert!(serde_json::from_str::<AssignedValue<Fr>>(&json).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use crate::utils::ScalarField;
+use crate::{Context, ContextCell};
+
+use super::copy_constraints::CopyConstraintManager;
+
+/// Graph of the copy constraints of a virtual circuit, for visualization and analysis.
+///
+/// Nodes are virtual advice cells and edges are equality constraints between them. Cells constrained to the same
+/// constant are also in the same component, since they are equal in any satisfying assignment. Gate constraints
+/// are not edges, so a cell that is only used by gates in its own [Context] is an isolated node.
+#[derive(Clone, Debug)]
+pub struct ConstraintGraph<F: ScalarField> {
+    /// Virtual advice cells, in order of their [Context], followed by external cells.
+    pub nodes: Vec<ContextCell>,
+    /// Equality constraints between advice cells, as pairs of indices into `nodes`.
+    pub edges: Vec<(usize, usize)>,
+    /// Equality constraints between constants and advice cells, as pairs of constant and index into `nodes`.
+    pub constants: Vec<(F, usize)>,
+    index: HashMap<ContextCell, usize>,
+}
+
+impl<F: ScalarField> ConstraintGraph<F> {
+    /// Builds the graph of all cells in `contexts` and all constraints in `copy_mgr`.
+    ///
+    /// Cells referenced by `copy_mgr` that do not belong to any of `contexts` (e.g., external cells) are added as nodes.
+    pub fn build_from_contexts(
+        contexts: &[Context<F>],
+        copy_mgr: &CopyConstraintManager<F>,
+    ) -> Self {
+        let mut graph =
+            Self { nodes: vec![], edges: vec![], constants: vec![], index: HashMap::new() };
+        for ctx in contexts {
+            for offset in 0..ctx.advice_len() {
+                graph.node(ContextCell::new(ctx.type_id(), ctx.id(), offset));
+            }
+        }
+        for &(left, right) in &copy_mgr.advice_equalities {
+            let edge = (graph.node(left), graph.node(right));
+            graph.edges.push(edge);
+        }
+        for &(c, cell) in &copy_mgr.constant_equalities {
+            let node = graph.node(cell);
+            graph.constants.push((c, node));
+        }
+        graph
+    }
+
+    /// Returns the index of `cell` in `self.nodes`, adding it if it is not present.
+    fn node(&mut self, cell: ContextCell) -> usize {
+        *self.index.entry(cell).or_insert_with(|| {
+            self.nodes.push(cell);
+            self.nodes.len() - 1
+        })
+    }
+
+    /// Returns the index in `self.nodes` of `cell`, if it is a node.
+    pub fn node_index(&self, cell: &ContextCell) -> Option<usize> {
+        self.index.get(cell).copied()
+    }
+
+    /// Returns the size of each connected component.
+    fn component_sizes(&self) -> Vec<usize> {
+        let mut parent = (0..self.nodes.len()).collect::<Vec<_>>();
+        let mut first_with_constant = BTreeMap::new();
+        let constant_edges = self
+            .constants
+            .iter()
+            .map(|&(c, node)| (*first_with_constant.entry(c).or_insert(node), node))
+            .collect::<Vec<_>>();
+        for &(a, b) in self.edges.iter().chain(&constant_edges) {
+            let (a, b) = (find(&mut parent, a), find(&mut parent, b));
+            parent[a] = b;
+        }
+        let mut sizes = HashMap::new();
+        for node in 0..self.nodes.len() {
+            *sizes.entry(find(&mut parent, node)).or_insert(0) += 1;
+        }
+        sizes.into_values().collect()
+    }
+
+    /// Returns the number of connected components, i.e., groups of cells that are not constrained to each other.
+    pub fn connected_components(&self) -> usize {
+        self.component_sizes().len()
+    }
+
+    /// Returns the number of cells in the largest connected component, or 0 if the graph is empty.
+    pub fn max_component_size(&self) -> usize {
+        self.component_sizes().into_iter().max().unwrap_or(0)
+    }
+
+    /// Returns the graph in the Graphviz DOT format.
+    ///
+    /// Nodes are labeled `context_id:offset`, together with the constant they are constrained to, if any.
+    pub fn to_dot(&self) -> String {
+        let mut labels =
+            self.nodes.iter().map(|c| format!("{}:{}", c.context_id, c.offset)).collect::<Vec<_>>();
+        for (c, node) in &self.constants {
+            write!(labels[*node], "\\n= {c:?}").unwrap();
+        }
+        let mut dot = String::from("graph constraints {\n");
+        for (i, label) in labels.iter().enumerate() {
+            writeln!(dot, "    n{i} [label=\"{label}\"];").unwrap();
+        }
+        for (a, b) in &self.edges {
+            writeln!(dot, "    n{a} -- n{b};").unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Returns the root of `node` in the union-find forest `parent`, halving paths along the way.
+fn find(parent: &mut [usize], mut node: usize) -> usize {
+    while parent[node] != node {
+        parent[node] = parent[parent[node]];
+        node = parent[node];
+    }
+    node
+}
@@ -11,10 +11,10 @@ use crate::halo2_proofs::{
     plonk::{Assigned, Column, Fixed},
 };
 use crate::utils::halo2::{raw_assign_fixed, raw_constrain_equal, Halo2AssignedCell};
-use crate::AssignedValue;
 use crate::{ff::Field, ContextCell};
+use crate::{AssignedValue, ContextTag};
 
-use super::manager::VirtualRegionManager;
+use super::manager::{CircuitStats, VirtualRegionManager};
 
 /// Thread-safe shared global manager for all copy constraints.
 pub type SharedCopyConstraintManager<F> = Arc<Mutex<CopyConstraintManager<F>>>;
@@ -37,6 +37,10 @@ pub struct CopyConstraintManager<F: Field + Ord> {
 
     external_cell_count: usize,
 
+    /// Challenge phase of every [Context](crate::Context) created with this manager, keyed by [ContextTag].
+    /// Only populated when debug assertions are enabled, to check that no cell references a cell from a later phase.
+    pub context_phases: HashMap<ContextTag, usize>,
+
     // In circuit assignments
     /// Advice assignments, mapping from virtual [ContextCell] to assigned physical [Cell]
     pub assigned_advices: HashMap<ContextCell, Cell>,
@@ -47,11 +51,41 @@ pub struct CopyConstraintManager<F: Field + Ord> {
 }
 
 impl<F: Field + Ord> CopyConstraintManager<F> {
+    /// Returns the number of equality constraints between pairs of virtual advice cells added so far.
+    pub fn count_advice_equalities(&self) -> usize {
+        self.advice_equalities.len()
+    }
+
+    /// Returns the number of equality constraints between virtual advice cells and constants added so far.
+    pub fn count_constant_equalities(&self) -> usize {
+        self.constant_equalities.len()
+    }
+
     /// Returns the number of distinct constants used.
     pub fn num_distinct_constants(&self) -> usize {
         self.constant_equalities.iter().map(|(x, _)| x).sorted().dedup().count()
     }
 
+    /// Returns `(num_copy_constraints, num_constants)` of the virtual regions with a `type_id` in `type_ids`.
+    ///
+    /// An advice equality is attributed to the region of its first cell, and a constant equality to the region of its
+    /// advice cell.
+    pub fn count_region_equalities(&self, type_ids: &[TypeId]) -> (usize, usize) {
+        let num_advice_equalities = self
+            .advice_equalities
+            .iter()
+            .filter(|(cell, _)| type_ids.contains(&cell.type_id))
+            .count();
+        let constants = self
+            .constant_equalities
+            .iter()
+            .filter(|(_, cell)| type_ids.contains(&cell.type_id))
+            .map(|(c, _)| c)
+            .collect_vec();
+        let num_copy_constraints = num_advice_equalities + constants.len();
+        (num_copy_constraints, constants.into_iter().sorted().dedup().count())
+    }
+
     /// Adds external raw [Halo2AssignedCell] to `self.assigned_advices` and returns a new virtual [AssignedValue]
     /// that can be used in any virtual region. No copy constraint is imposed, as the virtual cell "points" to the
     /// raw assigned cell. The returned [ContextCell] will have `type_id` the `TypeId::of::<Cell>()`.
@@ -95,6 +129,45 @@ impl<F: Field + Ord> CopyConstraintManager<F> {
         context_cell
     }
 
+    /// Moves all copy constraints of `other` into `self`, when the virtual region `type_id` of `other` is appended
+    /// after the one of `self`.
+    ///
+    /// Cells of `other` in the region `type_id` have their `context_id` shifted by `context_id_offset`, and external
+    /// cells are renumbered after the external cells of `self`. `other` is left cleared.
+    ///
+    /// # Panics
+    /// If `other` has cells in any other virtual region, or if either manager has already been assigned.
+    pub fn append(&mut self, other: &mut Self, type_id: TypeId, context_id_offset: usize) {
+        assert!(
+            self.assigned.get().is_none() && other.assigned.get().is_none(),
+            "cannot append to or from an assigned copy manager"
+        );
+        let external_cell_offset = self.external_cell_count;
+        let renumber = |cell: ContextCell| {
+            if cell.type_id == type_id {
+                ContextCell::new(type_id, cell.context_id + context_id_offset, cell.offset)
+            } else if cell.type_id == TypeId::of::<Cell>() {
+                ContextCell::new(cell.type_id, cell.context_id, cell.offset + external_cell_offset)
+            } else {
+                panic!("cells outside of the merged virtual region cannot be appended")
+            }
+        };
+        self.advice_equalities
+            .extend(other.advice_equalities.drain(..).map(|(a, b)| (renumber(a), renumber(b))));
+        self.constant_equalities
+            .extend(other.constant_equalities.drain(..).map(|(c, cell)| (c, renumber(cell))));
+        self.context_phases.extend(other.context_phases.drain().map(
+            |((tag_type_id, id), phase)| {
+                let cell = renumber(ContextCell::new(tag_type_id, id, 0));
+                ((cell.type_id, cell.context_id), phase)
+            },
+        ));
+        self.assigned_advices
+            .extend(other.assigned_advices.drain().map(|(cell, raw)| (renumber(cell), raw)));
+        self.external_cell_count += other.external_cell_count;
+        other.clear();
+    }
+
     /// Clears state
     pub fn clear(&mut self) {
         self.advice_equalities.clear();
@@ -102,10 +175,30 @@ impl<F: Field + Ord> CopyConstraintManager<F> {
         self.assigned_advices.clear();
         self.assigned_constants.clear();
         self.external_cell_count = 0;
+        self.context_phases.clear();
         self.assigned.take();
     }
 }
 
+/// Counting methods of [CopyConstraintManager] for a [SharedCopyConstraintManager], which take the lock
+/// only for the duration of the call.
+pub trait SharedCopyConstraintCounts {
+    /// See [CopyConstraintManager::count_advice_equalities].
+    fn count_advice_equalities(&self) -> usize;
+    /// See [CopyConstraintManager::count_constant_equalities].
+    fn count_constant_equalities(&self) -> usize;
+}
+
+impl<F: Field + Ord> SharedCopyConstraintCounts for SharedCopyConstraintManager<F> {
+    fn count_advice_equalities(&self) -> usize {
+        self.lock().unwrap().count_advice_equalities()
+    }
+
+    fn count_constant_equalities(&self) -> usize {
+        self.lock().unwrap().count_constant_equalities()
+    }
+}
+
 impl<F: Field + Ord> Drop for CopyConstraintManager<F> {
     fn drop(&mut self) {
         if self.assigned.get().is_some() {
@@ -170,4 +263,16 @@ impl<F: Field + Ord> VirtualRegionManager<F> for SharedCopyConstraintManager<F>
         // so the second run still assigns constants in the pk
         manager.assigned_constants.clear();
     }
+
+    /// Counts every copy constraint and distinct constant, across all virtual regions. The copy manager does not
+    /// assign advice cells or enable selectors itself.
+    fn statistics(&self) -> CircuitStats {
+        let manager = self.lock().unwrap();
+        CircuitStats {
+            num_copy_constraints: manager.count_advice_equalities()
+                + manager.count_constant_equalities(),
+            num_constants: manager.num_distinct_constants(),
+            ..Default::default()
+        }
+    }
 }
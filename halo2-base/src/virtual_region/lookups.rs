@@ -12,7 +12,7 @@ use crate::utils::halo2::raw_assign_advice;
 use crate::{AssignedValue, ContextTag};
 
 use super::copy_constraints::SharedCopyConstraintManager;
-use super::manager::VirtualRegionManager;
+use super::manager::{CircuitStats, VirtualRegionManager};
 
 /// A manager that can be used for any lookup argument. This manager automates
 /// the process of copying cells to designed advice columns with lookup enabled.
@@ -73,6 +73,28 @@ impl<F: Field + Ord, const ADVICE_COLS: usize> LookupAnyManager<F, ADVICE_COLS>
             .or_insert(vec![cells]);
     }
 
+    /// Add multiple lookup arguments to the manager, taking the lock only once.
+    pub fn add_lookups(
+        &self,
+        tag: ContextTag,
+        cells: impl IntoIterator<Item = [AssignedValue<F>; ADVICE_COLS]>,
+    ) {
+        self.cells_to_lookup.lock().unwrap().entry(tag).or_default().extend(cells);
+    }
+
+    /// Returns the number of lookups added so far by the [Context](crate::Context) with `tag`,
+    /// to later remove the ones added after it with [LookupAnyManager::rollback].
+    pub fn snapshot(&self, tag: ContextTag) -> usize {
+        self.cells_to_lookup.lock().unwrap().get(&tag).map_or(0, Vec::len)
+    }
+
+    /// Removes the lookups added by the [Context](crate::Context) with `tag` after it had added `len` lookups.
+    pub fn rollback(&self, tag: ContextTag, len: usize) {
+        if let Some(cells) = self.cells_to_lookup.lock().unwrap().get_mut(&tag) {
+            cells.truncate(len);
+        }
+    }
+
     /// The total number of virtual rows needed to special lookups
     pub fn total_rows(&self) -> usize {
         self.cells_to_lookup.lock().unwrap().iter().flat_map(|(_, advices)| advices).count()
@@ -149,4 +171,15 @@ impl<F: Field + Ord, const ADVICE_COLS: usize> VirtualRegionManager<F>
         // We cannot clear `cells_to_lookup` because keygen_vk and keygen_pk both call this function
         let _ = self.assigned.set(());
     }
+
+    /// Every cell to look up is copied to a lookup-enabled advice cell and, unless `witness_gen_only`, constrained to
+    /// equal the original cell. The lookup is always on, so no selectors are enabled.
+    fn statistics(&self) -> CircuitStats {
+        let num_advice_cells = self.total_rows() * ADVICE_COLS;
+        CircuitStats {
+            num_advice_cells,
+            num_copy_constraints: if self.witness_gen_only { 0 } else { num_advice_cells },
+            ..Default::default()
+        }
+    }
 }
@@ -13,4 +13,22 @@ pub trait VirtualRegionManager<F: Field> {
 
     /// Assign virtual region this is in charge of to the raw region described by `config`.
     fn assign_raw(&self, config: &Self::Config, region: &mut Region<F>) -> Self::Assignment;
+
+    /// Returns a [CircuitStats] summary of the virtual region this is in charge of, which can be called before `assign_raw`.
+    fn statistics(&self) -> CircuitStats;
+}
+
+/// Summary of the cost of a virtual region, which can be computed before the circuit is synthesized.
+///
+/// Selectors and copy constraints are only recorded when `witness_gen_only` is false, so they are `0` otherwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CircuitStats {
+    /// Number of virtual advice cells assigned
+    pub num_advice_cells: usize,
+    /// Number of enabled selectors, i.e., gates
+    pub num_selector_on: usize,
+    /// Number of equality constraints between two advice cells or between an advice cell and a constant
+    pub num_copy_constraints: usize,
+    /// Number of distinct constants used
+    pub num_constants: usize,
 }
@@ -4,12 +4,20 @@
 //! Currently a raw region refers to a subset of columns of the circuit, and spans all rows (so it is a vertical region),
 //! but this is not a requirement of the trait.
 
+/// Graph of copy constraints for visualization and analysis
+pub mod constraint_graph;
 /// Shared copy constraints across different virtual regions
 pub mod copy_constraints;
 /// Virtual region manager for lookup tables
 pub mod lookups;
 /// Virtual region manager
 pub mod manager;
+/// Reuse of cleared contexts
+pub mod recycler;
+/// Virtual region manager laying out a single phase in a single advice column
+pub mod single_column;
+
+pub use single_column::SinglePhaseSingleColumnRegion;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,74 @@
+use std::{any::TypeId, collections::HashMap};
+
+use crate::{utils::ScalarField, Context, ContextSnapshot};
+
+use super::copy_constraints::SharedCopyConstraintManager;
+
+/// Pool of cleared [Context]s whose `advice` and `selector` allocations can be reused.
+///
+/// Useful for circuits that repeatedly build sub-circuits of the same size in throwaway contexts.
+/// Every checked out [Context] gets a fresh `context_id`, so cells from different checkouts never alias.
+#[derive(Clone, Debug)]
+pub struct ContextRecycler<F: ScalarField> {
+    /// Cleared contexts available for reuse.
+    pub recycled: Vec<Context<F>>,
+    witness_gen_only: bool,
+    phase: usize,
+    type_id: TypeId,
+    next_context_id: usize,
+    copy_manager: SharedCopyConstraintManager<F>,
+    /// Snapshot of each checked out context, taken while it was still empty.
+    checked_out: HashMap<usize, ContextSnapshot<F>>,
+}
+
+impl<F: ScalarField> ContextRecycler<F> {
+    /// Creates an empty [ContextRecycler]. All contexts it hands out have the given `phase` and `type_id`,
+    /// with context ids counting up from 0. `type_id` should not be shared with any other virtual region.
+    pub fn new(
+        witness_gen_only: bool,
+        phase: usize,
+        type_id: TypeId,
+        copy_manager: SharedCopyConstraintManager<F>,
+    ) -> Self {
+        Self {
+            recycled: vec![],
+            witness_gen_only,
+            phase,
+            type_id,
+            next_context_id: 0,
+            copy_manager,
+            checked_out: HashMap::new(),
+        }
+    }
+
+    /// Returns an empty [Context], reusing the allocations of a recycled context if one is available.
+    pub fn checkout(&mut self) -> Context<F> {
+        let mut ctx = Context::new(
+            self.witness_gen_only,
+            self.phase,
+            self.type_id,
+            self.next_context_id,
+            self.copy_manager.clone(),
+        );
+        self.next_context_id += 1;
+        if let Some(mut old) = self.recycled.pop() {
+            let (advice, selector) = old.replace_columns(vec![], vec![]);
+            ctx.replace_columns(advice, selector);
+        }
+        self.checked_out.insert(ctx.id(), ctx.snapshot());
+        ctx
+    }
+
+    /// Clears `ctx` and stores it for reuse. The capacity of its `advice` and `selector` columns is retained.
+    ///
+    /// The copy constraints on cells of `ctx` are removed from the shared copy manager, since those cells will never be assigned.
+    /// Panics if `ctx` was not checked out from this recycler.
+    pub fn checkin(&mut self, mut ctx: Context<F>) {
+        let snapshot = self
+            .checked_out
+            .remove(&ctx.id())
+            .expect("context was not checked out from this recycler");
+        ctx.rollback(snapshot);
+        self.recycled.push(ctx);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    gates::flex_gate::{threads::SinglePhaseCoreManager, BasicGateConfig, FlexGateConfigParams},
+    halo2_proofs::circuit::Region,
+    utils::ScalarField,
+    virtual_region::{
+        copy_constraints::SharedCopyConstraintManager,
+        manager::{CircuitStats, VirtualRegionManager},
+    },
+    Context,
+};
+
+/// Virtual region manager for a single challenge phase that lays out all of its [Context]s, one after another,
+/// in a single advice column of the basic gate.
+///
+/// This is the narrowest possible circuit, so the prover holds the fewest columns in memory, e.g. in WASM, at the
+/// cost of needing `2^k` rows for all cells of the region.
+#[derive(Clone, Debug, Default)]
+pub struct SinglePhaseSingleColumnRegion<F: ScalarField> {
+    /// Manager of the [Context]s of this region.
+    pub core: SinglePhaseCoreManager<F>,
+}
+
+impl<F: ScalarField> SinglePhaseSingleColumnRegion<F> {
+    /// Creates a new [SinglePhaseSingleColumnRegion] in phase 0, see [SinglePhaseCoreManager::new].
+    pub fn new(witness_gen_only: bool, copy_manager: SharedCopyConstraintManager<F>) -> Self {
+        Self { core: SinglePhaseCoreManager::new(witness_gen_only, copy_manager) }
+    }
+
+    /// Returns a mutable reference to the [Context] of the last thread, see [SinglePhaseCoreManager::main].
+    pub fn main(&mut self) -> &mut Context<F> {
+        self.core.main()
+    }
+
+    /// Returns the smallest `k` such that all advice cells fit in a single column of `2^k` rows,
+    /// of which the last `minimum_rows` cannot be used, e.g. for blinding factors.
+    pub fn min_k(&self, minimum_rows: usize) -> usize {
+        (self.core.total_advice() + minimum_rows).next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// Returns the [FlexGateConfigParams] of the smallest circuit for this region: degree [`Self::min_k`],
+    /// a single advice column in phase 0, and enough fixed columns for the constants.
+    pub fn config_params(&self, minimum_rows: usize) -> FlexGateConfigParams {
+        let k = self.min_k(minimum_rows);
+        let usable_rows = (1 << k) - minimum_rows;
+        let num_constants = self.core.copy_manager.lock().unwrap().num_distinct_constants();
+        FlexGateConfigParams {
+            k,
+            num_advice_per_phase: vec![1],
+            num_fixed: (num_constants + usable_rows - 1) / usable_rows,
+        }
+    }
+}
+
+impl<F: ScalarField> VirtualRegionManager<F> for SinglePhaseSingleColumnRegion<F> {
+    type Config = (BasicGateConfig<F>, usize); // usize = usable_rows
+
+    fn assign_raw(&self, (config, usable_rows): &Self::Config, region: &mut Region<F>) {
+        let total_advice = self.core.total_advice();
+        assert!(
+            total_advice <= *usable_rows,
+            "{total_advice} advice cells do not fit in a single column of {usable_rows} usable rows"
+        );
+        // a single column never breaks, so the break points are known without keygen
+        self.core.break_points.borrow_mut().get_or_insert_with(Vec::new);
+        self.core.assign_raw(&(vec![config.clone()], *usable_rows), region);
+    }
+
+    fn statistics(&self) -> CircuitStats {
+        self.core.statistics()
+    }
+}
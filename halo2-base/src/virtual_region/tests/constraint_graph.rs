@@ -0,0 +1,90 @@
+use std::any::TypeId;
+
+use crate::{
+    halo2_proofs::halo2curves::bn256::Fr,
+    virtual_region::{
+        constraint_graph::ConstraintGraph, copy_constraints::SharedCopyConstraintManager,
+    },
+    Context, ContextCell,
+    QuantumCell::Existing,
+};
+
+#[test]
+fn test_constraint_graph_components() {
+    let copy_manager = SharedCopyConstraintManager::<Fr>::default();
+    let type_id = TypeId::of::<ConstraintGraph<Fr>>();
+    let mut ctx0 = Context::new(false, 0, type_id, 0, copy_manager.clone());
+    let mut ctx1 = Context::new(false, 0, type_id, 1, copy_manager.clone());
+
+    let a = ctx0.load_witness(Fr::from(1));
+    let b = ctx0.load_witness(Fr::from(2));
+    let c = ctx0.load_witness(Fr::from(1));
+    ctx0.load_constant(Fr::from(5));
+    ctx1.assign_cell(Existing(a));
+    ctx1.load_constant(Fr::from(5));
+    let d = ctx1.load_witness(Fr::from(2));
+    let e = ctx1.load_witness(Fr::from(7));
+    ctx1.constrain_equal(&d, &b);
+
+    let graph = {
+        let copy_manager = copy_manager.lock().unwrap();
+        ConstraintGraph::build_from_contexts(&[ctx0.clone(), ctx1.clone()], &copy_manager)
+    };
+    assert_eq!(graph.nodes.len(), 8);
+    assert_eq!(graph.edges.len(), 2);
+    // {a, ctx1[0]}, {b, d}, {c}, {both constants}, {e}
+    assert_eq!(graph.connected_components(), 5);
+    assert_eq!(graph.max_component_size(), 2);
+    assert_eq!(graph.node_index(&e.cell.unwrap()), Some(7));
+
+    // joining `c` to `a` merges two components
+    ctx0.constrain_equal(&c, &a);
+    let graph = {
+        let copy_manager = copy_manager.lock().unwrap();
+        ConstraintGraph::build_from_contexts(&[ctx0, ctx1], &copy_manager)
+    };
+    assert_eq!(graph.connected_components(), 4);
+    assert_eq!(graph.max_component_size(), 3);
+    copy_manager.lock().unwrap().clear();
+}
+
+#[test]
+fn test_constraint_graph_external_cells() {
+    let copy_manager = SharedCopyConstraintManager::<Fr>::default();
+    let mut ctx = Context::new(false, 0, TypeId::of::<Fr>(), 0, copy_manager.clone());
+    let external = copy_manager.lock().unwrap().mock_external_assigned(Fr::from(3));
+    ctx.assign_cell(Existing(external));
+
+    let graph = ConstraintGraph::build_from_contexts(&[ctx], &copy_manager.lock().unwrap());
+    // the external cell is added after the cells of the contexts
+    assert_eq!(graph.nodes.len(), 2);
+    assert_eq!(graph.nodes[1], external.cell.unwrap());
+    assert_eq!(graph.connected_components(), 1);
+    copy_manager.lock().unwrap().clear();
+}
+
+#[test]
+fn test_constraint_graph_to_dot() {
+    let copy_manager = SharedCopyConstraintManager::<Fr>::default();
+    let mut ctx = Context::new(false, 0, TypeId::of::<Fr>(), 3, copy_manager.clone());
+    let a = ctx.load_witness(Fr::from(1));
+    ctx.assign_cell(Existing(a));
+    ctx.load_constant(Fr::from(0));
+
+    let graph = ConstraintGraph::build_from_contexts(&[ctx], &copy_manager.lock().unwrap());
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("graph constraints {\n"));
+    assert!(dot.contains("n0 [label=\"3:0\"];"));
+    assert!(dot.contains("n2 [label=\"3:2\\n= "));
+    assert!(dot.contains("n1 -- n0;"));
+    assert!(dot.ends_with("}\n"));
+    assert_eq!(graph.node_index(&ContextCell::new(TypeId::of::<Fr>(), 3, 5)), None);
+    copy_manager.lock().unwrap().clear();
+}
+
+#[test]
+fn test_constraint_graph_empty() {
+    let graph = ConstraintGraph::<Fr>::build_from_contexts(&[], &Default::default());
+    assert_eq!(graph.connected_components(), 0);
+    assert_eq!(graph.max_component_size(), 0);
+}
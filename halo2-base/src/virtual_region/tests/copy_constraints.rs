@@ -0,0 +1,41 @@
+use std::any::TypeId;
+
+use crate::{
+    halo2_proofs::halo2curves::bn256::Fr,
+    virtual_region::copy_constraints::{SharedCopyConstraintCounts, SharedCopyConstraintManager},
+    Context,
+    QuantumCell::Existing,
+};
+
+#[test]
+fn test_count_equalities() {
+    let copy_manager = SharedCopyConstraintManager::<Fr>::default();
+    let mut ctx = Context::new(false, 0, TypeId::of::<Fr>(), 0, copy_manager.clone());
+    assert_eq!(copy_manager.count_advice_equalities(), 0);
+    assert_eq!(copy_manager.count_constant_equalities(), 0);
+
+    let a = ctx.load_witness(Fr::from(3));
+    let b = ctx.load_witness(Fr::from(3));
+    ctx.constrain_equal(&a, &b);
+    ctx.assign_cell(Existing(a));
+    ctx.load_constant(Fr::from(5));
+    ctx.load_constant(Fr::from(5));
+    assert_eq!(copy_manager.count_advice_equalities(), 2);
+    assert_eq!(copy_manager.count_constant_equalities(), 2);
+    assert_eq!(copy_manager.lock().unwrap().count_advice_equalities(), 2);
+
+    copy_manager.lock().unwrap().clear();
+    assert_eq!(copy_manager.count_advice_equalities(), 0);
+    assert_eq!(copy_manager.count_constant_equalities(), 0);
+}
+
+#[test]
+fn test_count_equalities_witness_gen_only() {
+    let copy_manager = SharedCopyConstraintManager::<Fr>::default();
+    let mut ctx = Context::new(true, 0, TypeId::of::<Fr>(), 0, copy_manager.clone());
+    let a = ctx.load_witness(Fr::from(3));
+    ctx.assign_cell(Existing(a));
+    ctx.load_constant(Fr::from(5));
+    assert_eq!(copy_manager.count_advice_equalities(), 0);
+    assert_eq!(copy_manager.count_constant_equalities(), 0);
+}
@@ -1 +1,5 @@
+mod constraint_graph;
+mod copy_constraints;
 mod lookups;
+mod recycler;
+mod single_column;
@@ -0,0 +1,43 @@
+use std::any::TypeId;
+
+use crate::{
+    gates::{GateChip, GateInstructions},
+    halo2_proofs::halo2curves::bn256::Fr,
+    virtual_region::{copy_constraints::SharedCopyConstraintManager, recycler::ContextRecycler},
+};
+
+#[test]
+#[allow(deprecated)] // checks the capacity of the `advice` column
+fn test_recycled_context_is_empty() {
+    let copy_manager = SharedCopyConstraintManager::default();
+    let mut recycler = ContextRecycler::<Fr>::new(
+        false,
+        0,
+        TypeId::of::<ContextRecycler<Fr>>(),
+        copy_manager.clone(),
+    );
+    let gate = GateChip::default();
+
+    let mut ctx = recycler.checkout();
+    let a = ctx.load_witness(Fr::from(3));
+    gate.mul(&mut ctx, a, a);
+    ctx.load_zero();
+    let (id, advice_capacity) = (ctx.id(), ctx.advice.capacity());
+    assert!(advice_capacity >= 5);
+    recycler.checkin(ctx);
+
+    let mut ctx = recycler.checkout();
+    assert!(recycler.recycled.is_empty());
+    assert_ne!(ctx.id(), id);
+    assert_eq!(ctx.advice_len(), 0);
+    assert_eq!(ctx.selector_len(), 0);
+    assert_eq!(ctx.advice.capacity(), advice_capacity);
+    // the zero cell is not carried over from the old context
+    let zero = ctx.load_zero();
+    assert_eq!(zero.cell.unwrap().offset, 0);
+    recycler.checkin(ctx);
+    // every constraint was on a cell of a checked in context
+    let copy_manager = copy_manager.lock().unwrap();
+    assert!(copy_manager.advice_equalities.is_empty());
+    assert!(copy_manager.constant_equalities.is_empty());
+}
@@ -0,0 +1,116 @@
+use crate::halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    gates::{
+        flex_gate::{FlexGateConfig, FlexGateConfigParams},
+        GateChip, GateInstructions,
+    },
+    utils::ScalarField,
+    virtual_region::{manager::VirtualRegionManager, SinglePhaseSingleColumnRegion},
+    QuantumCell::Constant,
+};
+
+// halo2 reserves a few rows at the end of every column for blinding factors
+const MINIMUM_ROWS: usize = 10;
+
+struct SingleColumnCircuit<F: ScalarField> {
+    region: SinglePhaseSingleColumnRegion<F>,
+    params: FlexGateConfigParams,
+}
+
+impl<F: ScalarField> Circuit<F> for SingleColumnCircuit<F> {
+    type Config = FlexGateConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = FlexGateConfigParams;
+
+    fn params(&self) -> Self::Params {
+        self.params.clone()
+    }
+
+    fn without_witnesses(&self) -> Self {
+        unimplemented!()
+    }
+
+    fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
+        let k = params.k;
+        let mut config = FlexGateConfig::configure(meta, params);
+        config.max_rows = (1 << k) - meta.minimum_rows();
+        config
+    }
+
+    fn configure(_: &mut ConstraintSystem<F>) -> Self::Config {
+        unreachable!()
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "single column",
+            |mut region| {
+                self.region
+                    .assign_raw(&(config.basic_gates[0][0].clone(), config.max_rows), &mut region);
+                self.region.core.copy_manager.assign_raw(&config.constants, &mut region);
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Assigns `num_muls` chained multiplications, spread over two threads.
+fn mul_chain(num_muls: usize) -> SinglePhaseSingleColumnRegion<Fr> {
+    let gate = GateChip::default();
+    let mut region = SinglePhaseSingleColumnRegion::new(false, Default::default());
+    let mut acc = region.main().load_witness(Fr::from(3));
+    for i in 0..num_muls {
+        if i == num_muls / 2 {
+            region.core.new_thread();
+        }
+        let ctx = region.main();
+        acc = gate.mul(ctx, acc, acc);
+        acc = gate.add(ctx, acc, Constant(Fr::from(i as u64)));
+    }
+    region
+}
+
+#[test]
+fn test_single_column_region() {
+    let region = mul_chain(100);
+    assert_eq!(region.core.total_advice(), 1 + 100 * 8);
+    let params = region.config_params(MINIMUM_ROWS);
+    assert_eq!(params.k, 10);
+    assert_eq!(params.num_advice_per_phase, vec![1]);
+    assert_eq!(params.num_fixed, 1);
+
+    let k = params.k as u32;
+    let circuit = SingleColumnCircuit { region, params };
+    MockProver::run(k, &circuit, vec![]).unwrap().assert_satisfied();
+    circuit.region.core.copy_manager.lock().unwrap().clear();
+}
+
+#[test]
+fn test_single_column_min_k() {
+    let region = mul_chain(0);
+    assert_eq!(region.min_k(0), 0);
+    assert_eq!(region.min_k(MINIMUM_ROWS), 4);
+    assert_eq!(mul_chain(126).min_k(MINIMUM_ROWS), 10);
+    assert_eq!(mul_chain(127).min_k(MINIMUM_ROWS), 11);
+}
+
+#[test]
+#[should_panic(expected = "do not fit in a single column")]
+fn test_single_column_region_too_small() {
+    let region = mul_chain(100);
+    let mut params = region.config_params(MINIMUM_ROWS);
+    params.k -= 1;
+    let k = params.k as u32;
+    let circuit = SingleColumnCircuit { region, params };
+    MockProver::run(k, &circuit, vec![]).unwrap();
+}
@@ -0,0 +1,163 @@
+#![allow(non_snake_case)]
+
+use super::pairing::PairingChip;
+use super::{Fp12Chip, Fp2Chip, FpChip};
+use crate::ecc::{multi_scalar_multiply, EccChip};
+use crate::fields::FieldChip;
+use crate::halo2_proofs::halo2curves::bn256::{Fq12, G1Affine, G2Affine};
+use halo2_base::gates::GateInstructions;
+use halo2_base::utils::{modulus, BigPrimeField};
+use halo2_base::{AssignedValue, Context, QuantumCell::Existing};
+
+const WINDOW_BITS: usize = 4;
+
+/// Groth16 verifying key over BN254.
+#[derive(Clone, Debug)]
+pub struct Groth16VerifyingKey {
+    pub alpha_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub gamma_g2: G2Affine,
+    pub delta_g2: G2Affine,
+    /// `ic[0]` is the constant term and `ic[i + 1]` is the term of the `i`-th public input
+    pub ic: Vec<G1Affine>,
+}
+
+/// Groth16 proof over BN254.
+#[derive(Clone, Copy, Debug)]
+pub struct Groth16Proof {
+    pub a: G1Affine,
+    pub b: G2Affine,
+    pub c: G1Affine,
+}
+
+/// Verifies a batch of Groth16 proofs for the same verifying key with a single final exponentiation.
+///
+/// A proof is valid iff `e(A, B) = e(alpha, beta) * e(L, gamma) * e(C, delta)`, where `L = ic[0] + sum_i x_i * ic[i + 1]`
+/// for public inputs `x`. The batch raises the check of proof `j` to the power `r^j` for a random challenge `r`
+/// and multiplies them together, so the right hand side collapses into three pairings with aggregated `G1` points.
+pub struct BatchVerifier<'chip, F: BigPrimeField> {
+    pub fp_chip: &'chip FpChip<'chip, F>,
+    pub pairing_chip: &'chip PairingChip<'chip, F>,
+    pub vk: Groth16VerifyingKey,
+    proofs: Vec<(Groth16Proof, Vec<AssignedValue<F>>)>,
+}
+
+impl<'chip, F: BigPrimeField> BatchVerifier<'chip, F> {
+    pub fn new(
+        fp_chip: &'chip FpChip<F>,
+        pairing_chip: &'chip PairingChip<F>,
+        vk: Groth16VerifyingKey,
+    ) -> Self {
+        Self { fp_chip, pairing_chip, vk, proofs: vec![] }
+    }
+
+    /// Adds `proof` for `public_inputs` to the batch.
+    ///
+    /// The proof points are only loaded into the circuit by [`Self::verify_all`].
+    pub fn add_proof(&mut self, proof: &Groth16Proof, public_inputs: &[AssignedValue<F>]) {
+        assert_eq!(
+            public_inputs.len() + 1,
+            self.vk.ic.len(),
+            "number of public inputs does not match the verifying key"
+        );
+        self.proofs.push((*proof, public_inputs.to_vec()));
+    }
+
+    /// Returns 1 if all proofs in the batch verify, and 0 otherwise (except with negligible probability).
+    ///
+    /// `gamma` must be a random challenge chosen after the proofs and public inputs are fixed, e.g. from a transcript.
+    /// It is constrained to be nonzero, since `gamma = 0` would only check the first proof.
+    /// Assumes the aggregated `G1` points are not the point at infinity.
+    pub fn verify_all(&self, ctx: &mut Context<F>, gamma: AssignedValue<F>) -> AssignedValue<F> {
+        assert!(!self.proofs.is_empty(), "proofs must not be empty");
+        let gate = self.fp_chip.gate();
+        let max_bits = modulus::<F>().bits() as usize;
+
+        let g1_chip = EccChip::new(self.fp_chip);
+        let fp2_chip = Fp2Chip::<F>::new(self.fp_chip);
+        let g2_chip = EccChip::new(&fp2_chip);
+
+        gate.assert_not_zero(ctx, gamma);
+        // r_j = gamma^j
+        let mut powers = vec![ctx.load_constant(F::ONE)];
+        for _ in 1..self.proofs.len() {
+            let next = gate.mul(ctx, *powers.last().unwrap(), gamma);
+            powers.push(next);
+        }
+
+        // e(r_j * A_j, B_j) for each proof
+        let mut scaled_a = Vec::with_capacity(self.proofs.len());
+        let mut b = Vec::with_capacity(self.proofs.len());
+        let mut c = Vec::with_capacity(self.proofs.len());
+        for ((proof, _), r) in self.proofs.iter().zip(&powers) {
+            let a = g1_chip.load_private::<G1Affine>(ctx, (proof.a.x, proof.a.y));
+            let a = multi_scalar_multiply::<F, _, G1Affine>(
+                self.fp_chip,
+                ctx,
+                &[a],
+                vec![vec![*r]],
+                max_bits,
+                WINDOW_BITS,
+            );
+            scaled_a.push(a);
+            b.push(g2_chip.load_private::<G2Affine>(ctx, (proof.b.x, proof.b.y)));
+            c.push(g1_chip.load_private::<G1Affine>(ctx, (proof.c.x, proof.c.y)));
+        }
+
+        // sum_j r_j * C_j
+        let scalars = powers.iter().map(|r| vec![*r]).collect();
+        let c_sum = multi_scalar_multiply::<F, _, G1Affine>(
+            self.fp_chip,
+            ctx,
+            &c,
+            scalars,
+            max_bits,
+            WINDOW_BITS,
+        );
+
+        // sum_j r_j * L_j = (sum_j r_j) * ic[0] + sum_i (sum_j r_j * x_{j,i}) * ic[i + 1]
+        let r_sum = gate.sum(ctx, powers.clone());
+        let mut ic_scalars = vec![vec![r_sum]];
+        for i in 0..self.vk.ic.len() - 1 {
+            let inputs = self.proofs.iter().map(|(_, inputs)| Existing(inputs[i]));
+            ic_scalars.push(vec![gate.inner_product(ctx, powers.clone(), inputs)]);
+        }
+        let ic =
+            self.vk.ic.iter().map(|p| g1_chip.assign_constant_point(ctx, *p)).collect::<Vec<_>>();
+        let l_sum = multi_scalar_multiply::<F, _, G1Affine>(
+            self.fp_chip,
+            ctx,
+            &ic,
+            ic_scalars,
+            max_bits,
+            WINDOW_BITS,
+        );
+
+        // (sum_j r_j) * alpha
+        let alpha = g1_chip.assign_constant_point(ctx, self.vk.alpha_g1);
+        let alpha_sum = multi_scalar_multiply::<F, _, G1Affine>(
+            self.fp_chip,
+            ctx,
+            &[alpha],
+            vec![vec![r_sum]],
+            max_bits,
+            WINDOW_BITS,
+        );
+
+        let beta = g2_chip.assign_constant_point(ctx, self.vk.beta_g2);
+        let gamma_g2 = g2_chip.assign_constant_point(ctx, self.vk.gamma_g2);
+        let delta = g2_chip.assign_constant_point(ctx, self.vk.delta_g2);
+        let neg_alpha_sum = g1_chip.negate(ctx, alpha_sum);
+        let neg_l_sum = g1_chip.negate(ctx, l_sum);
+        let neg_c_sum = g1_chip.negate(ctx, c_sum);
+
+        let mut pairs = scaled_a.iter().zip(&b).collect::<Vec<_>>();
+        pairs.extend([(&neg_alpha_sum, &beta), (&neg_l_sum, &gamma_g2), (&neg_c_sum, &delta)]);
+        let multi_paired = self.pairing_chip.multi_miller_loop(ctx, pairs);
+
+        let fp12_chip = Fp12Chip::<F>::new(self.fp_chip);
+        let result = fp12_chip.final_exp(ctx, multi_paired);
+        let fp12_one = fp12_chip.load_constant(ctx, Fq12::one());
+        fp12_chip.is_equal(ctx, result, fp12_one)
+    }
+}
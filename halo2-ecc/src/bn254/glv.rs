@@ -0,0 +1,151 @@
+#![allow(non_snake_case)]
+
+use super::{FpChip, FpPoint};
+use crate::ecc::{multi_scalar_multiply, EcPoint, EccChip};
+use crate::ff::Field;
+use crate::fields::FieldChip;
+use crate::group::{cofactor::CofactorCurveAffine, Curve};
+use crate::halo2_proofs::halo2curves::bn256::{Fq, Fr, G1Affine};
+use halo2_base::gates::{GateInstructions, RangeInstructions};
+use halo2_base::utils::{biguint_to_fe, fe_to_biguint, modulus, BigPrimeField};
+use halo2_base::{AssignedValue, Context, QuantumCell::Constant};
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+
+const WINDOW_BITS: usize = 4;
+/// Bit bound on the absolute values of the two halves of a decomposed scalar.
+pub const GLV_HALF_BITS: usize = 128;
+
+/// Constants of the endomorphism `phi(x, y) = (beta * x, y)` of BN254 `G1`, which acts as `phi(P) = lambda * P`.
+#[derive(Clone, Debug)]
+pub struct GlvParams {
+    pub beta: Fq,
+    pub lambda: Fr,
+    /// Short basis of the lattice `{(a, b) : a + b * lambda = 0 mod r}`
+    basis: [(BigInt, BigInt); 2],
+}
+
+impl Default for GlvParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlvParams {
+    pub fn new() -> Self {
+        let beta = cube_root_of_unity::<Fq>();
+        let omega = cube_root_of_unity::<Fr>();
+        // `lambda` is the cube root of unity in `Fr` matching `beta`
+        let G = G1Affine::generator();
+        let phi_G = G1Affine { x: G.x * beta, y: G.y };
+        let lambda = if (G * omega).to_affine() == phi_G { omega } else { omega.square() };
+        debug_assert_eq!((G * lambda).to_affine(), phi_G);
+
+        let basis = short_basis(&modulus::<Fr>().into(), &fe_to_biguint(&lambda).into());
+        Self { beta, lambda, basis }
+    }
+
+    /// Returns `(k1, k2)` with `k = k1 + lambda * k2 mod r` and `|k1|, |k2| < 2^GLV_HALF_BITS`.
+    pub fn decompose(&self, k: &Fr) -> (BigInt, BigInt) {
+        let k: BigInt = fe_to_biguint(k).into();
+        let [(a1, b1), (a2, b2)] = &self.basis;
+        // round the solution of `(k, 0) = c1 * v1 + c2 * v2` to the nearest lattice point
+        let det = a1 * b2 - a2 * b1;
+        let c1 = div_round(&(&k * b2), &det);
+        let c2 = div_round(&(-&k * b1), &det);
+        let k1 = k - &c1 * a1 - &c2 * a2;
+        let k2 = -(&c1 * b1) - &c2 * b2;
+        debug_assert!(k1.bits() <= GLV_HALF_BITS as u64 && k2.bits() <= GLV_HALF_BITS as u64);
+        (k1, k2)
+    }
+}
+
+/// Returns an element of multiplicative order 3 in `F`.
+fn cube_root_of_unity<F: BigPrimeField>() -> F {
+    let exp = ((modulus::<F>() - 1u64) / 3u64).to_u64_digits();
+    (2u64..).map(|g| F::from(g).pow_vartime(&exp)).find(|w| *w != F::ONE).unwrap()
+}
+
+/// Finds a short basis of `{(a, b) : a + b * lambda = 0 mod n}` with the extended Euclidean algorithm.
+///
+/// See Algorithm 3.74 of Guide to Elliptic Curve Cryptography (Hankerson, Menezes, Vanstone).
+fn short_basis(n: &BigInt, lambda: &BigInt) -> [(BigInt, BigInt); 2] {
+    let sqrt_n = n.sqrt();
+    // invariant: r_i = s_i * n + t_i * lambda
+    let (mut r0, mut r1) = (n.clone(), lambda.clone());
+    let (mut t0, mut t1) = (BigInt::zero(), BigInt::one());
+    while r1 >= sqrt_n {
+        let q = &r0 / &r1;
+        let r2 = &r0 - &q * &r1;
+        let t2 = &t0 - &q * &t1;
+        (r0, r1, t0, t1) = (r1, r2, t1, t2);
+    }
+    let q = &r0 / &r1;
+    let r2 = &r0 - &q * &r1;
+    let t2 = &t0 - &q * &t1;
+    let v1 = (r1, -t1);
+    let v2 = [(r0, -t0), (r2, -t2)].into_iter().min_by_key(|(a, b)| a * a + b * b).unwrap();
+    [v1, v2]
+}
+
+/// Returns `a / d` rounded to the nearest integer.
+fn div_round(a: &BigInt, d: &BigInt) -> BigInt {
+    let (a, d) = if d.is_negative() { (-a, -d) } else { (a.clone(), d.clone()) };
+    (a * 2u32 + &d).div_floor(&(&d * 2u32))
+}
+
+/// Computes `scalar * P` for a point `P` of BN254 `G1` using the GLV endomorphism.
+///
+/// `scalar` is split as `k1 + lambda * k2` with `|k1|, |k2| < 2^128`, and `k1 * P + k2 * phi(P)` is computed
+/// with a simultaneous double-and-add over half as many bits as [`EccChip::scalar_mult`] uses.
+/// Since `scalar` is an element of the native field, which is the scalar field of BN254, the decomposition
+/// only needs to be constrained modulo `r`.
+///
+/// # Assumptions
+/// * `P` is on the curve and is not the point at infinity
+pub fn scalar_mul_glv(
+    chip: &EccChip<Fr, FpChip<Fr>>,
+    ctx: &mut Context<Fr>,
+    P: &EcPoint<Fr, FpPoint<Fr>>,
+    scalar: AssignedValue<Fr>,
+) -> EcPoint<Fr, FpPoint<Fr>> {
+    let params = GlvParams::new();
+    let fp_chip = chip.field_chip;
+    let range = fp_chip.range();
+    let gate = range.gate();
+
+    // witness each half as a sign bit and an absolute value
+    let (k1, k2) = params.decompose(scalar.value());
+    let [(k1_neg, k1_abs), (k2_neg, k2_abs)] = [k1, k2].map(|k| {
+        let neg = ctx.load_witness(Fr::from(k.is_negative() as u64));
+        gate.assert_bit(ctx, neg);
+        let abs = ctx.load_witness(biguint_to_fe(k.magnitude()));
+        range.range_check(ctx, abs, GLV_HALF_BITS);
+        (neg, abs)
+    });
+    // k_i = abs_i - 2 * neg_i * abs_i
+    let [k1, k2] = [(k1_neg, k1_abs), (k2_neg, k2_abs)].map(|(neg, abs)| {
+        let neg_abs = gate.mul(ctx, neg, abs);
+        gate.mul_add(ctx, Constant(-Fr::from(2)), neg_abs, abs)
+    });
+    let recomposed = gate.mul_add(ctx, k2, Constant(params.lambda), k1);
+    ctx.constrain_equal(&recomposed, &scalar);
+
+    let beta = fp_chip.load_constant(ctx, params.beta);
+    let phi_x = fp_chip.mul(ctx, &P.x, beta);
+    let phi_P = EcPoint::new(phi_x, P.y.clone());
+    // fold the signs into the points so both scalars are nonnegative
+    let [P1, P2] = [(P.clone(), k1_neg), (phi_P, k2_neg)].map(|(pt, neg)| {
+        let neg_pt = chip.negate(ctx, pt.clone());
+        chip.select(ctx, neg_pt, pt, neg)
+    });
+    multi_scalar_multiply::<Fr, FpChip<Fr>, G1Affine>(
+        fp_chip,
+        ctx,
+        &[P1, P2],
+        vec![vec![k1_abs], vec![k2_abs]],
+        GLV_HALF_BITS,
+        WINDOW_BITS,
+    )
+}
@@ -3,8 +3,11 @@ use crate::fields::vector::FieldVector;
 use crate::fields::{fp, fp12, fp2};
 use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq12, Fq2};
 
+pub mod batch_verifier;
 pub mod bls_signature;
 pub mod final_exp;
+pub mod glv;
+pub mod msm;
 pub mod pairing;
 
 pub type FpChip<'range, F> = fp::FpChip<'range, F, Fq>;
@@ -0,0 +1,66 @@
+use super::{FpChip, FpPoint};
+use crate::ecc::{pippenger::multi_exp_par, EcPoint, EccChip};
+use crate::halo2_proofs::halo2curves::bn256::G1Affine;
+use halo2_base::gates::{flex_gate::threads::SinglePhaseCoreManager, RangeChip};
+use halo2_base::utils::BigPrimeField;
+use halo2_base::{AssignedValue, Context};
+
+/// Chip for multi-scalar multiplication of BN254 `G1` points using Pippenger's bucket method.
+///
+/// The coordinates of `G1` points lie in the BN254 base field, so they are non-native field elements
+/// represented with [FpChip], while each scalar is a single native cell.
+#[derive(Clone, Debug)]
+pub struct MsmChip<'range, F: BigPrimeField> {
+    pub fp_chip: FpChip<'range, F>,
+    /// Number of points grouped together in each bucket of [multi_exp_par]
+    pub clump_factor: usize,
+}
+
+impl<'range, F: BigPrimeField> MsmChip<'range, F> {
+    pub fn new(
+        range: &'range RangeChip<F>,
+        limb_bits: usize,
+        num_limbs: usize,
+        clump_factor: usize,
+    ) -> Self {
+        assert!(clump_factor > 0, "clump_factor must be positive");
+        Self { fp_chip: FpChip::new(range, limb_bits, num_limbs), clump_factor }
+    }
+
+    pub fn ecc_chip(&self) -> EccChip<F, FpChip<'range, F>> {
+        EccChip::new(&self.fp_chip)
+    }
+
+    /// Loads `point` as a private witness and constrains it to lie on the curve, which excludes the identity point.
+    pub fn load_point(&self, ctx: &mut Context<F>, point: G1Affine) -> EcPoint<F, FpPoint<F>> {
+        self.ecc_chip().load_private::<G1Affine>(ctx, (point.x, point.y))
+    }
+
+    /// Constrains and returns `sum_i scalars[i] * points[i]`.
+    ///
+    /// The buckets are filled in parallel across the threads of `builder`, see [multi_exp_par].
+    ///
+    /// # Assumptions
+    /// * `points.len() == scalars.len()`
+    /// * every element of `scalars` has at most `scalar_bits` bits
+    /// * `points` are on the curve, e.g. loaded with [MsmChip::load_point]
+    /// * the result is not the point at infinity
+    pub fn msm(
+        &self,
+        builder: &mut SinglePhaseCoreManager<F>,
+        points: &[EcPoint<F, FpPoint<F>>],
+        scalars: &[AssignedValue<F>],
+        scalar_bits: usize,
+    ) -> EcPoint<F, FpPoint<F>> {
+        assert_eq!(points.len(), scalars.len(), "points and scalars must have the same length");
+        let scalars = scalars.iter().map(|scalar| vec![*scalar]).collect();
+        multi_exp_par::<F, _, G1Affine>(
+            &self.fp_chip,
+            builder,
+            points,
+            scalars,
+            scalar_bits,
+            self.clump_factor,
+        )
+    }
+}
@@ -0,0 +1,118 @@
+use super::*;
+use crate::bn254::batch_verifier::{BatchVerifier, Groth16Proof, Groth16VerifyingKey};
+use crate::ff::Field;
+use crate::group::cofactor::CofactorCurveAffine;
+use crate::halo2_proofs::halo2curves::bn256::G2Affine;
+use halo2_base::{gates::RangeChip, Context};
+use rand::RngCore;
+
+const NUM_PUBLIC_INPUTS: usize = 2;
+
+/// Secret scalars of a Groth16 verifying key, which allow creating proofs without a circuit.
+struct Trapdoor {
+    alpha: Fr,
+    beta: Fr,
+    gamma: Fr,
+    delta: Fr,
+    ic: Vec<Fr>,
+}
+
+fn setup(mut rng: impl RngCore) -> (Groth16VerifyingKey, Trapdoor) {
+    let mut random = || Fr::random(&mut rng);
+    let td = Trapdoor {
+        alpha: random(),
+        beta: random(),
+        gamma: random(),
+        delta: random(),
+        ic: (0..=NUM_PUBLIC_INPUTS).map(|_| random()).collect(),
+    };
+    let vk = Groth16VerifyingKey {
+        alpha_g1: (G1Affine::generator() * td.alpha).to_affine(),
+        beta_g2: (G2Affine::generator() * td.beta).to_affine(),
+        gamma_g2: (G2Affine::generator() * td.gamma).to_affine(),
+        delta_g2: (G2Affine::generator() * td.delta).to_affine(),
+        ic: td.ic.iter().map(|s| (G1Affine::generator() * s).to_affine()).collect(),
+    };
+    (vk, td)
+}
+
+/// Returns a proof satisfying `ab = alpha * beta + l * gamma + c * delta` in the exponent.
+fn prove(mut rng: impl RngCore, td: &Trapdoor, public_inputs: &[Fr]) -> Groth16Proof {
+    let (a, b) = (Fr::random(&mut rng), Fr::random(&mut rng));
+    let l = td.ic[0] + td.ic[1..].iter().zip(public_inputs).map(|(s, x)| s * x).sum::<Fr>();
+    let c = (a * b - td.alpha * td.beta - l * td.gamma) * td.delta.invert().unwrap();
+    Groth16Proof {
+        a: (G1Affine::generator() * a).to_affine(),
+        b: (G2Affine::generator() * b).to_affine(),
+        c: (G1Affine::generator() * c).to_affine(),
+    }
+}
+
+fn batch_verify_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    vk: Groth16VerifyingKey,
+    proofs: &[(Groth16Proof, Vec<Fr>)],
+    gamma: Fr,
+) -> Fr {
+    let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+    let pairing_chip = PairingChip::new(&fp_chip);
+    let mut verifier = BatchVerifier::new(&fp_chip, &pairing_chip, vk);
+    for (proof, public_inputs) in proofs {
+        let public_inputs = ctx.assign_witnesses(public_inputs.clone());
+        verifier.add_proof(proof, &public_inputs);
+    }
+    let gamma = ctx.load_witness(gamma);
+    *verifier.verify_all(ctx, gamma).value()
+}
+
+fn random_proofs(mut rng: impl RngCore, td: &Trapdoor, n: usize) -> Vec<(Groth16Proof, Vec<Fr>)> {
+    (0..n)
+        .map(|_| {
+            let public_inputs =
+                (0..NUM_PUBLIC_INPUTS).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+            (prove(&mut rng, td, &public_inputs), public_inputs)
+        })
+        .collect()
+}
+
+#[test]
+fn test_batch_verify_valid() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let (vk, td) = setup(&mut rng);
+    let proofs = random_proofs(&mut rng, &td, 3);
+    let gamma = Fr::random(&mut rng);
+    let res = base_test()
+        .k(20)
+        .lookup_bits(19)
+        .run(|ctx, range| batch_verify_test(ctx, range, vk, &proofs, gamma));
+    assert_eq!(res, Fr::one());
+}
+
+#[test]
+fn test_batch_verify_one_invalid_of_ten() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let (vk, td) = setup(&mut rng);
+    let mut proofs = random_proofs(&mut rng, &td, 10);
+    // the proof no longer matches its public inputs
+    proofs[7].1[0] += Fr::one();
+    let gamma = Fr::random(&mut rng);
+    let res = base_test()
+        .k(20)
+        .lookup_bits(19)
+        .run(|ctx, range| batch_verify_test(ctx, range, vk, &proofs, gamma));
+    assert_eq!(res, Fr::zero());
+}
+
+#[test]
+fn test_batch_verify_zero_gamma() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let (vk, td) = setup(&mut rng);
+    // a single proof is not scaled by gamma, so only the nonzero constraint on gamma fails
+    let proofs = random_proofs(&mut rng, &td, 1);
+    base_test()
+        .k(20)
+        .lookup_bits(19)
+        .expect_satisfied(false)
+        .run(|ctx, range| batch_verify_test(ctx, range, vk, &proofs, Fr::zero()));
+}
@@ -0,0 +1,63 @@
+use super::*;
+use crate::ecc::compression::{compress_ec_point, decompress_ec_point};
+use crate::ff::Field;
+use crate::fields::FieldChip;
+use crate::group::cofactor::CofactorCurveAffine;
+use crate::halo2_proofs::halo2curves::bn256::Fq;
+use halo2_base::Context;
+
+fn parity(a: &Fq) -> u64 {
+    fe_to_biguint(a).bit(0) as u64
+}
+
+fn ec_compression_test(ctx: &mut Context<Fr>, range: &RangeChip<Fr>, P: G1Affine) {
+    let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+    let chip = EccChip::new(&fp_chip);
+    let P_assigned = chip.load_private::<G1Affine>(ctx, (P.x, P.y));
+
+    let (x, y_is_odd) = compress_ec_point(&chip, ctx, &P_assigned);
+    assert_eq!(fp_chip.get_assigned_value(&x.clone().into()), P.x);
+    assert_eq!(*y_is_odd.value(), Fr::from(parity(&P.y)));
+
+    let Q = decompress_ec_point::<_, _, G1Affine>(&chip, ctx, x, y_is_odd);
+    assert_eq!(fp_chip.get_assigned_value(&Q.x.into()), P.x);
+    assert_eq!(fp_chip.get_assigned_value(&Q.y.into()), P.y);
+}
+
+#[test]
+fn test_ec_compression_roundtrip() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let P = G1Affine::random(&mut rng);
+    // exercise both signs of `y`
+    for P in [P, -P] {
+        base_test().k(16).lookup_bits(15).run(|ctx, range| ec_compression_test(ctx, range, P));
+    }
+}
+
+#[test]
+fn test_ec_decompress_generator() {
+    let G = G1Affine::generator();
+    for y in [G.y, -G.y] {
+        base_test().k(16).lookup_bits(15).run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+            let chip = EccChip::new(&fp_chip);
+            let x = fp_chip.load_private(ctx, G.x);
+            let y_is_odd = ctx.load_witness(Fr::from(parity(&y)));
+            let Q = decompress_ec_point::<_, _, G1Affine>(&chip, ctx, x, y_is_odd);
+            assert_eq!(fp_chip.get_assigned_value(&Q.y.into()), y);
+        });
+    }
+}
+
+#[test]
+fn test_ec_decompress_not_on_curve() {
+    // x = 0 gives y^2 = 3, which is not a square in Fq
+    assert!(bool::from(Fq::from(3).sqrt().is_none()));
+    base_test().k(16).lookup_bits(15).expect_satisfied(false).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let chip = EccChip::new(&fp_chip);
+        let x = fp_chip.load_private(ctx, Fq::zero());
+        let y_is_odd = ctx.load_witness(Fr::zero());
+        decompress_ec_point::<_, _, G1Affine>(&chip, ctx, x, y_is_odd);
+    });
+}
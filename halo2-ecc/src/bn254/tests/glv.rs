@@ -0,0 +1,82 @@
+use super::*;
+use crate::bn254::glv::{scalar_mul_glv, GlvParams, GLV_HALF_BITS};
+use crate::ff::{Field, PrimeField};
+use crate::fields::FieldChip;
+use crate::group::cofactor::CofactorCurveAffine;
+use halo2_base::utils::biguint_to_fe;
+use halo2_base::Context;
+use num_traits::Signed;
+use test_case::test_case;
+
+fn signed_to_fe(k: &num_bigint::BigInt) -> Fr {
+    let abs: Fr = biguint_to_fe(k.magnitude());
+    if k.is_negative() {
+        -abs
+    } else {
+        abs
+    }
+}
+
+/// Returns `scalar * P` computed with GLV and the naive scalar multiplication, and the number of cells each used.
+fn glv_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    P: G1Affine,
+    scalar: Fr,
+) -> ([G1Affine; 2], [usize; 2]) {
+    let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+    let chip = EccChip::new(&fp_chip);
+    let P_assigned = chip.load_private::<G1Affine>(ctx, (P.x, P.y));
+    let scalar = ctx.load_witness(scalar);
+
+    let start = ctx.advice_len();
+    let glv = scalar_mul_glv(&chip, ctx, &P_assigned, scalar);
+    let glv_cells = ctx.advice_len() - start;
+
+    let start = ctx.advice_len();
+    let naive =
+        chip.scalar_mult::<G1Affine>(ctx, P_assigned, vec![scalar], Fr::NUM_BITS as usize, 4);
+    let naive_cells = ctx.advice_len() - start;
+
+    let [glv, naive] = [glv, naive].map(|pt| G1Affine {
+        x: fp_chip.get_assigned_value(&pt.x.into()),
+        y: fp_chip.get_assigned_value(&pt.y.into()),
+    });
+    ([glv, naive], [glv_cells, naive_cells])
+}
+
+#[test]
+fn test_glv_decompose() {
+    let params = GlvParams::new();
+    let mut rng = StdRng::seed_from_u64(0);
+    let scalars = [Fr::zero(), Fr::one(), -Fr::one(), params.lambda];
+    for k in scalars.into_iter().chain((0..100).map(|_| Fr::random(&mut rng))) {
+        let (k1, k2) = params.decompose(&k);
+        assert!(k1.bits() <= GLV_HALF_BITS as u64 && k2.bits() <= GLV_HALF_BITS as u64);
+        assert_eq!(signed_to_fe(&k1) + params.lambda * signed_to_fe(&k2), k);
+    }
+}
+
+#[test]
+fn test_glv_endomorphism() {
+    let params = GlvParams::new();
+    let P = G1Affine::random(StdRng::seed_from_u64(0));
+    assert_eq!((P * params.lambda).to_affine(), G1Affine { x: P.x * params.beta, y: P.y });
+}
+
+#[test_case(Fr::one(); "one")]
+#[test_case(-Fr::one(); "minus one")]
+#[test_case(GlvParams::new().lambda; "lambda")]
+#[test_case(Fr::random(StdRng::seed_from_u64(1)); "random")]
+fn test_scalar_mul_glv(scalar: Fr) {
+    let P = G1Affine::random(StdRng::seed_from_u64(0));
+    let ([glv, naive], [glv_cells, naive_cells]) =
+        base_test().k(18).lookup_bits(17).run(|ctx, range| glv_test(ctx, range, P, scalar));
+    assert_eq!(glv, (P * scalar).to_affine());
+    assert_eq!(glv, naive);
+    println!(
+        "GLV scalar multiplication uses {glv_cells} advice cells vs {naive_cells} naive ({:.1}% fewer)",
+        100.0 * (1.0 - glv_cells as f64 / naive_cells as f64)
+    );
+    assert!(glv_cells < naive_cells);
+}
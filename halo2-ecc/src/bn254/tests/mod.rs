@@ -18,9 +18,12 @@ use rand_core::SeedableRng;
 use serde::{Deserialize, Serialize};
 use std::io::Write;
 
+pub mod batch_verifier;
 pub mod bls_signature;
 pub mod ec_add;
+pub mod ec_compression;
 pub mod fixed_base_msm;
+pub mod glv;
 pub mod msm;
 pub mod msm_sum_infinity;
 pub mod msm_sum_infinity_fixed_base;
@@ -5,6 +5,8 @@ use std::{
 };
 
 use super::*;
+use crate::bn254::msm::MsmChip;
+use crate::group::cofactor::CofactorCurveAffine;
 
 pub fn msm_test(
     pool: &mut SinglePhaseCoreManager<Fr>,
@@ -63,6 +65,47 @@ fn test_msm() {
     });
 }
 
+#[test]
+fn test_msm_chip() {
+    let path = "configs/bn254/msm_circuit.config";
+    let params: MSMCircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+    let (bases, scalars) = random_pairs(8, &StdRng::seed_from_u64(1));
+    let expected = bases
+        .iter()
+        .zip(scalars.iter())
+        .map(|(base, scalar)| base * scalar)
+        .reduce(|a, b| a + b)
+        .unwrap()
+        .to_affine();
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run_builder(|pool, range| {
+        let chip = MsmChip::new(range, params.limb_bits, params.num_limbs, params.window_bits);
+        let ctx = pool.main();
+        let scalars = ctx.assign_witnesses(scalars);
+        let points = bases.into_iter().map(|base| chip.load_point(ctx, base)).collect::<Vec<_>>();
+        let msm = chip.msm(pool, &points, &scalars, Fr::NUM_BITS as usize);
+        assert_eq!(msm.x.value(), fe_to_biguint(&expected.x));
+        assert_eq!(msm.y.value(), fe_to_biguint(&expected.y));
+    });
+}
+
+#[test]
+fn test_msm_chip_load_identity() {
+    let path = "configs/bn254/msm_circuit.config";
+    let params: MSMCircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).expect_satisfied(false).run(
+        |ctx, range| {
+            let chip = MsmChip::new(range, params.limb_bits, params.num_limbs, params.window_bits);
+            chip.load_point(ctx, G1Affine::identity());
+        },
+    );
+}
+
 #[test]
 fn bench_msm() -> Result<(), Box<dyn std::error::Error>> {
     let config_path = "configs/bn254/bench_msm.config";
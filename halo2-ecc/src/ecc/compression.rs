@@ -0,0 +1,60 @@
+use crate::bigint::ProperCrtUint;
+use crate::ff::Field;
+use crate::fields::{fp::FpChip, FieldChip, Selectable};
+use crate::halo2_proofs::arithmetic::CurveAffine;
+use halo2_base::{gates::GateInstructions, utils::BigPrimeField, AssignedValue, Context};
+
+use super::{EcPoint, EccChip};
+
+/// Compresses `P` into its `x` coordinate and the parity of its `y` coordinate.
+///
+/// Constrains that both coordinates are reduced modulo `p`, so the encoding is unique.
+/// Returns `(x, y_is_odd)`.
+pub fn compress_ec_point<F: BigPrimeField, CF: BigPrimeField>(
+    chip: &EccChip<F, FpChip<F, CF>>,
+    ctx: &mut Context<F>,
+    P: &EcPoint<F, ProperCrtUint<F>>,
+) -> (ProperCrtUint<F>, AssignedValue<F>) {
+    let base_chip = chip.field_chip;
+    base_chip.enforce_less_than_p(ctx, P.x.clone());
+    // `is_even` also constrains `y < p`
+    let y_is_even = base_chip.is_even(ctx, &P.y);
+    let y_is_odd = base_chip.gate().not(ctx, y_is_even);
+    (P.x.clone(), y_is_odd)
+}
+
+/// Decompresses the point on the curve `C` with coordinate `x` whose `y` coordinate has parity `y_is_odd`.
+///
+/// `y` is witnessed as a square root of `x^3 + b`, negated if its parity does not match `y_is_odd`, and the
+/// resulting point is constrained to lie on the curve. Constrains that `x` is reduced modulo `p` and `y_is_odd` is a bit.
+/// The returned `y` coordinate is reduced modulo `p`.
+///
+/// Assumes the curve has no point with `y = 0`, which holds for prime order curves such as BN254 G1.
+pub fn decompress_ec_point<F: BigPrimeField, CF: BigPrimeField, C>(
+    chip: &EccChip<F, FpChip<F, CF>>,
+    ctx: &mut Context<F>,
+    x: ProperCrtUint<F>,
+    y_is_odd: AssignedValue<F>,
+) -> EcPoint<F, ProperCrtUint<F>>
+where
+    C: CurveAffine<Base = CF>,
+{
+    let base_chip = chip.field_chip;
+    base_chip.enforce_less_than_p(ctx, x.clone());
+    base_chip.gate().assert_bit(ctx, y_is_odd);
+
+    // if `x^3 + b` is not a square, any witness fails the on curve check below
+    let x_val = base_chip.get_assigned_value(&(&x).into());
+    let y_val = (x_val.square() * x_val + C::b()).sqrt().unwrap_or(CF::ZERO);
+    let y = base_chip.load_private(ctx, y_val);
+
+    // `p` is odd, so negating a nonzero reduced `y` flips its parity
+    let y_is_even = base_chip.is_even(ctx, &y);
+    let flip = base_chip.gate().is_equal(ctx, y_is_even, y_is_odd);
+    let neg_y = base_chip.negate(ctx, y.clone());
+    let y = base_chip.select(ctx, neg_y, y, flip);
+
+    let P = EcPoint::new(x, y);
+    chip.assert_is_on_curve::<C>(ctx, &P);
+    P
+}
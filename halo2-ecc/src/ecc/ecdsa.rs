@@ -1,5 +1,11 @@
+use std::marker::PhantomData;
+
 use halo2_base::utils::BigPrimeField;
-use halo2_base::{gates::GateInstructions, utils::CurveAffineExt, AssignedValue, Context};
+use halo2_base::{
+    gates::{GateInstructions, RangeChip},
+    utils::CurveAffineExt,
+    AssignedValue, Context,
+};
 
 use crate::bigint::{big_is_equal, big_less_than, FixedOverflowInteger, ProperCrtUint};
 use crate::fields::{fp::FpChip, FieldChip};
@@ -103,3 +109,84 @@ where
     let res5 = base_chip.gate().and(ctx, res4, equal_check);
     res5
 }
+
+/// Limb decomposition and window sizes used by [EcdsaChip].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EcdsaParams {
+    /// Number of bits in each limb of a non-native field element
+    pub limb_bits: usize,
+    /// Number of limbs of a non-native field element
+    pub num_limbs: usize,
+    /// Window size for the scalar multiplication of the public key
+    pub var_window_bits: usize,
+    /// Window size for the scalar multiplication of the generator
+    pub fixed_window_bits: usize,
+}
+
+/// Chip for ECDSA signature verification on the curve `GA` with coordinate field `CF` and scalar field `SF`.
+///
+/// Owns the non-native field chips for both `CF` and `SF` and verifies with [ecdsa_verify_no_pubkey_check],
+/// so the same **WARNING** applies: only use it for curves such as secp256k1 where `p` and `n` are very close.
+#[derive(Clone, Debug)]
+pub struct EcdsaChip<'range, F: BigPrimeField, CF: BigPrimeField, SF: BigPrimeField, GA> {
+    /// Chip for the coordinate field of `GA`
+    pub fp_chip: FpChip<'range, F, CF>,
+    /// Chip for the scalar field of `GA`
+    pub fq_chip: FpChip<'range, F, SF>,
+    pub var_window_bits: usize,
+    pub fixed_window_bits: usize,
+    _marker: PhantomData<GA>,
+}
+
+impl<'range, F: BigPrimeField, CF: BigPrimeField, SF: BigPrimeField, GA>
+    EcdsaChip<'range, F, CF, SF, GA>
+where
+    GA: CurveAffineExt<Base = CF, ScalarExt = SF>,
+{
+    pub fn new(range: &'range RangeChip<F>, params: EcdsaParams) -> Self {
+        Self {
+            fp_chip: FpChip::new(range, params.limb_bits, params.num_limbs),
+            fq_chip: FpChip::new(range, params.limb_bits, params.num_limbs),
+            var_window_bits: params.var_window_bits,
+            fixed_window_bits: params.fixed_window_bits,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn ecc_chip(&self) -> EccChip<F, FpChip<'range, F, CF>> {
+        EccChip::new(&self.fp_chip)
+    }
+
+    /// Loads `pk` as a private witness and constrains it to lie on the curve, which excludes the identity point.
+    pub fn load_public_key(&self, ctx: &mut Context<F>, pk: GA) -> EcPoint<F, ProperCrtUint<F>> {
+        self.ecc_chip().load_private::<GA>(ctx, pk.into_coordinates())
+    }
+
+    /// Loads a scalar field element, such as a message hash or a signature component, as a private witness.
+    pub fn load_scalar(&self, ctx: &mut Context<F>, x: SF) -> ProperCrtUint<F> {
+        self.fq_chip.load_private(ctx, x)
+    }
+
+    /// Returns 1 if `sig = (r, s)` is a valid ECDSA signature of `msg_hash` under `pk`, otherwise 0.
+    ///
+    /// `pk` is not checked here; it should be loaded with [EcdsaChip::load_public_key] and must not be the identity point.
+    pub fn verify_ecdsa(
+        &self,
+        ctx: &mut Context<F>,
+        pk: EcPoint<F, ProperCrtUint<F>>,
+        msg_hash: ProperCrtUint<F>,
+        sig: (ProperCrtUint<F>, ProperCrtUint<F>),
+    ) -> AssignedValue<F> {
+        let (r, s) = sig;
+        ecdsa_verify_no_pubkey_check::<F, CF, SF, GA>(
+            &self.ecc_chip(),
+            ctx,
+            pk,
+            r,
+            s,
+            msg_hash,
+            self.var_window_bits,
+            self.fixed_window_bits,
+        )
+    }
+}
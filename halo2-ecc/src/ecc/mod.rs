@@ -15,6 +15,7 @@ use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use std::marker::PhantomData;
 
+pub mod compression;
 pub mod ecdsa;
 pub mod fixed_base;
 pub mod schnorr_signature;
@@ -1,4 +1,4 @@
-use crate::halo2_proofs::halo2curves::secp256k1::{Fp, Fq};
+use crate::halo2_proofs::halo2curves::secp256k1::{Fp, Fq, Secp256k1Affine};
 
 use crate::ecc;
 use crate::fields::fp;
@@ -6,6 +6,7 @@ use crate::fields::fp;
 pub type FpChip<'range, F> = fp::FpChip<'range, F, Fp>;
 pub type FqChip<'range, F> = fp::FpChip<'range, F, Fq>;
 pub type Secp256k1Chip<'chip, F> = ecc::EccChip<'chip, F, FpChip<'chip, F>>;
+pub type Secp256k1EcdsaChip<'range, F> = ecc::ecdsa::EcdsaChip<'range, F, Fp, Fq, Secp256k1Affine>;
 pub const SECP_B: u64 = 7;
 
 #[cfg(test)]
@@ -6,20 +6,26 @@ use std::{fs, io::BufRead};
 
 use super::*;
 use crate::fields::FpStrategy;
+use crate::group::cofactor::CofactorCurveAffine;
 use crate::halo2_proofs::{
     arithmetic::CurveAffine,
     halo2curves::bn256::Fr,
     halo2curves::secp256k1::{Fp, Fq, Secp256k1Affine},
 };
+use crate::secp256k1::Secp256k1EcdsaChip;
 use crate::secp256k1::{FpChip, FqChip};
 use crate::{
-    ecc::{ecdsa::ecdsa_verify_no_pubkey_check, EccChip},
+    ecc::{
+        ecdsa::{ecdsa_verify_no_pubkey_check, EcdsaParams},
+        EccChip,
+    },
     fields::FieldChip,
 };
 use halo2_base::gates::RangeChip;
 use halo2_base::utils::{biguint_to_fe, fe_to_biguint, modulus, BigPrimeField};
 use halo2_base::Context;
 use serde::{Deserialize, Serialize};
+use test_case::test_case;
 use test_log::test;
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -100,6 +106,68 @@ fn test_secp256k1_ecdsa() {
     run_test(input);
 }
 
+pub fn ecdsa_chip_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    params: CircuitParams,
+    input: ECDSAInput,
+) -> F {
+    let chip = Secp256k1EcdsaChip::<F>::new(
+        range,
+        EcdsaParams {
+            limb_bits: params.limb_bits,
+            num_limbs: params.num_limbs,
+            var_window_bits: 4,
+            fixed_window_bits: 4,
+        },
+    );
+    let pk = chip.load_public_key(ctx, input.pk);
+    let [m, r, s] = [input.msghash, input.r, input.s].map(|x| chip.load_scalar(ctx, x));
+    *chip.verify_ecdsa(ctx, pk, m, (r, s)).value()
+}
+
+#[test_case(false => Fr::ONE ; "EcdsaChip::verify_ecdsa(): valid signature")]
+#[test_case(true => Fr::ZERO ; "EcdsaChip::verify_ecdsa(): wrong message")]
+fn test_secp256k1_ecdsa_chip(tamper: bool) -> Fr {
+    let path = "configs/secp256k1/ecdsa_circuit.config";
+    let params: CircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut input = random_ecdsa_input(&mut rng);
+    if tamper {
+        input.msghash += Fq::ONE;
+    }
+    base_test()
+        .k(params.degree)
+        .lookup_bits(params.lookup_bits)
+        .run(|ctx, range| ecdsa_chip_test(ctx, range, params, input))
+}
+
+#[test]
+fn test_secp256k1_ecdsa_chip_identity_public_key() {
+    let path = "configs/secp256k1/ecdsa_circuit.config";
+    let params: CircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).expect_satisfied(false).run(
+        |ctx, range| {
+            let chip = Secp256k1EcdsaChip::<Fr>::new(
+                range,
+                EcdsaParams {
+                    limb_bits: params.limb_bits,
+                    num_limbs: params.num_limbs,
+                    var_window_bits: 4,
+                    fixed_window_bits: 4,
+                },
+            );
+            chip.load_public_key(ctx, Secp256k1Affine::identity());
+        },
+    );
+}
+
 #[test]
 fn bench_secp256k1_ecdsa() -> Result<(), Box<dyn std::error::Error>> {
     let config_path = "configs/secp256k1/bench_ecdsa.config";